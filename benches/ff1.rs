@@ -1,3 +1,5 @@
+use std::convert::TryFrom;
+
 use aes::Aes256;
 use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
 
@@ -77,17 +79,76 @@ fn ff1_binary_benchmark(c: &mut Criterion_) {
     */
 }
 
+fn ff1_batch_benchmark(c: &mut Criterion_) {
+    const BATCH_SIZE: usize = 10_000;
+
+    let inputs: Vec<_> = (0..BATCH_SIZE)
+        .map(|i| {
+            fpe::ff1::DecimalNumeralString::try_from(&format!("{:010}", i * 7919)[..]).unwrap()
+        })
+        .collect();
+
+    let fpe_ff = fpe::ff1::FF1::<Aes256>::new(&[0; 32], 10).unwrap();
+    let mut batch_group = c.benchmark_group("batch_decimal_10");
+    batch_group.throughput(Throughput::Elements(BATCH_SIZE as u64));
+    batch_group.bench_function("loop", |b| {
+        b.iter(|| {
+            inputs
+                .iter()
+                .map(|x| fpe_ff.encrypt(&[], x))
+                .collect::<Result<Vec<_>, _>>()
+        })
+    });
+    batch_group.bench_function("encrypt_batch", |b| {
+        b.iter(|| fpe_ff.encrypt_batch(&[], &inputs))
+    });
+    #[cfg(feature = "rayon")]
+    batch_group.bench_function("encrypt_batch_parallel", |b| {
+        b.iter(|| fpe_ff.encrypt_batch_parallel(&[], &inputs))
+    });
+    batch_group.finish();
+}
+
+/// Compares encryption times for inputs of the same length with different values.
+///
+/// This uses wall-clock time rather than cycles per byte, so that the timings of
+/// `CtNumeralString` can be compared directly; they should not differ significantly.
+#[cfg(feature = "ct-ops")]
+fn ff1_ct_benchmark(c: &mut Criterion) {
+    use fpe::ff1::CtNumeralString;
+
+    let fpe_ff = fpe::ff1::FF1::<Aes256>::new(&[0; 32], 10).unwrap();
+    let inputs: [(&str, Vec<u16>); 3] = [
+        ("zeros", vec![0; 32]),
+        ("nines", vec![9; 32]),
+        ("mixed", (0..32).map(|i| (i * 7 + 3) % 10).collect()),
+    ];
+
+    let mut ct_group = c.benchmark_group("ct_ops");
+    for (name, input) in inputs.iter() {
+        let input = CtNumeralString::<2>::from(input.clone());
+        ct_group.bench_with_input(BenchmarkId::from_parameter(name), &input, |b, input| {
+            b.iter(|| fpe_ff.encrypt(&[], input))
+        });
+    }
+    ct_group.finish();
+}
+
+#[cfg(not(feature = "ct-ops"))]
+fn ff1_ct_benchmark(_: &mut Criterion) {}
+
 #[cfg(unix)]
 criterion_group!(
     name = benches;
     config = setup_criterion()
         .with_profiler(PProfProfiler::new(100, Output::Flamegraph(None)));
-    targets = ff1_binary_benchmark
+    targets = ff1_binary_benchmark, ff1_batch_benchmark
 );
 #[cfg(not(unix))]
 criterion_group!(
     name = benches;
     config = setup_criterion();
-    targets = ff1_binary_benchmark
+    targets = ff1_binary_benchmark, ff1_batch_benchmark
 );
-criterion_main!(benches);
+criterion_group!(ct_benches, ff1_ct_benchmark);
+criterion_main!(benches, ct_benches);
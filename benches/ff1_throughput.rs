@@ -0,0 +1,124 @@
+use std::convert::TryFrom;
+
+use aes::{Aes128, Aes256};
+use cipher::{BlockCipher, BlockEncrypt};
+use criterion::{
+    black_box, criterion_group, criterion_main, measurement::WallTime, BenchmarkGroup, BenchmarkId,
+    Criterion, Throughput,
+};
+use fpe::ff1::{
+    BinaryNumeralString, BinaryNumeralString128, DecimalNumeralString, FlexibleNumeralString, FF1,
+};
+
+/// Returns a string of `len` decimal digits.
+fn digits(len: usize) -> String {
+    (0..len)
+        .map(|i| char::from(b'0' + ((i * 7 + 3) % 10) as u8))
+        .collect()
+}
+
+fn radix_10<CIPH: BlockCipher + BlockEncrypt + Clone>(
+    group: &mut BenchmarkGroup<'_, WallTime>,
+    ff: &FF1<CIPH>,
+) {
+    for len in [6, 10, 16, 100] {
+        let input = FlexibleNumeralString::from_decimal_str(&digits(len)).unwrap();
+        group.throughput(Throughput::Elements(len as u64));
+        group.bench_with_input(BenchmarkId::from_parameter(len), &input, |b, input| {
+            b.iter(|| black_box(ff.encrypt(black_box(&[]), black_box(input))))
+        });
+    }
+}
+
+fn cipher_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("radix_10/aes128");
+    radix_10(&mut group, &FF1::<Aes128>::new(&[0; 16], 10).unwrap());
+    group.finish();
+
+    let mut group = c.benchmark_group("radix_10/aes256");
+    radix_10(&mut group, &FF1::<Aes256>::new(&[0; 32], 10).unwrap());
+    group.finish();
+}
+
+fn batch_benchmark(c: &mut Criterion) {
+    const BATCH_SIZE: usize = 1000;
+
+    let ff = FF1::<Aes256>::new(&[0; 32], 10).unwrap();
+    let inputs: Vec<_> = (0..BATCH_SIZE)
+        .map(|i| FlexibleNumeralString::from_decimal_str(&format!("{:010}", i * 7919)).unwrap())
+        .collect();
+
+    let mut group = c.benchmark_group("batch_1000x10");
+    group.throughput(Throughput::Elements(BATCH_SIZE as u64));
+    // Recomputes the PRF over the header and tweak for every input.
+    group.bench_function("encrypt", |b| {
+        b.iter(|| {
+            black_box(&inputs)
+                .iter()
+                .map(|x| ff.encrypt(&[], x))
+                .collect::<Result<Vec<_>, _>>()
+        })
+    });
+    // Shares the PRF over the header and tweak between inputs of the same length.
+    group.bench_function("encrypt_batch", |b| {
+        b.iter(|| black_box(ff.encrypt_batch(&[], black_box(&inputs))))
+    });
+    group.finish();
+}
+
+fn binary_benchmark(c: &mut Criterion) {
+    let ff = FF1::<Aes256>::new(&[0; 32], 2).unwrap();
+    let mut group = c.benchmark_group("binary");
+    for bits in [20, 64, 128] {
+        group.throughput(Throughput::Elements(bits as u64));
+        // `BinaryNumeralString` holds whole bytes, so cannot represent 20 bits.
+        if bits % 8 == 0 {
+            let input = BinaryNumeralString::from_bytes_le(&vec![0xa5; bits / 8]);
+            group.bench_with_input(
+                BenchmarkId::new("BinaryNumeralString", bits),
+                &input,
+                |b, input| b.iter(|| black_box(ff.encrypt(&[], black_box(input)))),
+            );
+        }
+        let input = BinaryNumeralString128::new(u128::MAX >> (128 - bits), bits).unwrap();
+        group.bench_with_input(
+            BenchmarkId::new("BinaryNumeralString128", bits),
+            &input,
+            |b, input| b.iter(|| black_box(ff.encrypt(&[], black_box(input)))),
+        );
+    }
+    group.finish();
+}
+
+fn decimal_benchmark(c: &mut Criterion) {
+    let ff = FF1::<Aes256>::new(&[0; 32], 10).unwrap();
+    let mut group = c.benchmark_group("decimal");
+    for len in [6, 10, 16, 100] {
+        let s = digits(len);
+        group.throughput(Throughput::Elements(len as u64));
+
+        let input = FlexibleNumeralString::from_decimal_str(&s).unwrap();
+        group.bench_with_input(
+            BenchmarkId::new("FlexibleNumeralString", len),
+            &input,
+            |b, input| b.iter(|| black_box(ff.encrypt(&[], black_box(input)))),
+        );
+
+        let input = DecimalNumeralString::try_from(&s[..]).unwrap();
+        group.bench_with_input(
+            BenchmarkId::new("DecimalNumeralString", len),
+            &input,
+            |b, input| b.iter(|| black_box(ff.encrypt(&[], black_box(input)))),
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(
+    benches,
+    cipher_benchmark,
+    batch_benchmark,
+    binary_benchmark,
+    decimal_benchmark
+);
+criterion_main!(benches);
@@ -0,0 +1,3 @@
+//! Bundles the `fpe` JavaScript bindings into a WebAssembly module.
+
+pub use fpe::wasm::FF1Wasm;
@@ -0,0 +1,356 @@
+//! Procedural macros for the [`fpe`](https://docs.rs/fpe) crate.
+//!
+//! These are re-exported by `fpe` under the `macros` feature flag, and should be used
+//! from there; the generated code refers to items in `fpe`.
+
+use proc_macro::{Delimiter, Group, Literal, Span, TokenStream, TokenTree};
+
+/// Builds a token from a format string, encrypting each `{name:length:kind}` field.
+///
+/// See the documentation of `fpe::fpe_token` for details.
+#[proc_macro]
+pub fn fpe_token(input: TokenStream) -> TokenStream {
+    match expand(input) {
+        Ok(tokens) => tokens,
+        Err((span, msg)) => compile_error(span, &msg),
+    }
+}
+
+type Error = (Span, String);
+
+/// A field kind, which determines the radix of the field.
+enum Kind {
+    Decimal,
+    Hex,
+}
+
+/// A component of the format string.
+enum Piece {
+    /// Literal text, which is copied to the token.
+    Literal(String),
+    /// A field to encrypt, read from the variable `name`.
+    Field {
+        name: String,
+        len: usize,
+        kind: Kind,
+    },
+}
+
+fn expand(input: TokenStream) -> Result<TokenStream, Error> {
+    let args = split_args(input);
+    let (ff, tweak, format) = match <[_; 3]>::try_from(args) {
+        Ok([ff, tweak, format]) if !ff.is_empty() && !tweak.is_empty() => (ff, tweak, format),
+        _ => {
+            return Err((
+                Span::call_site(),
+                "expected `fpe_token!(ff1, tweak, \"format string\")`".into(),
+            ))
+        }
+    };
+
+    let literal = string_literal(&format)?;
+    let pieces = parse_format(&parse_string(&literal)?).map_err(|msg| (literal.span(), msg))?;
+
+    // ::fpe::macro_support::TokenBuilder::new(&(ff), AsRef::<[u8]>::as_ref(&(tweak)))
+    //     .literal("...")
+    //     .field(AsRef::<str>::as_ref(&name), len, FieldKind::Kind)
+    //     .finish()
+    let mut out = tokens("::fpe::macro_support::TokenBuilder::new");
+    let mut tweak_ref = tokens("&");
+    tweak_ref.extend([parens(tweak)]);
+    let mut new_args = tokens("&");
+    new_args.extend([parens(ff)]);
+    new_args.extend(tokens(", ::core::convert::AsRef::<[u8]>::as_ref"));
+    new_args.extend([parens(tweak_ref.into_iter().collect())]);
+    out.extend([TokenTree::Group(Group::new(
+        Delimiter::Parenthesis,
+        new_args,
+    ))]);
+
+    for piece in pieces {
+        let call = match piece {
+            Piece::Literal(text) => format!(".literal({})", Literal::string(&text)),
+            Piece::Field { name, len, kind } => format!(
+                ".field(::core::convert::AsRef::<str>::as_ref(&{}), {}, \
+                 ::fpe::macro_support::FieldKind::{})",
+                name,
+                len,
+                match kind {
+                    Kind::Decimal => "Decimal",
+                    Kind::Hex => "Hex",
+                },
+            ),
+        };
+        out.extend(tokens(&call));
+    }
+    out.extend(tokens(".finish()"));
+    Ok(out)
+}
+
+/// Splits the macro input at top-level commas, ignoring a trailing comma.
+fn split_args(input: TokenStream) -> Vec<Vec<TokenTree>> {
+    let mut args = vec![vec![]];
+    for tt in input {
+        match tt {
+            TokenTree::Punct(p) if p.as_char() == ',' => args.push(vec![]),
+            tt => args.last_mut().unwrap().push(tt),
+        }
+    }
+    if args.len() > 1 && args.last().unwrap().is_empty() {
+        args.pop();
+    }
+    args
+}
+
+/// Returns the string literal that makes up `arg`.
+fn string_literal(arg: &[TokenTree]) -> Result<Literal, Error> {
+    match arg {
+        [TokenTree::Literal(lit)] => Ok(lit.clone()),
+        // Literals passed through `macro_rules!` fragments are wrapped in a
+        // delimiter-less group.
+        [TokenTree::Group(g)] if g.delimiter() == Delimiter::None => {
+            string_literal(&g.stream().into_iter().collect::<Vec<_>>())
+        }
+        _ => Err((
+            arg.first().map_or_else(Span::call_site, |tt| tt.span()),
+            "expected a format string literal".into(),
+        )),
+    }
+}
+
+/// Returns the value of a string literal.
+fn parse_string(lit: &Literal) -> Result<String, Error> {
+    let repr = lit.to_string();
+    let err = |msg: &str| (lit.span(), msg.to_string());
+
+    if let Some(raw) = repr.strip_prefix('r') {
+        let hashes = raw.len() - raw.trim_start_matches('#').len();
+        let delim = "#".repeat(hashes);
+        return raw
+            .strip_prefix(&delim)
+            .and_then(|s| s.strip_prefix('"'))
+            .and_then(|s| s.strip_suffix(&delim))
+            .and_then(|s| s.strip_suffix('"'))
+            .map(String::from)
+            .ok_or_else(|| err("expected a format string literal"));
+    }
+
+    let body = repr
+        .strip_prefix('"')
+        .and_then(|s| s.strip_suffix('"'))
+        .ok_or_else(|| err("expected a format string literal"))?;
+    let mut value = String::new();
+    let mut chars = body.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            value.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => value.push('\n'),
+            Some('r') => value.push('\r'),
+            Some('t') => value.push('\t'),
+            Some('0') => value.push('\0'),
+            Some(c @ ('\\' | '\'' | '"')) => value.push(c),
+            Some('x') => {
+                let hex: String = chars.by_ref().take(2).collect();
+                let byte = u8::from_str_radix(&hex, 16).map_err(|_| err("invalid escape"))?;
+                value.push(char::from(byte));
+            }
+            Some('u') => {
+                let hex: String = chars
+                    .by_ref()
+                    .skip(1)
+                    .take_while(|&c| c != '}')
+                    .filter(|&c| c != '_')
+                    .collect();
+                let c = u32::from_str_radix(&hex, 16)
+                    .ok()
+                    .and_then(char::from_u32)
+                    .ok_or_else(|| err("invalid escape"))?;
+                value.push(c);
+            }
+            // A line continuation skips the newline and leading whitespace.
+            Some('\n') => {
+                while chars.peek().map_or(false, |c| c.is_whitespace()) {
+                    chars.next();
+                }
+            }
+            _ => return Err(err("invalid escape")),
+        }
+    }
+    Ok(value)
+}
+
+/// Parses a format string into literal text and fields.
+fn parse_format(s: &str) -> Result<Vec<Piece>, String> {
+    let mut pieces = vec![];
+    let mut text = String::new();
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                text.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                text.push('}');
+            }
+            '{' => {
+                let mut spec = String::new();
+                loop {
+                    match chars.next() {
+                        Some('}') => break,
+                        Some(c) => spec.push(c),
+                        None => return Err("unclosed `{` in format string".into()),
+                    }
+                }
+                if !text.is_empty() {
+                    pieces.push(Piece::Literal(std::mem::take(&mut text)));
+                }
+                pieces.push(parse_field(&spec)?);
+            }
+            '}' => return Err("unmatched `}` in format string; use `}}` for a literal `}`".into()),
+            c => text.push(c),
+        }
+    }
+    if !text.is_empty() {
+        pieces.push(Piece::Literal(text));
+    }
+    Ok(pieces)
+}
+
+/// Parses the `name:length:kind` specification of a field.
+fn parse_field(spec: &str) -> Result<Piece, String> {
+    let parts: Vec<_> = spec.split(':').collect();
+    let (name, len, kind) = match parts[..] {
+        [name, len, kind] => (name, len, kind),
+        _ => {
+            return Err(format!(
+                "invalid field `{{{}}}`; expected `{{name:length:kind}}`",
+                spec
+            ))
+        }
+    };
+
+    let mut name_chars = name.chars();
+    let valid_name = name_chars
+        .next()
+        .map_or(false, |c| c.is_alphabetic() || c == '_')
+        && name_chars.all(|c| c.is_alphanumeric() || c == '_')
+        && name != "_";
+    if !valid_name {
+        return Err(format!(
+            "invalid field name `{}`; expected an identifier",
+            name
+        ));
+    }
+
+    let len = match len.parse::<usize>() {
+        Ok(len) if len > 0 => len,
+        _ => {
+            return Err(format!(
+                "invalid length `{}` for field `{}`; expected a positive integer",
+                len, name
+            ))
+        }
+    };
+
+    let kind = match kind {
+        "decimal" => Kind::Decimal,
+        "hex" => Kind::Hex,
+        _ => {
+            return Err(format!(
+                "unknown kind `{}` for field `{}`; expected `decimal` or `hex`",
+                kind, name
+            ))
+        }
+    };
+
+    Ok(Piece::Field {
+        name: name.into(),
+        len,
+        kind,
+    })
+}
+
+fn tokens(s: &str) -> TokenStream {
+    s.parse().expect("generated code is valid")
+}
+
+fn parens(tokens: Vec<TokenTree>) -> TokenTree {
+    TokenTree::Group(Group::new(
+        Delimiter::Parenthesis,
+        tokens.into_iter().collect(),
+    ))
+}
+
+/// Returns `compile_error!(msg)`, spanned to `span` so that the error points at the
+/// offending input.
+fn compile_error(span: Span, msg: &str) -> TokenStream {
+    let mut lit = Literal::string(msg);
+    lit.set_span(span);
+    let mut out: TokenStream = tokens("::core::compile_error!")
+        .into_iter()
+        .map(|mut tt| {
+            tt.set_span(span);
+            tt
+        })
+        .collect();
+    let mut group = Group::new(Delimiter::Parenthesis, TokenTree::Literal(lit).into());
+    group.set_span(span);
+    out.extend([TokenTree::Group(group)]);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_format, Kind, Piece};
+
+    #[test]
+    fn format_strings() {
+        let pieces = parse_format("a{{-{ssn:9:decimal}}}{id:32:hex}").unwrap();
+        assert!(matches!(
+            &pieces[..],
+            [
+                Piece::Literal(a),
+                Piece::Field { name: ssn, len: 9, kind: Kind::Decimal },
+                Piece::Literal(b),
+                Piece::Field { name: id, len: 32, kind: Kind::Hex },
+            ] if a == "a{-" && ssn == "ssn" && b == "}" && id == "id"
+        ));
+        assert!(parse_format("").unwrap().is_empty());
+
+        for (format, err) in [
+            ("{", "unclosed `{` in format string"),
+            (
+                "}",
+                "unmatched `}` in format string; use `}}` for a literal `}`",
+            ),
+            ("{a}", "invalid field `{a}`; expected `{name:length:kind}`"),
+            (
+                "{a:1:hex:2}",
+                "invalid field `{a:1:hex:2}`; expected `{name:length:kind}`",
+            ),
+            (
+                "{_:1:hex}",
+                "invalid field name `_`; expected an identifier",
+            ),
+            (
+                "{a-b:1:hex}",
+                "invalid field name `a-b`; expected an identifier",
+            ),
+            (
+                "{a:x:hex}",
+                "invalid length `x` for field `a`; expected a positive integer",
+            ),
+            (
+                "{a:1:oct}",
+                "unknown kind `oct` for field `a`; expected `decimal` or `hex`",
+            ),
+        ] {
+            assert_eq!(parse_format(format).err().as_deref(), Some(err));
+        }
+    }
+}
@@ -0,0 +1,20 @@
+#![no_main]
+
+use fpe::ff1::{FlexibleNumeralString, Operations};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: (Vec<u16>, Vec<u8>, u32, usize)| {
+    let (mut numerals, other, radix, m) = input;
+    let radix = radix % ((1 << 16) - 1) + 2;
+    numerals.truncate(m);
+    for n in &mut numerals {
+        *n = (u32::from(*n) % radix) as u16;
+    }
+    let m = numerals.len();
+
+    let x = FlexibleNumeralString::from(numerals.clone());
+    let y = x
+        .add_mod_exp(other.iter().copied(), radix, m)
+        .sub_mod_exp(other.iter().copied(), radix, m);
+    assert_eq!(Vec::from(y), numerals);
+});
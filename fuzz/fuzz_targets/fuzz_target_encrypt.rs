@@ -0,0 +1,22 @@
+#![no_main]
+
+use aes::Aes128;
+use fpe::ff1::{FlexibleNumeralString, FF1};
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: (Vec<u8>, u32, Vec<u8>, Vec<u16>)| {
+    let (key, radix, tweak, numerals) = input;
+    if key.len() != 16 {
+        return;
+    }
+    let ff = match FF1::<Aes128>::new(&key, radix) {
+        Ok(ff) => ff,
+        Err(_) => return,
+    };
+
+    let pt = FlexibleNumeralString::from(numerals.clone());
+    if let Ok(ct) = ff.encrypt(&tweak, &pt) {
+        let decrypted = ff.decrypt(&tweak, &ct).expect("ciphertext is valid");
+        assert_eq!(Vec::from(decrypted), numerals);
+    }
+});
@@ -0,0 +1,17 @@
+#![no_main]
+
+use fpe::ff1::fuzzing::radix_from_u32;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|radix: u32| {
+    let valid = (2..=1 << 16).contains(&radix);
+    match radix_from_u32(radix) {
+        Ok(min_len) => {
+            assert!(valid);
+            assert!(min_len >= 2);
+            // radix^min_len must be at least 1,000,000.
+            assert!((radix as f64).powi(min_len as i32) >= 1_000_000.0);
+        }
+        Err(_) => assert!(!valid),
+    }
+});
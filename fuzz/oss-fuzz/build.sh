@@ -0,0 +1,8 @@
+#!/bin/bash -eu
+# Builds the fuzz targets for OSS-Fuzz.
+
+cd $SRC/fpe
+cargo fuzz build -O
+for target in $(cargo fuzz list); do
+  cp fuzz/target/x86_64-unknown-linux-gnu/release/$target $OUT/
+done
@@ -0,0 +1,255 @@
+//! A command-line tool for encrypting and decrypting numeral strings with FF1.
+
+use std::{env, fmt, process};
+
+use aes::{Aes128, Aes192, Aes256};
+use cipher::{BlockCipher, BlockEncrypt, KeyInit};
+use fpe::ff1::{
+    CreditCardNumeralString, FlexibleNumeralString, HexNumeralString, LuhnMode, NumeralStringError,
+    SegmentedNumeralString, FF1,
+};
+
+const USAGE: &str = "\
+Usage: fpe [--key <hex>] [--radix <radix>] [--tweak <hex>] [--format <ssn|cc|uuid>]
+           (--encrypt | --decrypt) <numeral-string>
+
+Options:
+    --key <hex>       The AES-128, AES-192 or AES-256 key, as hex. Defaults to the
+                      value of the FPE_KEY environment variable.
+    --radix <radix>   The radix of the numeral string, between 2 and 36. Numerals
+                      are written as 0-9 followed by a-z.
+    --tweak <hex>     The tweak, as hex. Defaults to the empty tweak.
+    --format <format> Treat the numeral string as a US Social Security number (ssn),
+                      a payment card number whose BIN and Luhn check digit are
+                      preserved (cc), or a hyphenated UUID (uuid).
+    --encrypt         Encrypt the numeral string.
+    --decrypt         Decrypt the numeral string.";
+
+/// The number of leading PAN digits that `--format cc` leaves unchanged.
+const BIN_LEN: usize = 6;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Direction {
+    Encrypt,
+    Decrypt,
+}
+
+#[derive(Clone, Copy)]
+enum Format {
+    Ssn,
+    CreditCard,
+    Uuid,
+}
+
+impl Format {
+    fn radix(self) -> u32 {
+        match self {
+            Format::Ssn | Format::CreditCard => 10,
+            Format::Uuid => 16,
+        }
+    }
+}
+
+struct Args {
+    key: Vec<u8>,
+    radix: u32,
+    tweak: Vec<u8>,
+    format: Option<Format>,
+    direction: Direction,
+    input: String,
+}
+
+enum Error {
+    Usage(String),
+    Invalid(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Usage(msg) => write!(f, "{}\n\n{}", msg, USAGE),
+            Error::Invalid(msg) => f.write_str(msg),
+        }
+    }
+}
+
+impl From<NumeralStringError> for Error {
+    fn from(e: NumeralStringError) -> Self {
+        Error::Invalid(e.to_string())
+    }
+}
+
+fn parse_hex(name: &str, s: &str) -> Result<Vec<u8>, Error> {
+    let invalid = || Error::Invalid(format!("{} is not a valid hex string", name));
+    if s.len() % 2 != 0 {
+        return Err(invalid());
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| {
+            s.get(i..i + 2)
+                .and_then(|b| u8::from_str_radix(b, 16).ok())
+                .ok_or_else(invalid)
+        })
+        .collect()
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Args, Error> {
+    let (mut key, mut radix, mut tweak, mut format, mut direction, mut input) =
+        (None, None, Vec::new(), None, None, None);
+
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| Error::Usage(format!("{} requires a value", arg)))
+        };
+        match arg.as_str() {
+            "--key" => key = Some(parse_hex("The key", &value()?)?),
+            "--radix" => {
+                let v = value()?;
+                radix = Some(
+                    v.parse()
+                        .map_err(|_| Error::Usage(format!("Invalid radix {}", v)))?,
+                );
+            }
+            "--tweak" => tweak = parse_hex("The tweak", &value()?)?,
+            "--format" => {
+                format = Some(match value()?.as_str() {
+                    "ssn" => Format::Ssn,
+                    "cc" => Format::CreditCard,
+                    "uuid" => Format::Uuid,
+                    f => return Err(Error::Usage(format!("Unknown format {}", f))),
+                })
+            }
+            "--encrypt" => direction = Some(Direction::Encrypt),
+            "--decrypt" => direction = Some(Direction::Decrypt),
+            _ if arg.starts_with("--") => {
+                return Err(Error::Usage(format!("Unknown option {}", arg)))
+            }
+            _ if input.is_none() => input = Some(arg),
+            _ => return Err(Error::Usage(format!("Unexpected argument {}", arg))),
+        }
+    }
+
+    let key = match key {
+        Some(key) => key,
+        None => match env::var("FPE_KEY") {
+            Ok(key) => parse_hex("FPE_KEY", &key)?,
+            Err(_) => return Err(Error::Usage("No key given".into())),
+        },
+    };
+    let radix = match (radix, format) {
+        (Some(radix), Some(format)) if radix != format.radix() => {
+            return Err(Error::Usage(format!(
+                "The format requires radix {}",
+                format.radix()
+            )))
+        }
+        (Some(radix), _) => radix,
+        (None, Some(format)) => format.radix(),
+        (None, None) => return Err(Error::Usage("No radix given".into())),
+    };
+    if radix > 36 {
+        return Err(Error::Usage("The radix must be at most 36".into()));
+    }
+
+    Ok(Args {
+        key,
+        radix,
+        tweak,
+        format,
+        direction: direction
+            .ok_or_else(|| Error::Usage("One of --encrypt or --decrypt is required".into()))?,
+        input: input.ok_or_else(|| Error::Usage("No numeral string given".into()))?,
+    })
+}
+
+fn run<CIPH: BlockCipher + BlockEncrypt + Clone + KeyInit>(args: &Args) -> Result<String, Error> {
+    let ff = FF1::<CIPH>::new(&args.key, args.radix).map_err(|e| Error::Invalid(e.to_string()))?;
+    let (tweak, encrypt) = (&args.tweak[..], args.direction == Direction::Encrypt);
+
+    match args.format {
+        None => {
+            let x = args
+                .input
+                .chars()
+                .map(|c| c.to_digit(args.radix).map(|d| d as u16))
+                .collect::<Option<Vec<_>>>()
+                .ok_or(NumeralStringError::InvalidForRadix(args.radix))?;
+            let x = FlexibleNumeralString::from(x);
+            let y = if encrypt {
+                ff.encrypt(tweak, &x)
+            } else {
+                ff.decrypt(tweak, &x)
+            }?;
+            Ok(Vec::from(y)
+                .into_iter()
+                .map(|d| char::from_digit(u32::from(d), args.radix).unwrap())
+                .collect())
+        }
+        Some(Format::Ssn) => {
+            let x = SegmentedNumeralString::from_format("###-##-####", &args.input)
+                .map_err(|e| Error::Invalid(e.to_string()))?;
+            let y = if encrypt {
+                ff.encrypt_segmented(tweak, &x)
+            } else {
+                ff.decrypt_segmented(tweak, &x)
+            }?;
+            Ok(y.to_string())
+        }
+        Some(Format::CreditCard) => {
+            let x: CreditCardNumeralString = args.input.parse()?;
+            let y = if encrypt {
+                ff.encrypt_pan(tweak, &x, BIN_LEN, LuhnMode::Preserve)
+            } else {
+                ff.decrypt_pan(tweak, &x, BIN_LEN, LuhnMode::Preserve)
+            }?;
+            Ok(y.to_string())
+        }
+        Some(Format::Uuid) => {
+            let invalid = || Error::Invalid(format!("{} is not a valid UUID", args.input));
+            let groups: Vec<_> = args.input.split('-').collect();
+            if groups.iter().map(|g| g.len()).collect::<Vec<_>>() != [8, 4, 4, 4, 12] {
+                return Err(invalid());
+            }
+            let x = HexNumeralString::from_hex_str(&groups.concat()).map_err(|_| invalid())?;
+            let y = if encrypt {
+                ff.encrypt(tweak, &x)
+            } else {
+                ff.decrypt(tweak, &x)
+            }?
+            .to_hex_str_lower();
+            Ok(format!(
+                "{}-{}-{}-{}-{}",
+                &y[..8],
+                &y[8..12],
+                &y[12..16],
+                &y[16..20],
+                &y[20..],
+            ))
+        }
+    }
+}
+
+fn main() {
+    let result = parse_args(env::args().skip(1)).and_then(|args| match args.key.len() {
+        16 => run::<Aes128>(&args),
+        24 => run::<Aes192>(&args),
+        32 => run::<Aes256>(&args),
+        len => Err(Error::Invalid(format!(
+            "The key must be 16, 24 or 32 bytes, not {}",
+            len
+        ))),
+    });
+
+    match result {
+        Ok(output) => println!("{}", output),
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(match e {
+                Error::Usage(_) => 2,
+                Error::Invalid(_) => 1,
+            });
+        }
+    }
+}
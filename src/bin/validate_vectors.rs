@@ -0,0 +1,302 @@
+//! Validates FF1 against test vectors in the NIST ACVP JSON format.
+
+use std::{env, fmt, fs, process};
+
+use aes::{Aes128, Aes192, Aes256};
+use cipher::{BlockCipher, BlockEncrypt, KeyInit};
+use fpe::ff1::{FlexibleNumeralString, FF1};
+use serde::Deserialize;
+
+const USAGE: &str = "\
+Usage: validate-vectors --vector-file <path> [--key-size <128|192|256>]
+                        [--output <text|junit>]
+
+Options:
+    --vector-file <path>  An ACVP-AES-FF1 vector set, as JSON. Each test case must
+                          contain both pt and ct.
+    --key-size <bits>     Only run the test groups with this AES key size.
+                          Defaults to running every test group.
+    --output <format>     Print results as text (the default) or as JUnit XML.
+
+Exits with code 0 if every test case passes, and 1 otherwise.";
+
+enum Error {
+    Usage(String),
+    Invalid(String),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Usage(msg) => write!(f, "{}\n\n{}", msg, USAGE),
+            Error::Invalid(msg) => f.write_str(msg),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Output {
+    Text,
+    Junit,
+}
+
+struct Args {
+    vector_file: String,
+    key_size: Option<u32>,
+    output: Output,
+}
+
+/// An ACVP vector set, or the part of one that is needed for validation.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct VectorSet {
+    test_groups: Vec<TestGroup>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TestGroup {
+    tg_id: u64,
+    direction: Direction,
+    key_len: u32,
+    alphabet: String,
+    tests: Vec<TestCase>,
+}
+
+#[derive(Clone, Copy, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Direction {
+    Encrypt,
+    Decrypt,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TestCase {
+    tc_id: u64,
+    key: String,
+    tweak: String,
+    pt: String,
+    ct: String,
+}
+
+/// The result of running one test case.
+struct Outcome {
+    name: String,
+    failure: Option<String>,
+}
+
+fn parse_hex(name: &str, s: &str) -> Result<Vec<u8>, String> {
+    let invalid = || format!("{} is not a valid hex string", name);
+    if s.len() % 2 != 0 {
+        return Err(invalid());
+    }
+    (0..s.len())
+        .step_by(2)
+        .map(|i| {
+            s.get(i..i + 2)
+                .and_then(|b| u8::from_str_radix(b, 16).ok())
+                .ok_or_else(invalid)
+        })
+        .collect()
+}
+
+fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Args, Error> {
+    let (mut vector_file, mut key_size, mut output) = (None, None, Output::Text);
+
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .ok_or_else(|| Error::Usage(format!("{} requires a value", arg)))
+        };
+        match arg.as_str() {
+            "--vector-file" => vector_file = Some(value()?),
+            "--key-size" => {
+                key_size = Some(match value()?.as_str() {
+                    "128" => 128,
+                    "192" => 192,
+                    "256" => 256,
+                    v => return Err(Error::Usage(format!("Invalid key size {}", v))),
+                })
+            }
+            "--output" => {
+                output = match value()?.as_str() {
+                    "text" => Output::Text,
+                    "junit" => Output::Junit,
+                    v => return Err(Error::Usage(format!("Unknown output format {}", v))),
+                }
+            }
+            _ => return Err(Error::Usage(format!("Unexpected argument {}", arg))),
+        }
+    }
+
+    Ok(Args {
+        vector_file: vector_file.ok_or_else(|| Error::Usage("No vector file given".into()))?,
+        key_size,
+        output,
+    })
+}
+
+/// Parses a vector set, which ACVP servers may wrap in an array after a version
+/// object.
+fn parse_vector_set(json: &str) -> Result<VectorSet, Error> {
+    let invalid = |e: serde_json::Error| Error::Invalid(format!("Invalid vector file: {}", e));
+    let value: serde_json::Value = serde_json::from_str(json).map_err(invalid)?;
+    let value = match value {
+        serde_json::Value::Array(values) => values
+            .into_iter()
+            .find(|v| v.get("testGroups").is_some())
+            .ok_or_else(|| Error::Invalid("The vector file has no test groups".into()))?,
+        value => value,
+    };
+    serde_json::from_value(value).map_err(invalid)
+}
+
+fn run_case<CIPH: BlockCipher + BlockEncrypt + Clone + KeyInit>(
+    group: &TestGroup,
+    case: &TestCase,
+) -> Result<(), String> {
+    let alphabet: Vec<char> = group.alphabet.chars().collect();
+    let to_numerals = |name: &str, s: &str| {
+        s.chars()
+            .map(|c| {
+                alphabet
+                    .iter()
+                    .position(|&a| a == c)
+                    .map(|i| i as u16)
+                    .ok_or_else(|| {
+                        format!("{} contains {:?}, which is not in the alphabet", name, c)
+                    })
+            })
+            .collect::<Result<Vec<_>, _>>()
+            .map(FlexibleNumeralString::from)
+    };
+    let to_string = |ns: FlexibleNumeralString| -> String {
+        Vec::from(ns)
+            .into_iter()
+            .map(|n| alphabet[usize::from(n)])
+            .collect()
+    };
+
+    let key = parse_hex("The key", &case.key)?;
+    let tweak = parse_hex("The tweak", &case.tweak)?;
+    let ff = FF1::<CIPH>::new(&key, alphabet.len() as u32).map_err(|e| e.to_string())?;
+
+    let (input, expected, actual) = match group.direction {
+        Direction::Encrypt => {
+            let pt = to_numerals("The plaintext", &case.pt)?;
+            (&case.pt, &case.ct, ff.encrypt(&tweak, &pt))
+        }
+        Direction::Decrypt => {
+            let ct = to_numerals("The ciphertext", &case.ct)?;
+            (&case.ct, &case.pt, ff.decrypt(&tweak, &ct))
+        }
+    };
+    let actual = to_string(actual.map_err(|e| e.to_string())?);
+    if &actual == expected {
+        Ok(())
+    } else {
+        Err(format!(
+            "input {}: expected {}, got {}",
+            input, expected, actual
+        ))
+    }
+}
+
+fn run(args: &Args) -> Result<Vec<Outcome>, Error> {
+    let json = fs::read_to_string(&args.vector_file)
+        .map_err(|e| Error::Invalid(format!("Could not read {}: {}", args.vector_file, e)))?;
+    let vector_set = parse_vector_set(&json)?;
+
+    let mut outcomes = Vec::new();
+    for group in &vector_set.test_groups {
+        if args.key_size.map_or(false, |size| size != group.key_len) {
+            continue;
+        }
+        for case in &group.tests {
+            // The key length of each case must match its group.
+            let result = match (group.key_len, case.key.len() * 4) {
+                (128, 128) => run_case::<Aes128>(group, case),
+                (192, 192) => run_case::<Aes192>(group, case),
+                (256, 256) => run_case::<Aes256>(group, case),
+                (key_len, len) => Err(format!(
+                    "The key has {} bits, but the group has keyLen {}",
+                    len, key_len
+                )),
+            };
+            outcomes.push(Outcome {
+                name: format!("tgId {} tcId {}", group.tg_id, case.tc_id),
+                failure: result.err(),
+            });
+        }
+    }
+    Ok(outcomes)
+}
+
+fn escape_xml(s: &str) -> String {
+    s.chars()
+        .map(|c| match c {
+            '&' => "&amp;".into(),
+            '<' => "&lt;".into(),
+            '>' => "&gt;".into(),
+            '"' => "&quot;".into(),
+            '\'' => "&apos;".into(),
+            c => c.to_string(),
+        })
+        .collect()
+}
+
+fn print_outcomes(outcomes: &[Outcome], output: Output) {
+    let failures = outcomes.iter().filter(|o| o.failure.is_some()).count();
+    match output {
+        Output::Text => {
+            for outcome in outcomes {
+                match &outcome.failure {
+                    None => println!("PASS {}", outcome.name),
+                    Some(msg) => println!("FAIL {}: {}", outcome.name, msg),
+                }
+            }
+            println!("{} passed, {} failed", outcomes.len() - failures, failures);
+        }
+        Output::Junit => {
+            println!(r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+            println!(
+                r#"<testsuite name="fpe-ff1" tests="{}" failures="{}">"#,
+                outcomes.len(),
+                failures
+            );
+            for outcome in outcomes {
+                let name = escape_xml(&outcome.name);
+                match &outcome.failure {
+                    None => println!(r#"  <testcase name="{}"/>"#, name),
+                    Some(msg) => {
+                        println!(r#"  <testcase name="{}">"#, name);
+                        println!(r#"    <failure message="{}"/>"#, escape_xml(msg));
+                        println!("  </testcase>");
+                    }
+                }
+            }
+            println!("</testsuite>");
+        }
+    }
+}
+
+fn main() {
+    let result = parse_args(env::args().skip(1)).and_then(|args| Ok((run(&args)?, args.output)));
+
+    match result {
+        Ok((outcomes, output)) => {
+            print_outcomes(&outcomes, output);
+            if outcomes.iter().any(|o| o.failure.is_some()) {
+                process::exit(1);
+            }
+        }
+        Err(e) => {
+            eprintln!("{}", e);
+            process::exit(match e {
+                Error::Usage(_) => 2,
+                Error::Invalid(_) => 1,
+            });
+        }
+    }
+}
@@ -1,48 +1,280 @@
 //! A Rust implementation of the FF1 algorithm, specified in
 //! [NIST Special Publication 800-38G](http://dx.doi.org/10.6028/NIST.SP.800-38G).
 
-use core::cmp;
+use core::{cmp, fmt, ops::Range};
 
 use cipher::{
-    generic_array::GenericArray, Block, BlockCipher, BlockEncrypt, BlockEncryptMut, InnerIvInit,
-    KeyInit,
+    generic_array::GenericArray, typenum::Unsigned, Block, BlockCipher, BlockEncrypt,
+    BlockEncryptMut, InnerIvInit, Key, KeyInit,
 };
 
+#[cfg(feature = "zeroize")]
+use cipher::zeroize::{Zeroize, ZeroizeOnDrop};
+
 #[cfg(test)]
 use static_assertions::const_assert;
 
 mod error;
-pub use error::{InvalidRadix, NumeralStringError};
+pub use error::{
+    AlphabetError, AuthenticationError, BuildError, DateError, DomainSpecError, FormatError,
+    InvalidKeyError, InvalidRadix, KeyRotationError, NistViolation, NumeralStringError,
+    VerificationError,
+};
+
+mod digits;
+
+mod fixed;
+pub use fixed::{FixedBytes, FixedHalf, FixedNumeralString};
+
+mod masked;
+pub use masked::MaskedNumeralString;
+
+mod binary128;
+pub use binary128::{BinaryNumeralString128, U128Ops};
+
+mod session;
+pub use session::EncryptionContext;
+
+#[cfg(feature = "test-utils")]
+mod round_reduced;
+#[cfg(feature = "test-utils")]
+pub use self::round_reduced::FF1RoundReduced;
 
 #[cfg(feature = "alloc")]
 mod alloc;
 #[cfg(feature = "alloc")]
-pub use self::alloc::{BinaryNumeralString, FlexibleNumeralString};
+pub use self::alloc::{BinaryNumeralString, FlexibleNumeralString, NumeralStringIter};
+
+#[cfg(feature = "heapless")]
+mod heapless;
+#[cfg(feature = "heapless")]
+pub use self::heapless::HeaplessNumeralString;
+
+#[cfg(all(feature = "alloc", feature = "smallvec"))]
+mod small;
+#[cfg(all(feature = "alloc", feature = "smallvec"))]
+pub use self::small::SmallNumeralString;
+
+#[cfg(feature = "alloc")]
+mod builder;
+#[cfg(feature = "alloc")]
+pub use self::builder::FF1Builder;
+
+#[cfg(all(feature = "std", feature = "tokio"))]
+mod tokio;
+#[cfg(all(feature = "std", feature = "tokio"))]
+pub use self::tokio::SharedFF1;
+
+#[cfg(feature = "alloc")]
+mod alphabet;
+#[cfg(feature = "alloc")]
+pub use self::alphabet::{Alphabet, AlphabetNumeralString};
+
+#[cfg(feature = "alloc")]
+mod domain;
+#[cfg(feature = "alloc")]
+pub use self::domain::DomainSpec;
+
+#[cfg(feature = "alloc")]
+mod decimal;
+#[cfg(feature = "alloc")]
+pub use self::decimal::DecimalNumeralString;
+
+#[cfg(feature = "alloc")]
+mod bytes;
+#[cfg(feature = "alloc")]
+pub use self::bytes::ByteNumeralString;
+
+#[cfg(feature = "ct-ops")]
+mod ct;
+#[cfg(feature = "ct-ops")]
+pub use self::ct::CtNumeralString;
+
+#[cfg(feature = "alloc")]
+mod printable;
+#[cfg(feature = "alloc")]
+pub use self::printable::PrintableAsciiNumeralString;
+
+#[cfg(feature = "alloc")]
+mod hex;
+#[cfg(feature = "alloc")]
+pub use self::hex::HexNumeralString;
+
+#[cfg(feature = "alloc")]
+mod checksum;
+#[cfg(feature = "alloc")]
+pub use self::checksum::{ChecksumAlgo, ChecksumPreservingFF1, DammAlgo, LuhnAlgo, VerhoeffAlgo};
+
+#[cfg(feature = "alloc")]
+mod pan;
+#[cfg(feature = "alloc")]
+pub use self::pan::{CreditCardNumeralString, LuhnMode};
+
+#[cfg(feature = "alloc")]
+mod segmented;
+#[cfg(feature = "alloc")]
+pub use self::segmented::{Segment, SegmentedNumeralString};
+
+#[cfg(feature = "alloc")]
+mod date;
+#[cfg(feature = "alloc")]
+pub use self::date::{DateFormat, DateNumeralString};
+
+#[cfg(feature = "alloc")]
+mod diff;
+
+#[cfg(feature = "alloc")]
+mod encrypted;
+#[cfg(feature = "alloc")]
+pub use self::encrypted::EncryptedValue;
+
+#[cfg(feature = "alloc")]
+mod inspect;
+#[cfg(feature = "alloc")]
+pub use self::inspect::RoundState;
+
+#[cfg(feature = "alloc")]
+mod conformance;
+#[cfg(feature = "alloc")]
+pub use self::conformance::{ConformanceCheck, ConformanceReport};
+
+#[cfg(feature = "alloc")]
+mod scalar;
+
+#[cfg(feature = "alloc")]
+mod truncated;
+#[cfg(feature = "alloc")]
+pub use self::truncated::TruncatedToken;
+
+#[cfg(feature = "alloc")]
+mod prefix;
+#[cfg(feature = "alloc")]
+pub use self::prefix::PrefixPreservingFF1;
+
+#[cfg(feature = "alloc")]
+mod verified;
+#[cfg(feature = "alloc")]
+pub use self::verified::{
+    LengthValidator, LuhnValidator, PrefixValidator, RangeValidator, Validator, VerifiedDecrypt,
+};
+
+#[cfg(feature = "alloc")]
+mod rotation;
+#[cfg(feature = "alloc")]
+pub use self::rotation::{KeyRotation, KeyVersion};
+
+#[cfg(feature = "alloc")]
+mod token_set;
+#[cfg(feature = "alloc")]
+pub use self::token_set::OrderedTokenSet;
+
+#[cfg(feature = "alloc")]
+mod tweak;
+#[cfg(feature = "hkdf-sha256")]
+pub use self::tweak::HkdfTweakDeriver;
+#[cfg(feature = "alloc")]
+pub use self::tweak::{TweakBuilder, TweakContext, TweakDeriver, TweakedFF1};
+
+#[cfg(all(feature = "alloc", feature = "rand"))]
+mod rand_tweak;
+#[cfg(all(feature = "alloc", feature = "rand"))]
+pub use self::rand_tweak::RandTweak;
+
+#[cfg(feature = "authenticated")]
+mod authenticated;
+#[cfg(feature = "authenticated")]
+pub use self::authenticated::{AuthenticatedFF1, Tag};
+
+#[cfg(feature = "kdf")]
+mod kdf;
+#[cfg(feature = "kdf")]
+pub use self::kdf::KeyDerivation;
+
+#[cfg(feature = "large-tweak")]
+mod large_tweak;
+#[cfg(feature = "large-tweak")]
+pub use self::large_tweak::{LargeTweakFF1, LargeTweakHashedWarning, LargeTweakResult};
+
+#[cfg(all(feature = "alloc", feature = "serde"))]
+mod serde;
+#[cfg(all(feature = "alloc", feature = "serde"))]
+pub use self::serde::WithRadix;
+
+#[cfg(all(feature = "alloc", feature = "proptest"))]
+pub mod proptest_strategies;
+
+#[cfg(feature = "std")]
+mod ipv4;
+#[cfg(feature = "std")]
+pub use self::ipv4::IPv4NumeralString;
+
+#[cfg(feature = "std")]
+mod io;
+#[cfg(feature = "std")]
+pub use self::io::{FF1Reader, FF1Writer};
+
+#[cfg(feature = "std")]
+mod epoch;
+#[cfg(feature = "std")]
+pub use self::epoch::EpochTweak;
+
+#[cfg(feature = "std")]
+mod cache;
+#[cfg(feature = "std")]
+pub use self::cache::FF1Cache;
+
+/// Crate internals exposed to the fuzz targets in `fuzz/`.
+#[cfg(fuzzing)]
+#[doc(hidden)]
+pub mod fuzzing {
+    use super::{InvalidRadix, Radix};
+
+    /// Calls `Radix::from_u32`, returning the minimum numeral string length.
+    pub fn radix_from_u32(radix: u32) -> Result<usize, InvalidRadix> {
+        Radix::from_u32(radix).map(|radix| radix.min_len())
+    }
+}
 
 #[cfg(test)]
 mod proptests;
 
 #[cfg(test)]
-mod test_vectors;
+pub(crate) mod test_vectors;
+
+/// The minimum radix supported by FF1.
+const MIN_RADIX: u32 = 2;
+/// The maximum radix supported by FF1.
+const MAX_RADIX: u32 = 1 << 16;
 
 /// The minimum allowed numeral string length for any radix.
 const MIN_NS_LEN: u32 = 2;
 /// The maximum allowed numeral string length for any radix.
 const MAX_NS_LEN: usize = u32::MAX as usize;
+/// The maximum allowed tweak length in bytes, as the length is encoded in four bytes
+/// of `P`.
+const MAX_TWEAK_LEN: usize = u32::MAX as usize;
+/// The tweak length in bytes above which a warning is logged, as long tweaks make every
+/// operation significantly slower.
+#[cfg(feature = "logging")]
+const ADVISORY_TWEAK_LEN: usize = 1 << 16;
 
 /// The minimum allowed value of radix^minlen.
 ///
 /// Defined in [NIST SP 800-38G Revision 1](https://nvlpubs.nist.gov/nistpubs/SpecialPublications/NIST.SP.800-38Gr1-draft.pdf).
 const MIN_NS_DOMAIN_SIZE: u64 = 1_000_000;
 
+/// The block size in bytes that NIST SP 800-38G requires of the FF1 block cipher.
+#[cfg(feature = "alloc")]
+const NIST_BLOCK_SIZE: usize = 16;
+
 /// `minlen` such that `2^minlen >= MIN_NS_DOMAIN_SIZE`.
 const MIN_RADIX_2_NS_LEN: u32 = 20;
 
 #[cfg(test)]
 const_assert!((1 << MIN_RADIX_2_NS_LEN) >= MIN_NS_DOMAIN_SIZE);
 
-#[derive(Debug, PartialEq)]
-enum Radix {
+#[derive(Clone, PartialEq)]
+pub(crate) enum Radix {
     /// A radix in [2..2^16]. It uses floating-point arithmetic.
     Any { radix: u32, min_len: u32 },
     /// A radix 2^i for i in [1..16]. It does not use floating-point arithmetic.
@@ -54,9 +286,9 @@ enum Radix {
 }
 
 impl Radix {
-    fn from_u32(radix: u32) -> Result<Self, InvalidRadix> {
+    pub(crate) fn from_u32(radix: u32) -> Result<Self, InvalidRadix> {
         // radix must be in range [2..=2^16]
-        if !(2..=(1 << 16)).contains(&radix) {
+        if !(MIN_RADIX..=MAX_RADIX).contains(&radix) {
             return Err(InvalidRadix(radix));
         }
 
@@ -80,11 +312,21 @@ impl Radix {
         })
     }
 
-    fn check_ns_length(&self, ns_len: usize) -> Result<(), NumeralStringError> {
-        let min_len = match *self {
+    /// Returns the radix for an alphabet of `size` characters.
+    pub(crate) fn from_alphabet_size(size: usize) -> Result<Self, InvalidRadix> {
+        Self::from_u32(u32::try_from(size).unwrap_or(u32::MAX))
+    }
+
+    /// Returns the minimum numeral string length for this radix.
+    pub(crate) fn min_len(&self) -> usize {
+        match *self {
             Radix::Any { min_len, .. } => min_len as usize,
             Radix::PowerTwo { min_len, .. } => min_len as usize,
-        };
+        }
+    }
+
+    pub(crate) fn check_ns_length(&self, ns_len: usize) -> Result<(), NumeralStringError> {
+        let min_len = self.min_len();
         let max_len = MAX_NS_LEN;
 
         if ns_len < min_len {
@@ -98,14 +340,16 @@ impl Radix {
 
     /// Calculates b = ceil(ceil(v * log2(radix)) / 8).
     fn calculate_b(&self, v: usize) -> usize {
-        use libm::{ceil, log2};
         match *self {
-            Radix::Any { radix, .. } => ceil(v as f64 * log2(f64::from(radix)) / 8f64) as usize,
+            #[cfg(all(feature = "libm", not(feature = "no_float")))]
+            Radix::Any { radix, .. } => calculate_b_float(radix, v),
+            #[cfg(any(not(feature = "libm"), feature = "no_float"))]
+            Radix::Any { radix, .. } => calculate_b_int(radix, v),
             Radix::PowerTwo { log_radix, .. } => ((v * log_radix as usize) + 7) / 8,
         }
     }
 
-    fn to_u32(&self) -> u32 {
+    pub(crate) fn to_u32(&self) -> u32 {
         match *self {
             Radix::Any { radix, .. } => radix,
             Radix::PowerTwo { radix, .. } => radix,
@@ -113,6 +357,115 @@ impl Radix {
     }
 }
 
+/// The properties of a radix that FF1 uses, for implementing custom [`NumeralString`]s.
+///
+/// Returned by [`FF1::radix_info`].
+#[derive(Clone, PartialEq)]
+pub struct RadixInfo(Radix);
+
+impl RadixInfo {
+    /// Returns the properties of the given radix.
+    ///
+    /// Returns an error if the given radix is not in [2..2^16].
+    pub fn new(radix: u32) -> Result<Self, InvalidRadix> {
+        Radix::from_u32(radix).map(RadixInfo)
+    }
+
+    /// Returns the properties of the radix for an alphabet of `size` characters.
+    ///
+    /// Returns an error if `size` is not in [2..2^16]. Sizes that do not fit in a `u32`
+    /// are reported as `InvalidRadix(u32::MAX)`.
+    pub fn from_alphabet_size(size: usize) -> Result<Self, InvalidRadix> {
+        Radix::from_alphabet_size(size).map(RadixInfo)
+    }
+
+    /// Returns the radix.
+    pub fn value(&self) -> u32 {
+        self.0.to_u32()
+    }
+
+    /// Returns the minimum numeral string length for this radix.
+    pub fn min_len(&self) -> usize {
+        self.0.min_len()
+    }
+
+    /// Returns `true` if the radix is a power of two.
+    ///
+    /// FF1 uses only integer arithmetic for such radixes.
+    pub fn is_power_of_two(&self) -> bool {
+        matches!(self.0, Radix::PowerTwo { .. })
+    }
+
+    /// Returns the number of bytes needed to represent any numeral string of length
+    /// `v` in this radix.
+    ///
+    /// This is $b = \lceil \lceil v \cdot LOG(radix) \rceil / 8 \rceil$ in the NIST
+    /// spec, which is the `b` passed to [`Operations::to_be_bytes`].
+    pub fn calculate_b(&self, v: usize) -> usize {
+        self.0.calculate_b(v)
+    }
+
+    /// Returns an error if numeral strings of length `ns_len` cannot be encrypted with
+    /// this radix.
+    pub fn check_ns_length(&self, ns_len: usize) -> Result<(), NumeralStringError> {
+        self.0.check_ns_length(ns_len)
+    }
+}
+
+impl fmt::Debug for RadixInfo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("RadixInfo").field(&self.value()).finish()
+    }
+}
+
+/// Calculates b = ceil(ceil(v * log2(radix)) / 8) using floating-point arithmetic.
+#[cfg(any(all(feature = "libm", not(feature = "no_float")), test))]
+fn calculate_b_float(radix: u32, v: usize) -> usize {
+    use libm::{ceil, log2};
+    ceil(v as f64 * log2(f64::from(radix)) / 8f64) as usize
+}
+
+/// Calculates b = ceil(ceil(v * log2(radix)) / 8) using integer arithmetic, for a radix
+/// that is not a power of two.
+#[cfg(any(not(feature = "libm"), feature = "no_float", test))]
+fn calculate_b_int(radix: u32, v: usize) -> usize {
+    // log2(radix) is irrational, so v * log2(radix) is never an integer, and its ceiling
+    // is one more than its floor.
+    let bits = ((v as u128 * log2_fixed(radix)) >> 64) + 1;
+    ((bits + 7) / 8) as usize
+}
+
+/// Returns log2(radix) as a fixed-point number with 64 fractional bits, rounded down.
+#[cfg(any(not(feature = "libm"), feature = "no_float", test))]
+fn log2_fixed(radix: u32) -> u128 {
+    let int_part = 31 - radix.leading_zeros();
+
+    // y = radix / 2^int_part, in [1, 2), with 63 fractional bits.
+    let mut y = u128::from(radix) << (63 - int_part);
+    let mut frac_part = 0u128;
+    for _ in 0..64 {
+        // Squaring y doubles its logarithm, shifting the next fractional bit of the
+        // logarithm into the integer part.
+        y = (y * y) >> 63;
+        frac_part <<= 1;
+        if y >= 1 << 64 {
+            y >>= 1;
+            frac_part |= 1;
+        }
+    }
+
+    (u128::from(int_part) << 64) | frac_part
+}
+
+impl fmt::Debug for Radix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Radix")
+            .field("radix", &self.to_u32())
+            .field("min_len", &self.min_len())
+            .finish()
+    }
+}
+
 /// Type representing FF1 operations that can be performed on a sub-section of a
 /// [`NumeralString`].
 pub trait Operations: Sized {
@@ -135,6 +488,23 @@ pub trait Operations: Sized {
     fn sub_mod_exp(self, other: impl Iterator<Item = u8>, radix: u32, m: usize) -> Self;
 }
 
+/// [`Operations`] that store one numeral per element, and can expose them directly.
+///
+/// `BinaryNumeralString`'s operations pack eight numerals into each byte, so they do
+/// not implement this trait.
+pub trait InspectableOps: Operations {
+    /// Type used to store each numeral.
+    type Numeral: Copy + Into<u16>;
+
+    /// Returns the numerals of this numeral sub-string, in big-endian order.
+    fn to_numerals(&self) -> &[Self::Numeral];
+
+    /// Returns the numeral at index `i`, or `None` if `i` is out of bounds.
+    fn get_numeral(&self, i: usize) -> Option<u16> {
+        self.to_numerals().get(i).map(|&n| n.into())
+    }
+}
+
 /// For a given base, a finite, ordered sequence of numerals for the base.
 pub trait NumeralString: Sized {
     /// Type used for FF1 computations.
@@ -150,12 +520,113 @@ pub trait NumeralString: Sized {
 
     /// Splits this numeral string of length `n` into two sections of lengths
     /// `u = floor(n / 2)` and `v = n - u` that can be used for FF1 computations.
+    ///
+    /// The sections must be the first `u` and last `v` numerals. Any other split, such
+    /// as interleaving even and odd positions, is equivalent to permuting the numerals
+    /// before and after standard FF1, so it produces non-standard ciphertexts without
+    /// changing how errors propagate: every Feistel round depends on a whole section,
+    /// so changing any numeral of a ciphertext changes the entire decryption.
     fn split(&self) -> (Self::Ops, Self::Ops);
 
     /// Concatenates two strings used for FF1 computations into a single numeral string.
     fn concat(a: Self::Ops, b: Self::Ops) -> Self;
+
+    /// Returns a copy of this numeral string with its numerals in reverse order.
+    ///
+    /// This corresponds to $REV(X)$ in the NIST spec, and is used by FF3-1.
+    fn reversed(&self) -> Self;
+}
+
+/// A [`NumeralString`] that can be overwritten in place with the result of an FF1
+/// computation.
+pub trait NumeralStringMut: NumeralString {
+    /// Replaces the contents of this numeral string with the concatenation of `a` and
+    /// `b`, reusing its existing storage where possible.
+    ///
+    /// This must be equivalent to `*self = Self::concat(a, b)`.
+    fn replace_with(&mut self, a: Self::Ops, b: Self::Ops);
 }
 
+/// A [`NumeralString`] from which a contiguous range of numerals can be extracted and
+/// replaced.
+pub trait SliceableNumeralString: NumeralString {
+    /// Returns a numeral string containing the numerals of this one in `range`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is out of bounds.
+    fn slice(&self, range: Range<usize>) -> Self;
+
+    /// Overwrites the numerals of this numeral string in `range` with `replacement`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `range` is out of bounds, or if `replacement` does not contain
+    /// `range.len()` numerals.
+    fn splice(&mut self, range: Range<usize>, replacement: Self);
+
+    /// Splits this numeral string into its first `n` numerals and the rest.
+    ///
+    /// Unlike [`NumeralString::split`], which always splits at the midpoint for the
+    /// Feistel network, `n` can be any position.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is greater than the number of numerals.
+    fn split_at(&self, n: usize) -> (Self, Self) {
+        let len = self.numeral_count();
+        (self.slice(0..n), self.slice(n..len))
+    }
+
+    /// Splits this numeral string into `k` consecutive parts, whose lengths differ by at
+    /// most one.
+    ///
+    /// If the number of numerals is not a multiple of `k`, the earlier parts are one
+    /// numeral longer.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `k` is 0.
+    #[cfg(feature = "alloc")]
+    fn split_many(&self, k: usize) -> ::alloc::vec::Vec<Self> {
+        assert!(k > 0, "cannot split a numeral string into 0 parts");
+        let len = self.numeral_count();
+        let (short_len, long_count) = (len / k, len % k);
+        let mut start = 0;
+        (0..k)
+            .map(|i| {
+                let end = start + short_len + usize::from(i < long_count);
+                let part = self.slice(start..end);
+                start = end;
+                part
+            })
+            .collect()
+    }
+
+    /// Concatenates the given parts, in order, into a single numeral string.
+    ///
+    /// This is the inverse of [`SliceableNumeralString::split_many`], and can be used to
+    /// encrypt parts of a numeral string independently.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `parts` is empty.
+    #[cfg(feature = "alloc")]
+    fn concat_many(parts: ::alloc::vec::Vec<Self>) -> Self
+    where
+        Self: NumeralString<Ops = Self>,
+    {
+        let mut parts = parts.into_iter();
+        let first = parts
+            .next()
+            .expect("cannot concatenate an empty list of numeral strings");
+        parts.fold(first, Self::concat)
+    }
+}
+
+/// The FF1 PRF (a CBC-MAC).
+///
+/// All state is stored inline, so the PRF never allocates.
 #[derive(Clone)]
 struct Prf<CIPH: BlockCipher + BlockEncrypt> {
     state: cbc::Encryptor<CIPH>,
@@ -164,8 +635,19 @@ struct Prf<CIPH: BlockCipher + BlockEncrypt> {
     offset: usize,
 }
 
+impl<CIPH: BlockCipher + BlockEncrypt> fmt::Debug for Prf<CIPH> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // The CBC state and buffer are derived from the key, so are not printed.
+        f.debug_struct("Prf")
+            .field("offset", &self.offset)
+            .finish_non_exhaustive()
+    }
+}
+
 impl<CIPH: BlockCipher + BlockEncrypt + Clone> Prf<CIPH> {
     fn new(ciph: &CIPH) -> Self {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::BLOCK_SIZE_IS_16;
         let ciph = ciph.clone();
         Prf {
             state: cbc::Encryptor::inner_iv_init(ciph, GenericArray::from_slice(&[0; 16])),
@@ -191,21 +673,40 @@ impl<CIPH: BlockCipher + BlockEncrypt + Clone> Prf<CIPH> {
     /// Returns the current PRF output.
     ///
     /// The caller MUST ensure that the PRF has processed an integer number of blocks.
-    fn output(&self) -> &Block<CIPH> {
+    fn finalize_into_array(&self) -> [u8; 16] {
         assert_eq!(self.offset, 0);
-        &self.buf[0]
+        let mut output = [0; 16];
+        output.copy_from_slice(&self.buf[0]);
+        output
     }
 }
 
-fn generate_s<'a, CIPH: BlockEncrypt>(
-    ciph: &'a CIPH,
-    r: &'a Block<CIPH>,
+impl<CIPH: BlockCipher + BlockEncrypt> Prf<CIPH> {
+    /// Asserts at compile time that the cipher has the 128-bit block size that FF1
+    /// requires.
+    const BLOCK_SIZE_IS_16: () = assert!(
+        <CIPH::BlockSize as Unsigned>::USIZE == 16,
+        "FF1 requires a block cipher with a 128-bit block size"
+    );
+}
+
+#[cfg(feature = "zeroize")]
+impl<CIPH: BlockCipher + BlockEncrypt> Drop for Prf<CIPH> {
+    fn drop(&mut self) {
+        // The buffer holds the CBC-MAC chaining value (or partial input). The CBC state
+        // zeroizes its own copy via `cbc/zeroize`.
+        self.buf[0].as_mut_slice().zeroize();
+    }
+}
+
+fn generate_s<CIPH: BlockEncrypt>(
+    ciph: &CIPH,
+    r: [u8; 16],
     d: usize,
-) -> impl Iterator<Item = u8> + 'a {
-    r.clone()
-        .into_iter()
+) -> impl Iterator<Item = u8> + Clone + '_ {
+    IntoIterator::into_iter(r)
         .chain((1..((d + 15) / 16) as u128).flat_map(move |j| {
-            let mut block = r.clone();
+            let mut block = Block::<CIPH>::clone_from_slice(&r);
             for (b, j) in block.iter_mut().zip(j.to_be_bytes().iter()) {
                 *b ^= j;
             }
@@ -215,51 +716,870 @@ fn generate_s<'a, CIPH: BlockEncrypt>(
         .take(d)
 }
 
+/// Formats a sequence of bytes as lowercase hex, for trace logging.
+#[cfg(feature = "logging")]
+struct HexFmt<I>(I);
+
+#[cfg(feature = "logging")]
+impl<I: Iterator<Item = u8> + Clone> fmt::Display for HexFmt<I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for b in self.0.clone() {
+            write!(f, "{:02x}", b)?;
+        }
+        Ok(())
+    }
+}
+
+/// Logs the state at the start of Feistel round `i`.
+///
+/// `NS::Ops` does not expose its numerals, so A and B are logged as their big-endian
+/// values `[NUM(A, radix)]^b` and `[NUM(B, radix)]^b`.
+#[cfg(feature = "logging")]
+fn trace_round<O: Operations>(
+    i: u8,
+    x_a: &O,
+    x_b: &O,
+    radix: u32,
+    b: usize,
+    s: impl Iterator<Item = u8> + Clone,
+) {
+    if log::log_enabled!(log::Level::Trace) {
+        log::trace!(
+            "FF1 round {}: A = {}, B = {}, y = {}",
+            i,
+            HexFmt(x_a.to_be_bytes(radix, b).as_ref().iter().copied()),
+            HexFmt(x_b.to_be_bytes(radix, b).as_ref().iter().copied()),
+            HexFmt(s),
+        );
+    }
+}
+
+/// Observes the FF1 state at the end of each Feistel round.
+trait Inspector<O> {
+    /// Called after round `i`, with the new halves and the `S` bytes and `m` used in the
+    /// round.
+    fn inspect(&mut self, i: u8, x_a: &O, x_b: &O, s: impl Iterator<Item = u8> + Clone, m: usize);
+}
+
+/// The no-op inspector used by every other encryption and decryption method, which
+/// compiles to nothing.
+impl<O> Inspector<O> for () {
+    #[inline(always)]
+    fn inspect(&mut self, _: u8, _: &O, _: &O, _: impl Iterator<Item = u8> + Clone, _: usize) {}
+}
+
+/// FF1 using AES-128 as the underlying cipher.
+#[cfg(feature = "aes")]
+pub type FF1Aes128 = FF1<aes::Aes128>;
+/// FF1 using AES-192 as the underlying cipher.
+#[cfg(feature = "aes")]
+pub type FF1Aes192 = FF1<aes::Aes192>;
+/// FF1 using AES-256 as the underlying cipher.
+#[cfg(feature = "aes")]
+pub type FF1Aes256 = FF1<aes::Aes256>;
+/// FF1 using SM4 as the underlying cipher.
+///
+/// SM4 has the 128-bit block size that FF1 requires, but is not an approved cipher
+/// for NIST SP 800-38G.
+#[cfg(feature = "sm4")]
+pub type FF1Sm4 = FF1<sm4::Sm4>;
+/// FF1 using Camellia-128 as the underlying cipher.
+///
+/// Camellia has the 128-bit block size that FF1 requires, but is not an approved
+/// cipher for NIST SP 800-38G, so is not covered by ACVP validation.
+#[cfg(feature = "camellia")]
+pub type FF1Camellia128 = FF1<camellia::Camellia128>;
+/// FF1 using Camellia-256 as the underlying cipher.
+///
+/// Camellia has the 128-bit block size that FF1 requires, but is not an approved
+/// cipher for NIST SP 800-38G, so is not covered by ACVP validation.
+#[cfg(feature = "camellia")]
+pub type FF1Camellia256 = FF1<camellia::Camellia256>;
+
 /// A struct for performing FF1 encryption and decryption operations.
-pub struct FF1<CIPH: BlockCipher> {
+///
+/// `FF1<CIPH>` is `Send` and `Sync` whenever `CIPH` is, which is the case for the
+/// RustCrypto block ciphers such as `Aes256`. Encryption and decryption only need
+/// `&self`, so a single instance can be shared between threads via
+/// `Arc<FF1<Aes256>>`. It can also be cloned, which clones the cipher.
+///
+/// FF1 requires a block cipher with a 128-bit block size. Constructing an `FF1` with
+/// any other cipher is a compile-time error:
+///
+/// ```compile_fail
+/// use cipher::{
+///     consts::{U1, U8},
+///     inout::InOut,
+///     Block, BlockBackend, BlockCipher, BlockClosure, BlockEncrypt, BlockSizeUser, Key,
+///     KeyInit, KeySizeUser, ParBlocksSizeUser,
+/// };
+/// use fpe::ff1::FF1;
+///
+/// /// A cipher with a 64-bit block size.
+/// #[derive(Clone)]
+/// struct Cipher64;
+///
+/// impl BlockSizeUser for Cipher64 {
+///     type BlockSize = U8;
+/// }
+/// impl KeySizeUser for Cipher64 {
+///     type KeySize = U8;
+/// }
+/// impl KeyInit for Cipher64 {
+///     fn new(_: &Key<Self>) -> Self {
+///         Cipher64
+///     }
+/// }
+/// impl BlockCipher for Cipher64 {}
+///
+/// struct Backend;
+///
+/// impl BlockSizeUser for Backend {
+///     type BlockSize = U8;
+/// }
+/// impl ParBlocksSizeUser for Backend {
+///     type ParBlocksSize = U1;
+/// }
+/// impl BlockBackend for Backend {
+///     fn proc_block(&mut self, _: InOut<'_, '_, Block<Self>>) {}
+/// }
+/// impl BlockEncrypt for Cipher64 {
+///     fn encrypt_with_backend(&self, f: impl BlockClosure<BlockSize = U8>) {
+///         f.call(&mut Backend)
+///     }
+/// }
+///
+/// let ff = FF1::<Cipher64>::new(&[0; 8], 10);
+/// ```
+pub struct FF1<CIPH: BlockCipher + BlockEncrypt> {
     ciph: CIPH,
     radix: Radix,
     faistel_rounds: u8,
+    /// The PRF state after processing the first 7 bytes of `P`, which only depend on
+    /// the radix.
+    prf_static: Prf<CIPH>,
+}
+
+impl<CIPH: BlockCipher + BlockEncrypt + Clone> Clone for FF1<CIPH> {
+    fn clone(&self) -> Self {
+        FF1 {
+            ciph: self.ciph.clone(),
+            radix: self.radix.clone(),
+            faistel_rounds: self.faistel_rounds,
+            prf_static: self.prf_static.clone(),
+        }
+    }
+}
+
+impl<CIPH: BlockCipher + BlockEncrypt> fmt::Debug for FF1<CIPH> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FF1")
+            .field("radix", &self.radix.to_u32())
+            .field("rounds", &self.faistel_rounds)
+            .field("key", &format_args!("[REDACTED]"))
+            .finish()
+    }
+}
+
+impl<CIPH: BlockCipher + BlockEncrypt> FF1<CIPH> {
+    /// Returns the key check value, the encryption of the all-zero block, which
+    /// identifies the key without revealing it.
+    ///
+    /// The cipher does not expose its key bytes, so equality and hashing use this
+    /// instead.
+    #[cfg(feature = "subtle")]
+    fn key_check_value(&self) -> Block<CIPH> {
+        let mut block = Block::<CIPH>::default();
+        self.ciph.encrypt_block(&mut block);
+        block
+    }
+}
+
+/// Two FF1 instances are equal if they have the same radix, number of rounds and key.
+///
+/// The keys are compared in constant time, via their key check values.
+#[cfg(feature = "subtle")]
+impl<CIPH: BlockCipher + BlockEncrypt> PartialEq for FF1<CIPH> {
+    fn eq(&self, other: &Self) -> bool {
+        use subtle::ConstantTimeEq;
+
+        self.radix.to_u32() == other.radix.to_u32()
+            && self.faistel_rounds == other.faistel_rounds
+            && bool::from(
+                self.key_check_value()
+                    .as_slice()
+                    .ct_eq(other.key_check_value().as_slice()),
+            )
+    }
+}
+
+#[cfg(feature = "subtle")]
+impl<CIPH: BlockCipher + BlockEncrypt> Eq for FF1<CIPH> {}
+
+#[cfg(feature = "subtle")]
+impl<CIPH: BlockCipher + BlockEncrypt> core::hash::Hash for FF1<CIPH> {
+    fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+        self.radix.to_u32().hash(state);
+        self.faistel_rounds.hash(state);
+        self.key_check_value().as_slice().hash(state);
+    }
+}
+
+impl<CIPH: BlockCipher + BlockEncrypt + Clone + KeyInit> FF1<CIPH> {
+    /// Creates a new FF1 object for the given key and radix.
+    ///
+    /// The caller is responsible for zeroizing `key` after this call. With the
+    /// `zeroize` feature flag, `FF1::new_with_secret_key` does this automatically.
+    ///
+    /// This does not go through `FF1Builder`, which stores the key in a `Vec` and so
+    /// requires the `alloc` feature; `FF1::new` is also available in `no_std` builds
+    /// without an allocator. With `alloc`, `FF1::builder` returns an error for a key
+    /// of the wrong length instead of panicking.
+    ///
+    /// Returns an error if the given radix is not in [2..2^16].
+    pub fn new(key: &[u8], radix: u32) -> Result<Self, InvalidRadix> {
+        Self::new_with_faistel_rounds(key, radix, 10)
+    }
+    /// Creates a new FF1 object for the given key, radix and number of Feistel rounds.
+    ///
+    /// NIST SP 800-38G mandates 10 rounds, and fewer rounds are not secure. The round
+    /// count is not checked here; `FF1Builder::build` rejects fewer than 10 rounds.
+    ///
+    /// Returns an error if the given radix is not in [2..2^16].
+    pub fn new_with_faistel_rounds(
+        key: &[u8],
+        radix: u32,
+        faistel_rounds: u8,
+    ) -> Result<Self, InvalidRadix> {
+        let ciph = CIPH::new(GenericArray::from_slice(key));
+        let radix = Radix::from_u32(radix)?;
+        Ok(Self::from_parts(ciph, radix, faistel_rounds))
+    }
+
+    /// Creates a new FF1 object for the given key and radix.
+    ///
+    /// Unlike [`FF1::new`], which panics if the key has the wrong length for the cipher,
+    /// the key length is checked at compile time:
+    ///
+    /// ```compile_fail
+    /// use aes::Aes256;
+    /// use fpe::ff1::FF1;
+    ///
+    /// let ff = FF1::<Aes256>::new_from_key(&[0; 16].into(), 10);
+    /// ```
+    ///
+    /// Returns an error if the given radix is not in [2..2^16].
+    pub fn new_from_key(key: &Key<CIPH>, radix: u32) -> Result<Self, InvalidRadix> {
+        let ciph = CIPH::new(key);
+        let radix = Radix::from_u32(radix)?;
+        Ok(Self::from_parts(ciph, radix, 10))
+    }
+
+    /// Creates a new FF1 object for the given key and radix.
+    ///
+    /// This is the preferred way to create an FF1 object, as the key is zeroized when
+    /// the `SecretKey` is dropped.
+    ///
+    /// Returns an error if the given radix is not in [2..2^16].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `N` is not a valid key length for the cipher.
+    #[cfg(feature = "zeroize")]
+    pub fn new_with_secret_key<const N: usize>(
+        key: &crate::SecretKey<N>,
+        radix: u32,
+    ) -> Result<Self, InvalidRadix> {
+        Self::new(key.as_bytes(), radix)
+    }
+
+    /// Creates a new FF1 object for the given key and radix 10, for decimal data such
+    /// as card and account numbers.
+    pub fn for_decimal(key: &[u8]) -> Result<Self, InvalidRadix> {
+        Self::new(key, 10)
+    }
+
+    /// Creates a new FF1 object for the given key and radix 2.
+    pub fn for_binary(key: &[u8]) -> Result<Self, InvalidRadix> {
+        Self::new(key, 2)
+    }
+
+    /// Creates a new FF1 object for the given key and radix 16.
+    pub fn for_hex(key: &[u8]) -> Result<Self, InvalidRadix> {
+        Self::new(key, 16)
+    }
+
+    /// Creates a new FF1 object for the given key and radix 36, for numerals
+    /// `'0'..='9'` followed by `'a'..='z'`.
+    pub fn for_alphanumeric(key: &[u8]) -> Result<Self, InvalidRadix> {
+        Self::new(key, 36)
+    }
+
+    /// Creates a new FF1 object for the given key and radix 256, for numeral strings of
+    /// bytes.
+    pub fn for_bytes(key: &[u8]) -> Result<Self, InvalidRadix> {
+        Self::new(key, 256)
+    }
+
+    /// Returns a builder for an FF1 object, which validates all parameters.
+    #[cfg(feature = "alloc")]
+    pub fn builder() -> FF1Builder<CIPH> {
+        FF1Builder::new()
+    }
+}
+
+#[cfg(all(feature = "rand", feature = "aes"))]
+impl FF1<aes::Aes256> {
+    /// Creates a new FF1 object for the given radix, with a random AES-256 key
+    /// generated by `rng`.
+    ///
+    /// The key is returned alongside the FF1 object, so that the caller can create
+    /// further instances with it, for example for another radix. It is zeroized when
+    /// dropped.
+    ///
+    /// Returns an error if the given radix is not in [2..2^16].
+    pub fn new_from_rng<R: rand_core::CryptoRng + rand_core::RngCore>(
+        rng: &mut R,
+        radix: u32,
+    ) -> Result<(Self, crate::SecretKey<32>), InvalidRadix> {
+        let key = crate::SecretKey::generate(rng);
+        let ff = Self::new_with_secret_key(&key, radix)?;
+        Ok((ff, key))
+    }
+
+    /// Creates a new FF1 object for the given radix, with a random AES-256 key from
+    /// the operating system's RNG.
+    ///
+    /// The key is never exposed, so ciphertexts can only be decrypted by this object.
+    /// This is useful for pseudonymizing values within a single process.
+    ///
+    /// Returns an error if the given radix is not in [2..2^16].
+    #[cfg(feature = "os-rng")]
+    pub fn new_ephemeral(radix: u32) -> Result<Self, InvalidRadix> {
+        Self::new_from_rng(&mut rand_core::OsRng, radix).map(|(ff, _)| ff)
+    }
+}
+
+impl<CIPH: BlockCipher + BlockEncrypt + Clone> FF1<CIPH> {
+    fn from_parts(ciph: CIPH, radix: Radix, faistel_rounds: u8) -> Self {
+        let prf_static = prf_static(&ciph, &radix);
+        FF1 {
+            ciph,
+            radix,
+            faistel_rounds,
+            prf_static,
+        }
+    }
+}
+
+fn check_tweak(tweak: &[u8]) -> Result<(), NumeralStringError> {
+    #[cfg(feature = "logging")]
+    if tweak.len() > ADVISORY_TWEAK_LEN {
+        log::warn!(
+            "FF1 tweak of {} bytes exceeds the advisory limit of {} bytes",
+            tweak.len(),
+            ADVISORY_TWEAK_LEN,
+        );
+    }
+
+    if tweak.len() > MAX_TWEAK_LEN {
+        Err(NumeralStringError::TweakTooLong {
+            tweak_len: tweak.len(),
+            max_len: MAX_TWEAK_LEN,
+        })
+    } else {
+        Ok(())
+    }
+}
+
+/// Buffers the first `len` numerals of `iter`.
+#[cfg(feature = "alloc")]
+fn collect_numerals<I: IntoIterator<Item = u16>>(
+    iter: I,
+    len: usize,
+) -> Result<FlexibleNumeralString, NumeralStringError> {
+    let x: ::alloc::vec::Vec<_> = iter.into_iter().take(len).collect();
+    if x.len() < len {
+        Err(NumeralStringError::TooShort {
+            ns_len: x.len(),
+            min_len: len,
+        })
+    } else {
+        Ok(FlexibleNumeralString::from(x))
+    }
+}
+
+/// Returns the PRF state after processing `[1, 2, 1] || [radix] || [10]`, the prefix of
+/// `P` that is fixed for a given FF1 instance.
+fn prf_static<CIPH: BlockCipher + BlockEncrypt + Clone>(ciph: &CIPH, radix: &Radix) -> Prf<CIPH> {
+    let mut p = [1, 2, 1, 0, 0, 0, 10];
+    p[3..6].copy_from_slice(&radix.to_u32().to_be_bytes()[1..]);
+    let mut prf = Prf::new(ciph);
+    prf.update(&p);
+    prf
+}
+
+#[cfg(feature = "zeroize")]
+impl<CIPH: BlockCipher + BlockEncrypt + Clone + Zeroize> Zeroize for FF1<CIPH> {
+    fn zeroize(&mut self) {
+        self.ciph.zeroize();
+        // The cached PRF state holds its own copy of the cipher. Replace it with one
+        // derived from the zeroized cipher; dropping the old state erases its copy if
+        // the cipher zeroizes on drop.
+        self.prf_static = prf_static(&self.ciph, &self.radix);
+    }
 }
 
-impl<CIPH: BlockCipher + KeyInit> FF1<CIPH> {
-    /// Creates a new FF1 object for the given key and radix.
+/// Dropping an `FF1` drops its cipher and PRF state, so it zeroizes on drop if its
+/// cipher does.
+#[cfg(feature = "zeroize")]
+impl<CIPH: BlockCipher + BlockEncrypt + ZeroizeOnDrop> ZeroizeOnDrop for FF1<CIPH> {}
+
+impl<CIPH: BlockCipher + BlockEncrypt + Clone> FF1<CIPH> {
+    /// Returns the minimum number of numerals in a numeral string that can be encrypted
+    /// with this FF1 instance.
+    ///
+    /// This depends on the radix, as NIST SP 800-38G requires the domain to contain at
+    /// least one million values.
+    pub fn min_numeral_count(&self) -> usize {
+        self.radix.min_len()
+    }
+
+    /// Returns the properties of the radix of this FF1 instance.
+    pub fn radix_info(&self) -> RadixInfo {
+        RadixInfo(self.radix.clone())
+    }
+
+    /// Returns the maximum number of numerals in a numeral string that can be encrypted
+    /// with this FF1 instance.
+    pub fn max_numeral_count(&self) -> usize {
+        MAX_NS_LEN
+    }
+
+    /// Returns `true` if numeral strings of length `n` can be encrypted with this FF1
+    /// instance.
+    pub fn is_valid_length(&self, n: usize) -> bool {
+        self.radix.check_ns_length(n).is_ok()
+    }
+
+    /// Checks every precondition that NIST SP 800-38G places on encrypting `x` with
+    /// the given tweak, returning all violations rather than only the first.
+    #[cfg(feature = "alloc")]
+    pub fn verify_nist_constraints<NS: NumeralString>(
+        &self,
+        tweak: &[u8],
+        x: &NS,
+    ) -> Result<(), ::alloc::vec::Vec<NistViolation>> {
+        let mut violations = ::alloc::vec::Vec::new();
+
+        // The block size is checked at compile time, and the minimum length of the radix
+        // ensures a domain of at least one million values, so neither can be violated
+        // once the FF1 instance exists.
+        let (radix, min_len) = (self.radix.to_u32(), self.radix.min_len());
+        if tweak.len() > MAX_TWEAK_LEN {
+            violations.push(NistViolation::TweakTooLong {
+                tweak_len: tweak.len(),
+                max_len: MAX_TWEAK_LEN,
+            });
+        }
+
+        let ns_len = x.numeral_count();
+        if ns_len < min_len {
+            violations.push(NistViolation::NumeralStringTooShort {
+                actual: ns_len,
+                min: min_len,
+            });
+        } else if ns_len > MAX_NS_LEN {
+            violations.push(NistViolation::NumeralStringTooLong {
+                actual: ns_len,
+                max: MAX_NS_LEN,
+            });
+        }
+
+        if !x.is_valid(radix) {
+            violations.push(NistViolation::InvalidForRadix(radix));
+        }
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(violations)
+        }
+    }
+
+    /// Encrypts the given numeral string.
+    ///
+    /// Returns an error if the numeral string is not in the required radix.
+    pub fn encrypt<NS: NumeralString>(
+        &self,
+        tweak: &[u8],
+        x: &NS,
+    ) -> Result<NS, NumeralStringError> {
+        check_tweak(tweak)?;
+        self.check_ns(x)?;
+        let prf = self.prf_prefix(x.numeral_count(), tweak);
+        let (x_a, x_b) = self.encrypt_with_prefix(&prf, x);
+        Ok(NS::concat(x_a, x_b))
+    }
+
+    /// Decrypts the given numeral string.
+    ///
+    /// Returns an error if the numeral string is not in the required radix.
+    pub fn decrypt<NS: NumeralString>(
+        &self,
+        tweak: &[u8],
+        x: &NS,
+    ) -> Result<NS, NumeralStringError> {
+        check_tweak(tweak)?;
+        self.check_ns(x)?;
+        let prf = self.prf_prefix(x.numeral_count(), tweak);
+        let (x_a, x_b) = self.decrypt_with_prefix(&prf, x);
+        Ok(NS::concat(x_a, x_b))
+    }
+
+    /// Encrypts the given numeral string, whose numerals are ordered least significant
+    /// first.
+    ///
+    /// This is equivalent to `self.encrypt(tweak, &x.reversed())` followed by
+    /// [`NumeralString::reversed`], and is not the same as [`FF1::encrypt`] on `x`.
+    ///
+    /// Returns an error if the numeral string is not in the required radix.
+    pub fn encrypt_reversed<NS: NumeralString>(
+        &self,
+        tweak: &[u8],
+        x: &NS,
+    ) -> Result<NS, NumeralStringError> {
+        self.encrypt(tweak, &x.reversed()).map(|y| y.reversed())
+    }
+
+    /// Decrypts the given numeral string, whose numerals are ordered least significant
+    /// first.
+    ///
+    /// This is the inverse of [`FF1::encrypt_reversed`].
+    ///
+    /// Returns an error if the numeral string is not in the required radix.
+    pub fn decrypt_reversed<NS: NumeralString>(
+        &self,
+        tweak: &[u8],
+        x: &NS,
+    ) -> Result<NS, NumeralStringError> {
+        self.decrypt(tweak, &x.reversed()).map(|y| y.reversed())
+    }
+
+    /// Encrypts the given numeral string in place.
+    ///
+    /// Returns an error if the numeral string is not in the required radix, in which
+    /// case `x` is left unmodified.
+    pub fn encrypt_mut<NS: NumeralStringMut>(
+        &self,
+        tweak: &[u8],
+        x: &mut NS,
+    ) -> Result<(), NumeralStringError> {
+        check_tweak(tweak)?;
+        self.check_ns(x)?;
+        let prf = self.prf_prefix(x.numeral_count(), tweak);
+        let (x_a, x_b) = self.encrypt_with_prefix(&prf, x);
+        x.replace_with(x_a, x_b);
+        Ok(())
+    }
+
+    /// Decrypts the given numeral string in place.
+    ///
+    /// Returns an error if the numeral string is not in the required radix, in which
+    /// case `x` is left unmodified.
+    pub fn decrypt_mut<NS: NumeralStringMut>(
+        &self,
+        tweak: &[u8],
+        x: &mut NS,
+    ) -> Result<(), NumeralStringError> {
+        check_tweak(tweak)?;
+        self.check_ns(x)?;
+        let prf = self.prf_prefix(x.numeral_count(), tweak);
+        let (x_a, x_b) = self.decrypt_with_prefix(&prf, x);
+        x.replace_with(x_a, x_b);
+        Ok(())
+    }
+
+    /// Encrypts the first `len` numerals yielded by `iter`, returning an iterator over
+    /// the encrypted numerals.
+    ///
+    /// FF1 needs the entire numeral string for every Feistel round, so the input is
+    /// buffered. The output is yielded directly from the two halves computed by the
+    /// final round, without concatenating them into a second buffer.
+    ///
+    /// Returns an error if `iter` yields fewer than `len` numerals, or if they are not
+    /// in the required radix.
+    #[cfg(feature = "alloc")]
+    pub fn encrypt_iter<I: IntoIterator<Item = u16>>(
+        &self,
+        tweak: &[u8],
+        iter: I,
+        len: usize,
+    ) -> Result<NumeralStringIter, NumeralStringError> {
+        check_tweak(tweak)?;
+        let x = collect_numerals(iter, len)?;
+        self.check_ns(&x)?;
+        let prf = self.prf_prefix(len, tweak);
+        let (x_a, x_b) = self.encrypt_with_prefix(&prf, &x);
+        Ok(NumeralStringIter::new(x_a, x_b))
+    }
+
+    /// Decrypts the first `len` numerals yielded by `iter`, returning an iterator over
+    /// the decrypted numerals.
+    ///
+    /// See [`FF1::encrypt_iter`] for details.
+    #[cfg(feature = "alloc")]
+    pub fn decrypt_iter<I: IntoIterator<Item = u16>>(
+        &self,
+        tweak: &[u8],
+        iter: I,
+        len: usize,
+    ) -> Result<NumeralStringIter, NumeralStringError> {
+        check_tweak(tweak)?;
+        let x = collect_numerals(iter, len)?;
+        self.check_ns(&x)?;
+        let prf = self.prf_prefix(len, tweak);
+        let (x_a, x_b) = self.decrypt_with_prefix(&prf, &x);
+        Ok(NumeralStringIter::new(x_a, x_b))
+    }
+
+    /// Encrypts each of the given numeral strings with the same tweak.
     ///
-    /// Returns an error if the given radix is not in [2..2^16].
-    pub fn new(key: &[u8], radix: u32) -> Result<Self, InvalidRadix> {
-        let ciph = CIPH::new(GenericArray::from_slice(key));
-        let radix = Radix::from_u32(radix)?;
-        Ok(FF1 { ciph, radix, faistel_rounds:10 })
+    /// This is equivalent to calling [`FF1::encrypt`] on each input, but the PRF
+    /// computation over the fixed header and tweak is shared between consecutive inputs
+    /// of the same length.
+    ///
+    /// Returns an error if any numeral string is not in the required radix.
+    #[cfg(feature = "alloc")]
+    pub fn encrypt_batch<NS: NumeralString>(
+        &self,
+        tweak: &[u8],
+        inputs: &[NS],
+    ) -> Result<::alloc::vec::Vec<NS>, NumeralStringError> {
+        check_tweak(tweak)?;
+        let mut cache = None;
+        inputs
+            .iter()
+            .map(|x| {
+                self.check_ns(x)?;
+                let prf = self.cached_prf_prefix(&mut cache, x.numeral_count(), tweak);
+                let (x_a, x_b) = self.encrypt_with_prefix(prf, x);
+                Ok(NS::concat(x_a, x_b))
+            })
+            .collect()
     }
-    /// Creates a new FF1 object for the given key and radix.
+
+    /// Decrypts each of the given numeral strings with the same tweak.
     ///
-    /// Returns an error if the given radix is not in [2..2^16].
-    pub fn new_with_faistel_rounds(key: &[u8], radix: u32, faistel_rounds:u8 ) -> Result<Self, InvalidRadix> {
-        let ciph = CIPH::new(GenericArray::from_slice(key));
-        let radix = Radix::from_u32(radix)?;
-        Ok(FF1 { ciph, radix, faistel_rounds })
+    /// This is equivalent to calling [`FF1::decrypt`] on each input, but the PRF
+    /// computation over the fixed header and tweak is shared between consecutive inputs
+    /// of the same length.
+    ///
+    /// Returns an error if any numeral string is not in the required radix.
+    #[cfg(feature = "alloc")]
+    pub fn decrypt_batch<NS: NumeralString>(
+        &self,
+        tweak: &[u8],
+        inputs: &[NS],
+    ) -> Result<::alloc::vec::Vec<NS>, NumeralStringError> {
+        check_tweak(tweak)?;
+        let mut cache = None;
+        inputs
+            .iter()
+            .map(|x| {
+                self.check_ns(x)?;
+                let prf = self.cached_prf_prefix(&mut cache, x.numeral_count(), tweak);
+                let (x_a, x_b) = self.decrypt_with_prefix(prf, x);
+                Ok(NS::concat(x_a, x_b))
+            })
+            .collect()
     }
 
-}
+    /// Encrypts each of the given numeral strings with the same tweak, in parallel.
+    ///
+    /// This behaves like [`FF1::encrypt_batch`], with the inputs split across Rayon
+    /// worker threads.
+    #[cfg(all(feature = "alloc", feature = "rayon"))]
+    pub fn encrypt_batch_parallel<NS: NumeralString + Send + Sync>(
+        &self,
+        tweak: &[u8],
+        inputs: &[NS],
+    ) -> Result<::alloc::vec::Vec<NS>, NumeralStringError>
+    where
+        CIPH: Sync,
+    {
+        use rayon::prelude::*;
+
+        self.encrypt_par_iter(tweak, inputs)?.collect()
+    }
 
-impl<CIPH: BlockCipher + BlockEncrypt + Clone> FF1<CIPH> {
-    /// Encrypts the given numeral string.
+    /// Decrypts each of the given numeral strings with the same tweak, in parallel.
     ///
-    /// Returns an error if the numeral string is not in the required radix.
-    #[allow(clippy::many_single_char_names)]
-    pub fn encrypt<NS: NumeralString>(
+    /// This behaves like [`FF1::decrypt_batch`], with the inputs split across Rayon
+    /// worker threads.
+    #[cfg(all(feature = "alloc", feature = "rayon"))]
+    pub fn decrypt_batch_parallel<NS: NumeralString + Send + Sync>(
         &self,
         tweak: &[u8],
-        x: &NS,
-    ) -> Result<NS, NumeralStringError> {
+        inputs: &[NS],
+    ) -> Result<::alloc::vec::Vec<NS>, NumeralStringError>
+    where
+        CIPH: Sync,
+    {
+        use rayon::prelude::*;
+
+        self.decrypt_par_iter(tweak, inputs)?.collect()
+    }
+
+    /// Returns a parallel iterator over the encryptions of the given numeral strings
+    /// with the same tweak.
+    ///
+    /// Unlike [`FF1::encrypt_batch_parallel`], the ciphertexts are not collected, so
+    /// they can be combined with other Rayon adaptors (for example, zipped with record
+    /// IDs). Each item is an error if that numeral string is not in the required radix.
+    ///
+    /// Returns an error if the tweak is too long.
+    #[cfg(all(feature = "alloc", feature = "rayon"))]
+    pub fn encrypt_par_iter<'a, NS: NumeralString + Send + Sync>(
+        &'a self,
+        tweak: &'a [u8],
+        inputs: &'a [NS],
+    ) -> Result<
+        impl rayon::iter::IndexedParallelIterator<Item = Result<NS, NumeralStringError>> + 'a,
+        NumeralStringError,
+    >
+    where
+        CIPH: Sync,
+    {
+        use rayon::prelude::*;
+
+        check_tweak(tweak)?;
+        Ok(inputs.par_iter().map_init(
+            || None,
+            move |cache, x| {
+                self.check_ns(x)?;
+                let prf = self.cached_prf_prefix(cache, x.numeral_count(), tweak);
+                let (x_a, x_b) = self.encrypt_with_prefix(prf, x);
+                Ok(NS::concat(x_a, x_b))
+            },
+        ))
+    }
+
+    /// Returns a parallel iterator over the decryptions of the given numeral strings
+    /// with the same tweak.
+    ///
+    /// Unlike [`FF1::decrypt_batch_parallel`], the plaintexts are not collected, so
+    /// they can be combined with other Rayon adaptors. Each item is an error if that
+    /// numeral string is not in the required radix.
+    ///
+    /// Returns an error if the tweak is too long.
+    #[cfg(all(feature = "alloc", feature = "rayon"))]
+    pub fn decrypt_par_iter<'a, NS: NumeralString + Send + Sync>(
+        &'a self,
+        tweak: &'a [u8],
+        inputs: &'a [NS],
+    ) -> Result<
+        impl rayon::iter::IndexedParallelIterator<Item = Result<NS, NumeralStringError>> + 'a,
+        NumeralStringError,
+    >
+    where
+        CIPH: Sync,
+    {
+        use rayon::prelude::*;
+
+        check_tweak(tweak)?;
+        Ok(inputs.par_iter().map_init(
+            || None,
+            move |cache, x| {
+                self.check_ns(x)?;
+                let prf = self.cached_prf_prefix(cache, x.numeral_count(), tweak);
+                let (x_a, x_b) = self.decrypt_with_prefix(prf, x);
+                Ok(NS::concat(x_a, x_b))
+            },
+        ))
+    }
+
+    fn check_ns<NS: NumeralString>(&self, x: &NS) -> Result<(), NumeralStringError> {
         if !x.is_valid(self.radix.to_u32()) {
             return Err(NumeralStringError::InvalidForRadix(self.radix.to_u32()));
         }
-        self.radix.check_ns_length(x.numeral_count())?;
+        self.radix.check_ns_length(x.numeral_count())
+    }
 
-        let n = x.numeral_count();
+    /// Returns the PRF state after processing `P || T || [0]^((-t-b-1) mod 16)`, which
+    /// is shared by every Feistel round for numeral strings of length `n`.
+    fn prf_prefix(&self, n: usize, tweak: &[u8]) -> Prf<CIPH> {
         let t = tweak.len();
 
+        // 1. Let u = floor(n / 2); v = n - u
+        let u = n / 2;
+        let v = n - u;
+
+        // 3. Let b = ceil(ceil(v * log2(radix)) / 8).
+        let b = self.radix.calculate_b(v);
+
+        // 5. Let P = [1, 2, 1] || [radix] || [10] || [u mod 256] || [n] || [t].
+        //
+        // `self.prf_static` has already processed the bytes before [u mod 256].
+        let mut p = [u as u8, 0, 0, 0, 0, 0, 0, 0, 0];
+        p[1..5].copy_from_slice(&(n as u32).to_be_bytes());
+        p[5..9].copy_from_slice(&(t as u32).to_be_bytes());
+
+        //  6i. Let Q = T || [0]^((-t-b-1) mod 16) || [i] || [NUM(B, radix)].
+        // 6ii. Let R = PRF(P || Q).
+        #[cfg(feature = "logging")]
+        log::trace!(
+            "FF1 PRF: P = 010201{}0a{}",
+            HexFmt(self.radix.to_u32().to_be_bytes()[1..].iter().copied()),
+            HexFmt(p.iter().copied()),
+        );
+
+        let mut prf = self.prf_static.clone();
+        prf.update(&p);
+        prf.update(tweak);
+        for _ in 0..((((-(t as i32) - (b as i32) - 1) % 16) + 16) % 16) {
+            prf.update(&[0]);
+        }
+        prf
+    }
+
+    /// Returns the PRF prefix for numeral strings of length `n`, reusing the one in
+    /// `cache` if it was computed for the same length.
+    fn cached_prf_prefix<'a>(
+        &self,
+        cache: &'a mut Option<(usize, Prf<CIPH>)>,
+        n: usize,
+        tweak: &[u8],
+    ) -> &'a Prf<CIPH> {
+        if !matches!(cache, Some((cached_n, _)) if *cached_n == n) {
+            *cache = Some((n, self.prf_prefix(n, tweak)));
+        }
+        match cache {
+            Some((_, prf)) => prf,
+            None => unreachable!(),
+        }
+    }
+
+    /// Encrypts `x`, given the PRF prefix for its length and tweak, and returns the two
+    /// halves of the ciphertext.
+    ///
+    /// The caller MUST have checked that `x` is valid for this FF1 instance.
+    fn encrypt_with_prefix<NS: NumeralString>(
+        &self,
+        prf: &Prf<CIPH>,
+        x: &NS,
+    ) -> (NS::Ops, NS::Ops) {
+        self.encrypt_with_inspector_inner(prf, x, &mut ())
+    }
+
+    /// As [`Self::encrypt_with_prefix`], calling `inspector` at the end of each round.
+    #[allow(clippy::many_single_char_names)]
+    fn encrypt_with_inspector_inner<NS: NumeralString>(
+        &self,
+        prf: &Prf<CIPH>,
+        x: &NS,
+        inspector: &mut impl Inspector<NS::Ops>,
+    ) -> (NS::Ops, NS::Ops) {
         // 1. Let u = floor(n / 2); v = n - u
         // 2. Let A = X[1..u]; B = X[u + 1..n].
         let (mut x_a, mut x_b) = x.split();
@@ -273,63 +1593,62 @@ impl<CIPH: BlockCipher + BlockEncrypt + Clone> FF1<CIPH> {
         let d = 4 * ((b + 3) / 4) + 4;
 
         // 5. Let P = [1, 2, 1] || [radix] || [10] || [u mod 256] || [n] || [t].
-        let mut p = [1, 2, 1, 0, 0, 0, 10, u as u8, 0, 0, 0, 0, 0, 0, 0, 0];
-        p[3..6].copy_from_slice(&self.radix.to_u32().to_be_bytes()[1..]);
-        p[8..12].copy_from_slice(&(n as u32).to_be_bytes());
-        p[12..16].copy_from_slice(&(t as u32).to_be_bytes());
-
         //  6i. Let Q = T || [0]^((-t-b-1) mod 16) || [i] || [NUM(B, radix)].
         // 6ii. Let R = PRF(P || Q).
-        let mut prf = Prf::new(&self.ciph);
-        prf.update(&p);
-        prf.update(tweak);
-        for _ in 0..((((-(t as i32) - (b as i32) - 1) % 16) + 16) % 16) {
-            prf.update(&[0]);
-        }
+        //
+        // `prf` has already processed P and the first part of Q.
         for i in 0..self.faistel_rounds {
             let mut prf = prf.clone();
             prf.update(&[i]);
             prf.update(x_b.to_be_bytes(self.radix.to_u32(), b).as_ref());
-            let r = prf.output();
+            let r = prf.finalize_into_array();
 
             // 6iii. Let S be the first d bytes of R.
             let s = generate_s(&self.ciph, r, d);
 
+            #[cfg(feature = "logging")]
+            trace_round(i, &x_a, &x_b, self.radix.to_u32(), b, s.clone());
+
             // 6iv. Let y = NUM(S).
             // 6v. If i is even, let m = u; else, let m = v.
             // 6vi. Let c = (NUM(A, radix) + y) mod radix^m.
             // 6vii. Let C = STR(c, radix).
             let m = if i % 2 == 0 { u } else { v };
-            let x_c = x_a.add_mod_exp(s, self.radix.to_u32(), m);
+            let x_c = x_a.add_mod_exp(s.clone(), self.radix.to_u32(), m);
 
             // 6viii. Let A = B.
             x_a = x_b;
 
             // 6ix. Let B = C.
             x_b = x_c;
+
+            inspector.inspect(i, &x_a, &x_b, s, m);
         }
 
         // 7. Return A || B.
-        Ok(NS::concat(x_a, x_b))
+        (x_a, x_b)
     }
 
-    /// Decrypts the given numeral string.
+    /// Decrypts `x`, given the PRF prefix for its length and tweak, and returns the two
+    /// halves of the plaintext.
     ///
-    /// Returns an error if the numeral string is not in the required radix.
-    #[allow(clippy::many_single_char_names)]
-    pub fn decrypt<NS: NumeralString>(
+    /// The caller MUST have checked that `x` is valid for this FF1 instance.
+    fn decrypt_with_prefix<NS: NumeralString>(
         &self,
-        tweak: &[u8],
+        prf: &Prf<CIPH>,
         x: &NS,
-    ) -> Result<NS, NumeralStringError> {
-        if !x.is_valid(self.radix.to_u32()) {
-            return Err(NumeralStringError::InvalidForRadix(self.radix.to_u32()));
-        }
-        self.radix.check_ns_length(x.numeral_count())?;
-
-        let n = x.numeral_count();
-        let t = tweak.len();
+    ) -> (NS::Ops, NS::Ops) {
+        self.decrypt_with_inspector_inner(prf, x, &mut ())
+    }
 
+    /// As [`Self::decrypt_with_prefix`], calling `inspector` at the end of each round.
+    #[allow(clippy::many_single_char_names)]
+    fn decrypt_with_inspector_inner<NS: NumeralString>(
+        &self,
+        prf: &Prf<CIPH>,
+        x: &NS,
+        inspector: &mut impl Inspector<NS::Ops>,
+    ) -> (NS::Ops, NS::Ops) {
         // 1. Let u = floor(n / 2); v = n - u
         // 2. Let A = X[1..u]; B = X[u + 1..n].
         let (mut x_a, mut x_b) = x.split();
@@ -343,55 +1662,67 @@ impl<CIPH: BlockCipher + BlockEncrypt + Clone> FF1<CIPH> {
         let d = 4 * ((b + 3) / 4) + 4;
 
         // 5. Let P = [1, 2, 1] || [radix] || [10] || [u mod 256] || [n] || [t].
-        let mut p = [1, 2, 1, 0, 0, 0, 10, u as u8, 0, 0, 0, 0, 0, 0, 0, 0];
-        p[3..6].copy_from_slice(&self.radix.to_u32().to_be_bytes()[1..]);
-        p[8..12].copy_from_slice(&(n as u32).to_be_bytes());
-        p[12..16].copy_from_slice(&(t as u32).to_be_bytes());
-
         //  6i. Let Q = T || [0]^((-t-b-1) mod 16) || [i] || [NUM(A, radix)].
         // 6ii. Let R = PRF(P || Q).
-        let mut prf = Prf::new(&self.ciph);
-        prf.update(&p);
-        prf.update(tweak);
-        for _ in 0..((((-(t as i32) - (b as i32) - 1) % 16) + 16) % 16) {
-            prf.update(&[0]);
-        }
+        //
+        // `prf` has already processed P and the first part of Q.
         for i in 0..self.faistel_rounds {
             let i = self.faistel_rounds - 1 - i;
             let mut prf = prf.clone();
             prf.update(&[i]);
             prf.update(x_a.to_be_bytes(self.radix.to_u32(), b).as_ref());
-            let r = prf.output();
+            let r = prf.finalize_into_array();
 
             // 6iii. Let S be the first d bytes of R.
             let s = generate_s(&self.ciph, r, d);
 
+            #[cfg(feature = "logging")]
+            trace_round(i, &x_a, &x_b, self.radix.to_u32(), b, s.clone());
+
             // 6iv. Let y = NUM(S).
             // 6v. If i is even, let m = u; else, let m = v.
             // 6vi. Let c = (NUM(B, radix) - y) mod radix^m.
             // 6vii. Let C = STR(c, radix).
             let m = if i % 2 == 0 { u } else { v };
-            let x_c = x_b.sub_mod_exp(s, self.radix.to_u32(), m);
+            let x_c = x_b.sub_mod_exp(s.clone(), self.radix.to_u32(), m);
 
             // 6viii. Let B = A.
             x_b = x_a;
 
             // 6ix. Let A = C.
             x_a = x_c;
+
+            inspector.inspect(i, &x_a, &x_b, s, m);
         }
 
         // 7. Return A || B.
-        Ok(NS::concat(x_a, x_b))
+        (x_a, x_b)
+    }
+}
+
+impl<CIPH: BlockCipher + BlockEncrypt + Clone> crate::FpeAlgorithm for FF1<CIPH> {
+    type Tweak = [u8];
+
+    fn encrypt<NS: NumeralString>(&self, tweak: &[u8], x: &NS) -> Result<NS, NumeralStringError> {
+        FF1::encrypt(self, tweak, x)
+    }
+
+    fn decrypt<NS: NumeralString>(&self, tweak: &[u8], x: &NS) -> Result<NS, NumeralStringError> {
+        FF1::decrypt(self, tweak, x)
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{InvalidRadix, Radix, MIN_NS_LEN, MIN_RADIX_2_NS_LEN};
+    use super::{
+        calculate_b_float, calculate_b_int, generate_s, InvalidRadix, NistViolation,
+        NumeralStringError, Prf, Radix, RadixInfo, MAX_RADIX, MAX_TWEAK_LEN, MIN_NS_LEN,
+        MIN_RADIX_2_NS_LEN,
+    };
    // use super::ff1::BinaryNumeralString;
    
      use crate::ff1::FF1;
-    use num_bigint::{BigUint, ToBigUint};
+    use num_bigint::BigUint;
      use crate::ff1::{FlexibleNumeralString, BinaryNumeralString};
      use aes::Aes256;
 
@@ -415,7 +1746,7 @@ mod tests {
          let n1 = BigUint::parse_bytes(bytes, 10).unwrap();
          let b1 = n1.to_bytes_le();
          println!("bytes:{:?}, n1:{}, b1:{:?}", bytes, n1, b1);
-         let ns =  BinaryNumeralString::from_bytes_le(&bytes);
+         let ns =  BinaryNumeralString::from_bytes_le(bytes);
          println!("ns:{:?}", ns);
          let ns_num1 = BigUint::parse_bytes(&ns.to_bytes_le(),10).unwrap();
          println!("ns_num1:{:?}", ns_num1);
@@ -456,7 +1787,7 @@ mod tests {
     fn ff1_test() {
          
          let bytes = "123456789".as_bytes();
-         let ns =  BinaryNumeralString::from_bytes_le(&bytes);
+         let ns =  BinaryNumeralString::from_bytes_le(bytes);
          println!("ns:{:?}", ns);
          assert_eq!(bytes, ns.to_bytes_le());
 
@@ -560,8 +1891,8 @@ mod tests {
         let fpe_ff = FF1::<Aes256>::new_with_faistel_rounds(key, radix, rounds).unwrap();
         let num = BigUint::parse_bytes(bytes, radix).unwrap(); 
         let fns = FlexibleNumeralString::str_radix(num, radix, bytes.len());
-        let ns_encrypted = fpe_ff.encrypt(&[], &fns).unwrap();
-        let ns_decrypted = fpe_ff.decrypt(&[], &ns_encrypted).unwrap();
+        let ns_encrypted = fpe_ff.encrypt(tweak, &fns).unwrap();
+        let ns_decrypted = fpe_ff.decrypt(tweak, &ns_encrypted).unwrap();
         let num = ns_decrypted.num_radix(radix);
 
         let new_str = num.to_str_radix(10);
@@ -570,6 +1901,522 @@ mod tests {
     }
     
 
+    #[test]
+    fn encrypt_mut() {
+        let ff = FF1::<Aes256>::new(&[0; 32], 10).unwrap();
+        let pt = FlexibleNumeralString::from_decimal_str("0123456789").unwrap();
+        let ct = ff.encrypt(b"tweak", &pt).unwrap();
+
+        let mut x = FlexibleNumeralString::from_decimal_str("0123456789").unwrap();
+        ff.encrypt_mut(b"tweak", &mut x).unwrap();
+        assert_eq!(x.to_decimal_str(), ct.to_decimal_str());
+        ff.decrypt_mut(b"tweak", &mut x).unwrap();
+        assert_eq!(x.to_decimal_str(), "0123456789");
+
+        // Invalid inputs are left unmodified.
+        let mut x = FlexibleNumeralString::from(vec![10; 6]);
+        assert_eq!(
+            ff.encrypt_mut(&[], &mut x).unwrap_err(),
+            NumeralStringError::InvalidForRadix(10),
+        );
+        assert_eq!(Vec::from(x), vec![10; 6]);
+
+        let ff = FF1::<Aes256>::new(&[0; 32], 2).unwrap();
+        let bytes = [0xab, 0xcd, 0xef];
+        let mut x = BinaryNumeralString::from_bytes_le(&bytes);
+        ff.encrypt_mut(&[], &mut x).unwrap();
+        assert_eq!(x.to_bytes_le(), [0x75, 0xfb, 0x62]);
+        ff.decrypt_mut(&[], &mut x).unwrap();
+        assert_eq!(x.to_bytes_le(), bytes);
+    }
+
+    #[test]
+    fn encrypt_reversed() {
+        let ff = FF1::<Aes256>::new(&[0; 32], 10).unwrap();
+        let pt = FlexibleNumeralString::from_decimal_str("0123456789").unwrap();
+
+        let ct = ff.encrypt_reversed(b"tweak", &pt).unwrap();
+        let reversed = FlexibleNumeralString::from_decimal_str("9876543210").unwrap();
+        assert_eq!(
+            ct.to_decimal_str().chars().rev().collect::<String>(),
+            ff.encrypt(b"tweak", &reversed).unwrap().to_decimal_str(),
+        );
+        assert_ne!(
+            ct.to_decimal_str(),
+            ff.encrypt(b"tweak", &pt).unwrap().to_decimal_str()
+        );
+        assert_eq!(
+            ff.decrypt_reversed(b"tweak", &ct).unwrap().to_decimal_str(),
+            "0123456789"
+        );
+    }
+
+    #[test]
+    fn encrypt_iter() {
+        let ff = FF1::<Aes256>::new(&[0; 32], 10).unwrap();
+        let digits = || (0..10).map(|d| d as u16);
+        let ct = ff
+            .encrypt(
+                b"tweak",
+                &FlexibleNumeralString::from(digits().collect::<Vec<_>>()),
+            )
+            .unwrap();
+
+        let ct_iter = ff.encrypt_iter(b"tweak", digits(), 10).unwrap();
+        assert_eq!(ct_iter.size_hint(), (10, Some(10)));
+        let ct_numerals: Vec<_> = ct_iter.collect();
+        assert_eq!(ct_numerals, Vec::from(ct));
+
+        let pt: Vec<_> = ff
+            .decrypt_iter(b"tweak", ct_numerals.into_iter(), 10)
+            .unwrap()
+            .collect();
+        assert_eq!(pt, digits().collect::<Vec<_>>());
+
+        // Only the first `len` numerals are encrypted.
+        assert_eq!(
+            ff.encrypt_iter(b"tweak", digits().chain(digits()), 10)
+                .unwrap()
+                .count(),
+            10,
+        );
+        assert_eq!(
+            ff.encrypt_iter(b"tweak", digits(), 11).err(),
+            Some(NumeralStringError::TooShort {
+                ns_len: 10,
+                min_len: 11,
+            }),
+        );
+    }
+
+    #[test]
+    fn clone_and_thread_safety() {
+        use static_assertions::assert_impl_all;
+
+        assert_impl_all!(FF1<Aes256>: Clone, Send, Sync);
+
+        let ff = FF1::<Aes256>::new(&[0; 32], 10).unwrap();
+        let pt = FlexibleNumeralString::from_decimal_str("0123456789").unwrap();
+        let ct = ff.encrypt(&[], &pt).unwrap().to_decimal_str();
+
+        // A clone shares no PRF state with the original, so both continue to produce
+        // the same results after being used.
+        let cloned = ff.clone();
+        assert_eq!(cloned.encrypt(&[], &pt).unwrap().to_decimal_str(), ct);
+        assert_eq!(ff.encrypt(&[], &pt).unwrap().to_decimal_str(), ct);
+
+        let ff = std::sync::Arc::new(ff);
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let ff = ff.clone();
+                std::thread::spawn(move || {
+                    let pt = FlexibleNumeralString::from_decimal_str("0123456789").unwrap();
+                    ff.encrypt(&[], &pt).unwrap().to_decimal_str()
+                })
+            })
+            .collect();
+        for handle in handles {
+            assert_eq!(handle.join().unwrap(), ct);
+        }
+    }
+
+    #[test]
+    fn prf_finalize_into_array() {
+        use cipher::{Block, BlockEncrypt, KeyInit};
+
+        let ciph = Aes256::new(&[0; 32].into());
+        let mut prf = Prf::new(&ciph);
+        prf.update(&[1; 16]);
+        let r = prf.finalize_into_array();
+
+        // S starts with R, followed by the encryptions of R xor [j]^16.
+        let s: Vec<u8> = generate_s(&ciph, r, 20).collect();
+        assert_eq!(&s[..16], &r);
+        let mut block = Block::<Aes256>::clone_from_slice(&r);
+        block[15] ^= 1;
+        ciph.encrypt_block(&mut block);
+        assert_eq!(&s[16..], &block[..4]);
+
+        let mut blocks = r.to_vec();
+        for j in 1..4u8 {
+            let mut block = Block::<Aes256>::clone_from_slice(&r);
+            block[15] ^= j;
+            ciph.encrypt_block(&mut block);
+            blocks.extend_from_slice(&block);
+        }
+        for d in 0..=blocks.len() {
+            let s = generate_s(&ciph, r, d);
+            assert_eq!(s.clone().collect::<Vec<_>>(), &blocks[..d]);
+
+            // A clone continues from the same position.
+            let mut s = s.skip(d / 2);
+            s.next();
+            assert!(s.clone().eq(s));
+        }
+    }
+
+    #[test]
+    fn debug_redacts_key() {
+        let ff = FF1::<Aes256>::new(&[0xab; 32], 10).unwrap();
+        assert_eq!(
+            format!("{:?}", ff),
+            "FF1 { radix: 10, rounds: 10, key: [REDACTED] }",
+        );
+        assert_eq!(format!("{:?}", ff.prf_static), "Prf { offset: 7, .. }");
+        assert_eq!(
+            format!("{:?}", Radix::from_u32(2).unwrap()),
+            "Radix { radix: 2, min_len: 20 }",
+        );
+    }
+
+    #[test]
+    fn error_display() {
+        assert_eq!(
+            NumeralStringError::TweakTooLong {
+                tweak_len: 1 << 32,
+                max_len: MAX_TWEAK_LEN,
+            }
+            .to_string(),
+            "The given tweak is too long for FF1 (4294967296 > 4294967295)",
+        );
+        assert_eq!(
+            InvalidRadix(1).to_string(),
+            "The radix 1 is not in the range 2..=(1 << 16)",
+        );
+    }
+
+    #[test]
+    fn verify_nist_constraints() {
+        let ff = FF1::<Aes256>::new(&[0; 32], 10).unwrap();
+        assert_eq!(
+            ff.verify_nist_constraints(&[], &FlexibleNumeralString::from(vec![1; 6])),
+            Ok(()),
+        );
+        assert_eq!(
+            ff.verify_nist_constraints(&[], &FlexibleNumeralString::from(vec![1, 2, 10])),
+            Err(vec![
+                NistViolation::NumeralStringTooShort { actual: 3, min: 6 },
+                NistViolation::InvalidForRadix(10),
+            ]),
+        );
+    }
+
+    #[test]
+    fn batch_matches_single() {
+        let ff = FF1::<Aes256>::new(&[0; 32], 10).unwrap();
+        let tweak = b"batch tweak";
+        // Mixed lengths exercise the PRF prefix cache being refreshed.
+        let raw = [
+            vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9],
+            vec![9, 8, 7, 6, 5, 4, 3, 2, 1, 0],
+            vec![1, 2, 3, 4, 5, 6],
+            vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 0, 1, 2, 3, 4, 5, 6],
+            vec![5, 5, 5, 5, 5, 5],
+        ];
+        let inputs = raw
+            .iter()
+            .cloned()
+            .map(FlexibleNumeralString::from)
+            .collect::<Vec<_>>();
+
+        let cts = ff.encrypt_batch(tweak, &inputs).unwrap();
+        let pts = ff.decrypt_batch(tweak, &cts).unwrap();
+        for (((x, raw), ct), pt) in inputs.iter().zip(raw).zip(cts).zip(pts) {
+            assert_eq!(Vec::from(ct), Vec::from(ff.encrypt(tweak, x).unwrap()));
+            assert_eq!(Vec::from(pt), raw);
+        }
+
+        assert_eq!(
+            ff.encrypt_batch(tweak, &[FlexibleNumeralString::from(vec![10; 6])])
+                .unwrap_err(),
+            NumeralStringError::InvalidForRadix(10),
+        );
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn batch_parallel_matches_sequential() {
+        let ff = FF1::<Aes256>::new(&[0; 32], 10).unwrap();
+        let inputs = (0..100u16)
+            .map(|i| FlexibleNumeralString::from(vec![i % 10; 6 + usize::from(i % 3)]))
+            .collect::<Vec<_>>();
+
+        let cts = ff.encrypt_batch_parallel(&[], &inputs).unwrap();
+        let pts = ff.decrypt_batch_parallel(&[], &cts).unwrap();
+        let expected = ff.encrypt_batch(&[], &inputs).unwrap();
+        for (((x, ct), pt), expected) in inputs.into_iter().zip(cts).zip(pts).zip(expected) {
+            assert_eq!(Vec::from(ct), Vec::from(expected));
+            assert_eq!(Vec::from(pt), Vec::from(x));
+        }
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn par_iter() {
+        use rayon::prelude::*;
+
+        let ff = FF1::<Aes256>::new(&[0; 32], 10).unwrap();
+        let inputs = (0..100u16)
+            .map(|i| FlexibleNumeralString::from(vec![i % 10; 6]))
+            .collect::<Vec<_>>();
+
+        // The items can be zipped with other parallel iterators.
+        let (ids, cts): (Vec<_>, Vec<_>) = ff
+            .encrypt_par_iter(b"tweak", &inputs)
+            .unwrap()
+            .zip(0..100)
+            .map(|(ct, id)| (id, ct.unwrap()))
+            .unzip();
+        assert_eq!(ids, (0..100).collect::<Vec<_>>());
+        let pts = ff
+            .decrypt_par_iter(b"tweak", &cts)
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        for ((x, ct), pt) in inputs.iter().zip(cts).zip(pts) {
+            assert_eq!(Vec::from(ct), Vec::from(ff.encrypt(b"tweak", x).unwrap()));
+            assert_eq!(Vec::from(pt), x.0);
+        }
+
+        let inputs = [
+            FlexibleNumeralString::from(vec![1; 6]),
+            FlexibleNumeralString::from(vec![10; 6]),
+        ];
+        let results = ff
+            .encrypt_par_iter(&[], &inputs)
+            .unwrap()
+            .collect::<Vec<_>>();
+        assert!(results[0].is_ok());
+        assert_eq!(
+            results[1].as_ref().unwrap_err(),
+            &NumeralStringError::InvalidForRadix(10)
+        );
+    }
+
+    #[test]
+    fn new_from_key() {
+        let key = [7; 32];
+        let pt = FlexibleNumeralString::from(vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        let ff = FF1::<Aes256>::new(&key, 10).unwrap();
+        let ff_key = FF1::<Aes256>::new_from_key(&key.into(), 10).unwrap();
+        assert_eq!(
+            Vec::from(ff_key.encrypt(&[], &pt).unwrap()),
+            Vec::from(ff.encrypt(&[], &pt).unwrap()),
+        );
+    }
+
+    #[cfg(feature = "aes")]
+    #[test]
+    fn aes192() {
+        use super::{
+            test_vectors::{self, AesType},
+            FF1Aes192,
+        };
+
+        for tv in test_vectors::get().filter(|tv| tv.aes == AesType::AES192) {
+            // The key length is checked at compile time.
+            let key: [u8; 24] = tv.key.as_slice().try_into().unwrap();
+            let ff = FF1Aes192::new_from_key(&key.into(), tv.radix).unwrap();
+            let ct = ff
+                .encrypt(&tv.tweak, &FlexibleNumeralString::from(tv.pt.clone()))
+                .unwrap();
+            assert_eq!(Vec::from(ct), tv.ct);
+        }
+    }
+
+    #[test]
+    fn radix_constructors() {
+        use aes::{Aes128, Aes192};
+
+        use super::test_vectors::{self, AesType};
+
+        fn check<CIPH: cipher::BlockCipher + cipher::BlockEncrypt + Clone + cipher::KeyInit>(
+            tv: &test_vectors::TestVector,
+        ) {
+            let ff = match tv.radix {
+                2 => FF1::<CIPH>::for_binary(&tv.key),
+                10 => FF1::<CIPH>::for_decimal(&tv.key),
+                36 => FF1::<CIPH>::for_alphanumeric(&tv.key),
+                r => panic!("no constructor for radix {}", r),
+            }
+            .unwrap();
+            let new = FF1::<CIPH>::new(&tv.key, tv.radix).unwrap();
+            assert!(ff.radix_info() == new.radix_info());
+            let pt = FlexibleNumeralString::from(tv.pt.clone());
+            let ct = Vec::from(ff.encrypt(&tv.tweak, &pt).unwrap());
+            assert_eq!(ct, Vec::from(new.encrypt(&tv.tweak, &pt).unwrap()));
+            assert_eq!(ct, tv.ct);
+        }
+
+        for tv in test_vectors::get() {
+            match tv.aes {
+                AesType::AES128 => check::<Aes128>(&tv),
+                AesType::AES192 => check::<Aes192>(&tv),
+                AesType::AES256 => check::<Aes256>(&tv),
+            }
+        }
+
+        let key = [0; 32];
+        for (ff, radix) in [
+            (FF1::<Aes256>::for_hex(&key).unwrap(), 16),
+            (FF1::<Aes256>::for_bytes(&key).unwrap(), 256),
+        ] {
+            assert_eq!(ff.radix_info().value(), radix);
+        }
+    }
+
+    #[cfg(feature = "sm4")]
+    #[test]
+    fn sm4() {
+        use super::FF1Sm4;
+
+        let ff = FF1Sm4::new(&[0x2b; 16], 10).unwrap();
+        let pt = FlexibleNumeralString::from(vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        let ct = ff.encrypt(b"tweak", &pt).unwrap();
+        let ct = Vec::from(ct);
+        assert_ne!(ct, [0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+
+        // SM4 and AES-128 give different ciphertexts for the same key.
+        let aes = FF1::<aes::Aes128>::new(&[0x2b; 16], 10).unwrap();
+        assert_ne!(Vec::from(aes.encrypt(b"tweak", &pt).unwrap()), ct);
+
+        let ct = FlexibleNumeralString::from(ct);
+        assert_eq!(Vec::from(ff.decrypt(b"tweak", &ct).unwrap()), Vec::from(pt));
+    }
+
+    #[cfg(feature = "camellia")]
+    #[test]
+    fn camellia() {
+        use super::{FF1Camellia128, FF1Camellia256};
+
+        let pt = FlexibleNumeralString::from(vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+
+        let ff = FF1Camellia128::new(&[0x2b; 16], 10).unwrap();
+        let ct = Vec::from(ff.encrypt(b"tweak", &pt).unwrap());
+        let aes = FF1::<aes::Aes128>::new(&[0x2b; 16], 10).unwrap();
+        assert_ne!(Vec::from(aes.encrypt(b"tweak", &pt).unwrap()), ct);
+        let ct = FlexibleNumeralString::from(ct);
+        assert_eq!(Vec::from(ff.decrypt(b"tweak", &ct).unwrap()), pt.0);
+
+        let ff = FF1Camellia256::new(&[0x2b; 32], 10).unwrap();
+        let ct = ff.encrypt(b"tweak", &pt).unwrap();
+        assert_eq!(Vec::from(ff.decrypt(b"tweak", &ct).unwrap()), pt.0);
+    }
+
+    #[cfg(feature = "zeroize")]
+    #[test]
+    fn new_with_secret_key() {
+        use cipher::zeroize::ZeroizeOnDrop;
+        use static_assertions::assert_impl_all;
+
+        use crate::SecretKey;
+
+        assert_impl_all!(FF1<Aes256>: ZeroizeOnDrop);
+        assert_impl_all!(SecretKey<32>: ZeroizeOnDrop);
+
+        let key = [7; 32];
+        let pt = FlexibleNumeralString::from(vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        let ff = FF1::<Aes256>::new(&key, 10).unwrap();
+        let ff_secret = FF1::<Aes256>::new_with_secret_key(&SecretKey::new(key), 10).unwrap();
+        assert_eq!(
+            Vec::from(ff_secret.encrypt(&[], &pt).unwrap()),
+            Vec::from(ff.encrypt(&[], &pt).unwrap()),
+        );
+        let ff_aes =
+            FF1::<Aes256>::new_with_secret_key(&SecretKey::from_aes_bytes(&key), 10).unwrap();
+        assert_eq!(
+            Vec::from(ff_aes.encrypt(&[], &pt).unwrap()),
+            Vec::from(ff.encrypt(&[], &pt).unwrap()),
+        );
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn generate_secret_key() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        use crate::SecretKey;
+
+        let mut rng = StdRng::seed_from_u64(0);
+        let k1 = SecretKey::<32>::generate(&mut rng);
+        let k2 = SecretKey::<32>::generate(&mut rng);
+        assert_ne!(k1.as_bytes(), k2.as_bytes());
+        assert_ne!(k1.as_bytes(), &[0; 32]);
+
+        let pt = FlexibleNumeralString::from(vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        let ff = FF1::<Aes256>::new_with_secret_key(&k1, 10).unwrap();
+        let ct = ff.encrypt(&[], &pt).unwrap();
+        assert_eq!(Vec::from(ff.decrypt(&[], &ct).unwrap()), Vec::from(pt));
+    }
+
+    #[cfg(all(feature = "rand", feature = "aes"))]
+    #[test]
+    fn new_from_rng() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let pt = FlexibleNumeralString::from(vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        let (ff, key) = FF1::<Aes256>::new_from_rng(&mut StdRng::seed_from_u64(0), 10).unwrap();
+        let ct = Vec::from(ff.encrypt(&[], &pt).unwrap());
+
+        // The returned key is the key that was generated.
+        let expected = crate::SecretKey::<32>::generate(&mut StdRng::seed_from_u64(0));
+        assert_eq!(key.as_bytes(), expected.as_bytes());
+        let ff_key = FF1::<Aes256>::new_with_secret_key(&key, 10).unwrap();
+        assert_eq!(Vec::from(ff_key.encrypt(&[], &pt).unwrap()), ct);
+
+        let (other, _) = FF1::<Aes256>::new_from_rng(&mut StdRng::seed_from_u64(1), 10).unwrap();
+        assert_ne!(Vec::from(other.encrypt(&[], &pt).unwrap()), ct);
+
+        assert!(FF1::<Aes256>::new_from_rng(&mut StdRng::seed_from_u64(0), 1).is_err());
+    }
+
+    #[cfg(feature = "os-rng")]
+    #[test]
+    fn new_ephemeral() {
+        let pt = FlexibleNumeralString::from(vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        let ff = FF1::<Aes256>::new_ephemeral(10).unwrap();
+        let ct = ff.encrypt(&[], &pt).unwrap();
+        assert_eq!(Vec::from(ff.decrypt(&[], &ct).unwrap()), Vec::from(pt));
+        assert!(FF1::<Aes256>::new_ephemeral(1).is_err());
+    }
+
+    #[test]
+    fn numeral_count_bounds() {
+        let ff = FF1::<Aes256>::new(&[0; 32], 10).unwrap();
+        assert_eq!(ff.min_numeral_count(), 6);
+        assert_eq!(ff.max_numeral_count(), u32::MAX as usize);
+        assert!(!ff.is_valid_length(5));
+        assert!(ff.is_valid_length(6));
+
+        let ff = FF1::<Aes256>::new(&[0; 32], 2).unwrap();
+        assert_eq!(ff.min_numeral_count(), MIN_RADIX_2_NS_LEN as usize);
+
+        let info = ff.radix_info();
+        assert_eq!(info.value(), 2);
+        assert_eq!(info.min_len(), MIN_RADIX_2_NS_LEN as usize);
+        assert!(info.is_power_of_two());
+        assert_eq!(info.calculate_b(20), 3);
+
+        let info = RadixInfo::new(10).unwrap();
+        assert!(!info.is_power_of_two());
+        assert_eq!(info.calculate_b(6), 3);
+        assert_eq!(info.calculate_b(13), 6);
+        assert_eq!(
+            info.check_ns_length(5),
+            Err(NumeralStringError::TooShort {
+                ns_len: 5,
+                min_len: 6
+            })
+        );
+        assert!(info.check_ns_length(6).is_ok());
+        assert!(RadixInfo::new(1).is_err());
+
+        assert_eq!(InvalidRadix::min_valid_radix(), 2);
+        assert_eq!(InvalidRadix::max_valid_radix(), 1 << 16);
+        assert!(Radix::from_u32(InvalidRadix::max_valid_radix()).is_ok());
+        assert!(Radix::from_u32(InvalidRadix::max_valid_radix() + 1).is_err());
+    }
+
     #[test]
     fn radix() {
         assert_eq!(Radix::from_u32(1), Err(InvalidRadix(1)));
@@ -657,4 +2504,19 @@ mod tests {
         );
         assert_eq!(Radix::from_u32(65537), Err(InvalidRadix(65537)));
     }
+
+    #[test]
+    fn calculate_b_int_matches_float() {
+        for radix in (3..MAX_RADIX).filter(|&r| !r.is_power_of_two()) {
+            for v in [1, 2, 3, 7, 10, 100, 1000, 100_000] {
+                assert_eq!(
+                    calculate_b_int(radix, v),
+                    calculate_b_float(radix, v),
+                    "radix {}, v {}",
+                    radix,
+                    v,
+                );
+            }
+        }
+    }
 }
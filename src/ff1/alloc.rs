@@ -1,8 +1,15 @@
 //! FF1 NumeralString implementations that require a global allocator.
 
-use core::iter;
+use core::{
+    cmp,
+    fmt::{self, Write},
+    iter,
+    ops::{Index, IndexMut, Range},
+    slice,
+    str::FromStr,
+};
 
-use alloc::{vec, vec::Vec};
+use alloc::{collections::VecDeque, string::String, vec, vec::Vec};
 
 use num_bigint::{BigInt, BigUint, Sign};
 use num_traits::{
@@ -10,7 +17,12 @@ use num_traits::{
     ToPrimitive,
 };
 
-use super::{NumeralString, Operations};
+use cipher::{BlockCipher, BlockEncrypt};
+
+use super::{
+    digits, InspectableOps, NumeralString, NumeralStringError, NumeralStringMut, Operations,
+    SliceableNumeralString, FF1, MAX_RADIX, MIN_RADIX,
+};
 
 fn pow(x: u32, e: usize) -> BigUint {
     let mut res = BigUint::one();
@@ -78,8 +90,9 @@ impl Numeral for BigUint {
 }
 
 /// A numeral string that supports radixes in [2..2^16).
-#[cfg_attr(test, derive(Debug))]
-pub struct FlexibleNumeralString(Vec<u16>);
+#[derive(PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(any(test, feature = "proptest"), derive(Debug))]
+pub struct FlexibleNumeralString(pub(super) Vec<u16>);
 
 impl From<Vec<u16>> for FlexibleNumeralString {
     fn from(v: Vec<u16>) -> Self {
@@ -93,11 +106,147 @@ impl From<FlexibleNumeralString> for Vec<u16> {
     }
 }
 
+impl iter::FromIterator<u16> for FlexibleNumeralString {
+    fn from_iter<I: IntoIterator<Item = u16>>(iter: I) -> Self {
+        FlexibleNumeralString(iter.into_iter().collect())
+    }
+}
+
+impl IntoIterator for FlexibleNumeralString {
+    type Item = u16;
+    type IntoIter = vec::IntoIter<u16>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a FlexibleNumeralString {
+    type Item = u16;
+    type IntoIter = iter::Copied<slice::Iter<'a, u16>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a mut FlexibleNumeralString {
+    type Item = &'a mut u16;
+    type IntoIter = slice::IterMut<'a, u16>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl Index<usize> for FlexibleNumeralString {
+    type Output = u16;
+
+    fn index(&self, i: usize) -> &u16 {
+        &self.0[i]
+    }
+}
+
+impl IndexMut<usize> for FlexibleNumeralString {
+    fn index_mut(&mut self, i: usize) -> &mut u16 {
+        &mut self.0[i]
+    }
+}
+
+/// Parses a radix-10 numeral string, as [`FlexibleNumeralString::from_decimal_str`].
+impl TryFrom<&str> for FlexibleNumeralString {
+    type Error = NumeralStringError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        FlexibleNumeralString::from_decimal_str(s)
+    }
+}
+
+/// Parses a radix-10 numeral string, as [`FlexibleNumeralString::from_decimal_str`].
+///
+/// A string carries no radix, so other radixes must be parsed explicitly.
+impl FromStr for FlexibleNumeralString {
+    type Err = NumeralStringError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        FlexibleNumeralString::try_from(s)
+    }
+}
+
+impl FlexibleNumeralString {
+    /// Writes the numerals as digits in the given radix if they are all valid for it,
+    /// and otherwise as a list such as `[1, 20, 3]`.
+    fn fmt_radix(&self, f: &mut fmt::Formatter<'_>, radix: u32, upper: bool) -> fmt::Result {
+        let write_numeral = |f: &mut fmt::Formatter<'_>, n: u16| match (radix, upper) {
+            (16, false) => write!(f, "{:x}", n),
+            (16, true) => write!(f, "{:X}", n),
+            _ => write!(f, "{}", n),
+        };
+
+        if self.0.iter().all(|&n| u32::from(n) < radix) {
+            for &n in &self.0 {
+                write_numeral(f, n)?;
+            }
+            Ok(())
+        } else {
+            f.write_char('[')?;
+            for (i, &n) in self.0.iter().enumerate() {
+                if i > 0 {
+                    f.write_str(", ")?;
+                }
+                write_numeral(f, n)?;
+            }
+            f.write_char(']')
+        }
+    }
+}
+
+/// Formats a radix-10 numeral string as its decimal digits.
+///
+/// Numeral strings with any numeral of 10 or more are formatted as a list of numerals,
+/// such as `[1, 20, 3]`.
+impl fmt::Display for FlexibleNumeralString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_radix(f, 10, false)
+    }
+}
+
+/// Formats a radix-16 numeral string as its lowercase hexadecimal digits.
+///
+/// Numeral strings with any numeral of 16 or more are formatted as a list of numerals
+/// in hexadecimal, such as `[1, 2a, 3]`.
+impl fmt::LowerHex for FlexibleNumeralString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_radix(f, 16, false)
+    }
+}
+
+/// Formats a radix-16 numeral string as its uppercase hexadecimal digits.
+///
+/// Numeral strings with any numeral of 16 or more are formatted as a list of numerals
+/// in hexadecimal, such as `[1, 2A, 3]`.
+impl fmt::UpperHex for FlexibleNumeralString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_radix(f, 16, true)
+    }
+}
+
+/// Compares the numerals in constant time.
+///
+/// Numeral strings of different lengths compare unequal immediately, as the length is
+/// not secret.
+#[cfg(feature = "subtle")]
+impl subtle::ConstantTimeEq for FlexibleNumeralString {
+    fn ct_eq(&self, other: &Self) -> subtle::Choice {
+        self.0.ct_eq(&other.0)
+    }
+}
+
 impl NumeralString for FlexibleNumeralString {
     type Ops = Self;
 
     fn is_valid(&self, radix: u32) -> bool {
-        self.0.iter().all(|n| (u32::from(*n) < radix))
+        self.validate(radix).is_ok()
     }
 
     fn numeral_count(&self) -> usize {
@@ -114,6 +263,69 @@ impl NumeralString for FlexibleNumeralString {
         a.0.append(&mut b.0);
         a
     }
+
+    fn reversed(&self) -> Self {
+        FlexibleNumeralString(self.0.iter().rev().copied().collect())
+    }
+}
+
+impl NumeralStringMut for FlexibleNumeralString {
+    fn replace_with(&mut self, a: Self, b: Self) {
+        self.0.clear();
+        self.0.extend_from_slice(&a.0);
+        self.0.extend_from_slice(&b.0);
+    }
+}
+
+/// A numeral string held in a `VecDeque`, for example one that is assembled at both
+/// ends.
+///
+/// Each half is copied into a [`FlexibleNumeralString`] for the FF1 computations, as
+/// `split` only borrows the numeral string.
+impl NumeralString for VecDeque<u16> {
+    type Ops = FlexibleNumeralString;
+
+    fn is_valid(&self, radix: u32) -> bool {
+        self.iter().all(|n| (u32::from(*n) < radix))
+    }
+
+    fn numeral_count(&self) -> usize {
+        self.len()
+    }
+
+    fn split(&self) -> (Self::Ops, Self::Ops) {
+        let u = self.len() / 2;
+        (
+            self.range(..u).copied().collect(),
+            self.range(u..).copied().collect(),
+        )
+    }
+
+    fn concat(a: Self::Ops, b: Self::Ops) -> Self {
+        a.0.into_iter().chain(b.0).collect()
+    }
+
+    fn reversed(&self) -> Self {
+        self.iter().rev().copied().collect()
+    }
+}
+
+impl NumeralStringMut for VecDeque<u16> {
+    fn replace_with(&mut self, a: Self::Ops, b: Self::Ops) {
+        self.clear();
+        self.extend(a.0);
+        self.extend(b.0);
+    }
+}
+
+impl SliceableNumeralString for FlexibleNumeralString {
+    fn slice(&self, range: Range<usize>) -> Self {
+        FlexibleNumeralString(self.0[range].to_vec())
+    }
+
+    fn splice(&mut self, range: Range<usize>, replacement: Self) {
+        self.0[range].copy_from_slice(&replacement.0);
+    }
 }
 
 impl Operations for FlexibleNumeralString {
@@ -140,7 +352,63 @@ impl Operations for FlexibleNumeralString {
     }
 }
 
+impl InspectableOps for FlexibleNumeralString {
+    type Numeral = u16;
+
+    fn to_numerals(&self) -> &[u16] {
+        &self.0
+    }
+}
+
 impl FlexibleNumeralString {
+    /// Creates a numeral string from the given numerals, checking that each is less
+    /// than `radix`.
+    ///
+    /// Returns [`NumeralStringError::InvalidNumeral`] for the first numeral that is not
+    /// in the given radix. The conversion from `Vec<u16>` performs no such check.
+    pub fn from_numerals(numerals: Vec<u16>, radix: u32) -> Result<Self, NumeralStringError> {
+        let ns = FlexibleNumeralString(numerals);
+        ns.validate(radix)?;
+        Ok(ns)
+    }
+
+    /// Checks that every numeral is less than `radix`.
+    ///
+    /// Returns [`NumeralStringError::InvalidNumeral`] for the first numeral that is not.
+    pub fn validate(&self, radix: u32) -> Result<(), NumeralStringError> {
+        match self.0.iter().position(|&n| u32::from(n) >= radix) {
+            None => Ok(()),
+            Some(position) => Err(NumeralStringError::InvalidNumeral {
+                position,
+                value: self.0[position].into(),
+                radix,
+            }),
+        }
+    }
+
+    /// Returns the numerals, from the most significant.
+    pub fn as_numerals(&self) -> &[u16] {
+        &self.0
+    }
+
+    /// Consumes the numeral string, returning its numerals.
+    pub fn into_numerals(self) -> Vec<u16> {
+        self.0
+    }
+
+    /// Returns an iterator over the numerals, from the most significant.
+    pub fn iter(&self) -> iter::Copied<slice::Iter<'_, u16>> {
+        self.0.iter().copied()
+    }
+
+    /// Returns an iterator that allows modifying each numeral.
+    ///
+    /// The numerals are not checked against any radix until the numeral string is
+    /// encrypted or decrypted.
+    pub fn iter_mut(&mut self) -> slice::IterMut<'_, u16> {
+        self.0.iter_mut()
+    }
+
     /// numeral string to bigunit
     /// 
     /// returns BigUint
@@ -163,12 +431,335 @@ impl FlexibleNumeralString {
         }
         FlexibleNumeralString(res)
     }
+
+    /// Returns $STR^{len}_{radix}(n)$, the numeral string of `len` numerals that
+    /// represents `n`, padded with leading zeros.
+    ///
+    /// This is the inverse of [`FlexibleNumeralString::num_radix`]. Unlike
+    /// [`FlexibleNumeralString::str_radix`], it returns
+    /// [`NumeralStringError::TooLong`] if `n` needs more than `len` numerals, instead
+    /// of truncating it.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `radix` is less than 2.
+    pub fn from_biguint(n: &BigUint, radix: u32, len: usize) -> Result<Self, NumeralStringError> {
+        assert!(radix >= 2, "radix must be at least 2");
+        let mut res = Vec::with_capacity(len);
+        let mut x = n.clone();
+        while !x.is_zero() {
+            res.push((&x % radix).to_u16().unwrap());
+            x /= radix;
+        }
+        if res.len() > len {
+            return Err(NumeralStringError::TooLong {
+                ns_len: res.len(),
+                max_len: len,
+            });
+        }
+        res.resize(len, 0);
+        res.reverse();
+        Ok(FlexibleNumeralString(res))
+    }
+
+    /// Returns the numeral string of `len` zeros, which represents zero in any radix.
+    pub fn zero(len: usize) -> Self {
+        FlexibleNumeralString(vec![0; len])
+    }
+
+    /// Returns the numeral string of `len` numerals that represents one in any radix.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `len` is zero.
+    pub fn one(len: usize) -> Self {
+        assert!(len > 0, "one cannot be represented with zero numerals");
+        let mut ns = Self::zero(len);
+        ns.0[len - 1] = 1;
+        ns
+    }
+
+    /// Returns the numeral string of `len` numerals that represents `radix^len - 1`,
+    /// the largest value of that length.
+    pub fn max_value(radix: u32, len: usize) -> Self {
+        FlexibleNumeralString(vec![(radix - 1) as u16; len])
+    }
+
+    /// Returns `true` if this numeral string represents zero.
+    pub fn is_zero(&self) -> bool {
+        self.0.iter().all(|&n| n == 0)
+    }
+
+    /// Computes `(self + other) mod radix^len`, where `len` is the length of both
+    /// numeral strings.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the numeral strings have different lengths.
+    pub fn wrapping_add(&self, other: &Self, radix: u32) -> Self {
+        let mut res = self.0.clone();
+        digits::add_mod(&mut res, &other.0, radix);
+        FlexibleNumeralString(res)
+    }
+
+    /// Computes `(self - other) mod radix^len`, where `len` is the length of both
+    /// numeral strings.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the numeral strings have different lengths.
+    pub fn wrapping_sub(&self, other: &Self, radix: u32) -> Self {
+        let mut res = self.0.clone();
+        digits::sub_mod(&mut res, &other.0, radix);
+        FlexibleNumeralString(res)
+    }
+
+    /// Returns this numeral string with `fill` prepended until it has `target_len`
+    /// numerals.
+    ///
+    /// Returns a copy if the numeral string already has at least `target_len`
+    /// numerals.
+    pub fn pad_left(&self, target_len: usize, fill: u16) -> Self {
+        let pad = target_len.saturating_sub(self.0.len());
+        FlexibleNumeralString(
+            iter::repeat(fill)
+                .take(pad)
+                .chain(self.0.iter().copied())
+                .collect(),
+        )
+    }
+
+    /// Returns this numeral string with `fill` appended until it has `target_len`
+    /// numerals.
+    ///
+    /// Returns a copy if the numeral string already has at least `target_len`
+    /// numerals.
+    pub fn pad_right(&self, target_len: usize, fill: u16) -> Self {
+        let mut res = self.0.clone();
+        if res.len() < target_len {
+            res.resize(target_len, fill);
+        }
+        FlexibleNumeralString(res)
+    }
+
+    /// Returns this numeral string with leading zeros prepended until it is long
+    /// enough to be encrypted with `ff`.
+    ///
+    /// Leading zeros do not change the value of the numeral string, and can be removed
+    /// after decryption with [`FlexibleNumeralString::unpad_left`]. Returns an error if
+    /// the numeral string is not valid for the radix of `ff`.
+    pub fn padded_to_min<CIPH: BlockCipher + BlockEncrypt + Clone>(
+        &self,
+        ff: &FF1<CIPH>,
+    ) -> Result<Self, NumeralStringError> {
+        let radix = ff.radix.to_u32();
+        if !self.is_valid(radix) {
+            return Err(NumeralStringError::InvalidForRadix(radix));
+        }
+        Ok(self.pad_left(ff.min_numeral_count(), 0))
+    }
+
+    /// Returns this numeral string with its leading zeros removed.
+    ///
+    /// This reverses [`FlexibleNumeralString::padded_to_min`] only if the original
+    /// numeral string did not itself start with a zero.
+    pub fn unpad_left(&self) -> Self {
+        let start = self.0.iter().position(|&n| n != 0).unwrap_or(self.0.len());
+        FlexibleNumeralString(self.0[start..].to_vec())
+    }
+
+    /// Splits this numeral string into its first `n` numerals and the remaining
+    /// `len - n` numerals, such as the BIN and account number of a PAN.
+    ///
+    /// This is the fallible form of [`SliceableNumeralString::split_at`]. Returns
+    /// [`NumeralStringError::InvalidRange`] if `n` is greater than the number of
+    /// numerals.
+    pub fn try_split_at(&self, n: usize) -> Result<(Self, Self), NumeralStringError> {
+        if n > self.0.len() {
+            return Err(NumeralStringError::InvalidRange {
+                start: 0,
+                end: n,
+                ns_len: self.0.len(),
+            });
+        }
+        let (a, b) = self.0.split_at(n);
+        Ok((
+            FlexibleNumeralString(a.to_vec()),
+            FlexibleNumeralString(b.to_vec()),
+        ))
+    }
+
+    /// Joins two numeral strings, reversing [`FlexibleNumeralString::try_split_at`].
+    pub fn join(a: Self, b: Self) -> Self {
+        let mut res = a.0;
+        res.extend_from_slice(&b.0);
+        FlexibleNumeralString(res)
+    }
+
+    /// Creates a radix-10 numeral string from a string of ASCII decimal digits.
+    ///
+    /// Returns [`NumeralStringError::InvalidNumeral`] for the first character that is
+    /// not in `'0'..='9'`.
+    pub fn from_decimal_str(s: &str) -> Result<Self, NumeralStringError> {
+        s.chars()
+            .enumerate()
+            .map(|(position, c)| match c.to_digit(10) {
+                Some(d) => Ok(d as u16),
+                None => Err(NumeralStringError::InvalidNumeral {
+                    position,
+                    value: c.into(),
+                    radix: 10,
+                }),
+            })
+            .collect::<Result<_, _>>()
+            .map(FlexibleNumeralString)
+    }
+
+    /// Returns the digits of this numeral string in the given radix, using `'0'..='9'`
+    /// followed by `'a'..='z'`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `radix` is not in the range 2 to 36, or if this numeral string is not
+    /// valid for `radix`.
+    pub fn to_string_radix(&self, radix: u32) -> String {
+        assert!(
+            (2..=36).contains(&radix),
+            "radix {} is not in the range 2..=36",
+            radix
+        );
+        self.0
+            .iter()
+            .map(|&n| {
+                char::from_digit(u32::from(n), radix).expect("numeral is not valid for the radix")
+            })
+            .collect()
+    }
+
+    /// Returns the string of ASCII decimal digits for this radix-10 numeral string.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this numeral string is not valid for radix 10.
+    pub fn to_decimal_str(&self) -> String {
+        self.0
+            .iter()
+            .map(|&n| char::from_digit(u32::from(n), 10).expect("numeral is not a decimal digit"))
+            .collect()
+    }
+
+    /// Returns the numeral string in the given radix that represents `bytes` as a
+    /// big-endian integer.
+    ///
+    /// The length of the numeral string depends only on the number of bytes: it is the
+    /// smallest length that can represent every byte string of that length, which is
+    /// $\lceil 8 \cdot len(bytes) / LOG(radix) \rceil$. Leading zero bytes are
+    /// therefore preserved by [`FlexibleNumeralString::to_bytes_with_radix`].
+    ///
+    /// Returns an error if `radix` is not in [2..2^16].
+    pub fn from_bytes_with_radix(bytes: &[u8], radix: u32) -> Result<Self, NumeralStringError> {
+        if !(MIN_RADIX..=MAX_RADIX).contains(&radix) {
+            return Err(NumeralStringError::InvalidForRadix(radix));
+        }
+        let bits = 8 * bytes.len() as u64;
+        let mut domain = BigUint::one();
+        let mut len = 0;
+        while domain.bits() <= bits {
+            domain *= radix;
+            len += 1;
+        }
+        Self::from_biguint(&BigUint::from_bytes_be(bytes), radix, len)
+    }
+
+    /// Returns the big-endian bytes of the integer that this numeral string represents
+    /// in the given radix.
+    ///
+    /// This is the inverse of [`FlexibleNumeralString::from_bytes_with_radix`]. The
+    /// number of bytes is the largest that the numeral string's length can represent,
+    /// and may be zero. To encrypt bytes and store them in another radix, encrypt them
+    /// as radix 256 and then convert the ciphertext; the converse does not round-trip,
+    /// because a ciphertext may represent a larger integer than its plaintext.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this numeral string is not valid for `radix`, or if it represents an
+    /// integer too large for that number of bytes.
+    pub fn to_bytes_with_radix(&self, radix: u32) -> Vec<u8> {
+        assert!(
+            self.is_valid(radix),
+            "numeral string is not valid for the radix"
+        );
+        // The largest `k` with 256^k <= radix^n.
+        let byte_len = ((pow(radix, self.0.len()).bits() - 1) / 8) as usize;
+        let value = self.num_radix(radix);
+        assert!(
+            value.bits() <= 8 * byte_len as u64,
+            "numeral string does not fit in {} bytes",
+            byte_len
+        );
+
+        let mut bytes = vec![0; byte_len];
+        if !value.is_zero() {
+            let value = value.to_bytes_be();
+            bytes[byte_len - value.len()..].copy_from_slice(&value);
+        }
+        bytes
+    }
+}
+
+/// An iterator over the numerals of an encrypted or decrypted numeral string.
+///
+/// Returned by [`FF1::encrypt_iter`](super::FF1::encrypt_iter) and
+/// [`FF1::decrypt_iter`](super::FF1::decrypt_iter).
+pub struct NumeralStringIter {
+    inner: iter::Chain<vec::IntoIter<u16>, vec::IntoIter<u16>>,
+}
+
+impl NumeralStringIter {
+    pub(super) fn new(a: FlexibleNumeralString, b: FlexibleNumeralString) -> Self {
+        NumeralStringIter {
+            inner: a.0.into_iter().chain(b.0),
+        }
+    }
+}
+
+impl Iterator for NumeralStringIter {
+    type Item = u16;
+
+    fn next(&mut self) -> Option<u16> {
+        self.inner.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
 }
 
 /// A numeral string with radix 2.
-#[cfg_attr(test, derive(Debug))]
+///
+/// Numeral strings are ordered lexicographically by their numerals, so numeral strings
+/// of the same length are ordered by the integers they represent.
+#[derive(PartialEq, Eq, Hash)]
+#[cfg_attr(any(test, feature = "proptest"), derive(Debug))]
 pub struct BinaryNumeralString(Vec<u8>);
 
+impl PartialOrd for BinaryNumeralString {
+    fn partial_cmp(&self, other: &Self) -> Option<cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for BinaryNumeralString {
+    fn cmp(&self, other: &Self) -> cmp::Ordering {
+        // Each byte holds its first numeral in the least significant bit, so comparing
+        // the bytes directly would not match the order of the numerals.
+        self.0
+            .iter()
+            .map(|b| b.reverse_bits())
+            .cmp(other.0.iter().map(|b| b.reverse_bits()))
+    }
+}
+
 impl BinaryNumeralString {
     /// Creates a BinaryNumeralString from a byte slice, with each byte
     /// interpreted in little-endian bit order.
@@ -176,11 +767,115 @@ impl BinaryNumeralString {
         BinaryNumeralString(s.to_vec())
     }
 
-    /// Returns a Vec<u8>, with each byte written from the BinaryNumeralString
+    /// Returns a `Vec<u8>`, with each byte written from the BinaryNumeralString
     /// in little-endian bit order.
     pub fn to_bytes_le(&self) -> Vec<u8> {
         self.0.to_vec()
     }
+
+    /// Creates a BinaryNumeralString from a byte slice, with each byte
+    /// interpreted in big-endian bit order.
+    ///
+    /// The numerals are then the bits of `s` in reading order, most significant bit
+    /// of each byte first.
+    pub fn from_bytes_be(s: &[u8]) -> Self {
+        BinaryNumeralString(s.iter().map(|b| b.reverse_bits()).collect())
+    }
+
+    /// Returns a `Vec<u8>`, with each byte written from the BinaryNumeralString
+    /// in big-endian bit order.
+    pub fn to_bytes_be(&self) -> Vec<u8> {
+        self.bytes_be().collect()
+    }
+
+    fn bytes_be(&self) -> impl Iterator<Item = u8> + '_ {
+        self.0.iter().map(|b| b.reverse_bits())
+    }
+
+    /// Creates a BinaryNumeralString from a hexadecimal string, with each byte
+    /// interpreted in big-endian bit order, as [`BinaryNumeralString::from_bytes_be`].
+    ///
+    /// For example, `"deadbeef"` is the 32-bit numeral string
+    /// `11011110101011011011111011101111`. Both uppercase and lowercase digits are
+    /// accepted. Returns [`NumeralStringError::InvalidNumeral`] for the first character
+    /// that is not a hexadecimal digit, or [`NumeralStringError::InvalidForRadix`] if
+    /// the string has an odd number of digits.
+    pub fn from_hex(s: &str) -> Result<Self, NumeralStringError> {
+        let digits = s
+            .chars()
+            .enumerate()
+            .map(|(position, c)| match c.to_digit(16) {
+                Some(d) => Ok(d as u8),
+                None => Err(NumeralStringError::InvalidNumeral {
+                    position,
+                    value: c.into(),
+                    radix: 16,
+                }),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if digits.len() % 2 != 0 {
+            return Err(NumeralStringError::InvalidForRadix(16));
+        }
+        Ok(BinaryNumeralString(
+            digits
+                .chunks(2)
+                .map(|pair| (pair[0] << 4 | pair[1]).reverse_bits())
+                .collect(),
+        ))
+    }
+
+    /// Returns the lowercase hexadecimal string for this numeral string, with each byte
+    /// written in big-endian bit order, as [`BinaryNumeralString::to_bytes_be`].
+    pub fn to_hex(&self) -> String {
+        const CHARS: &[u8; 16] = b"0123456789abcdef";
+        self.bytes_be()
+            .flat_map(|b| [CHARS[usize::from(b >> 4)], CHARS[usize::from(b & 0x0f)]])
+            .map(char::from)
+            .collect()
+    }
+}
+
+/// Formats the numerals as `0b` followed by the bits, in numeral order.
+impl fmt::Display for BinaryNumeralString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("0b")?;
+        for b in self.bytes_be() {
+            write!(f, "{:08b}", b)?;
+        }
+        Ok(())
+    }
+}
+
+/// Formats the numerals as lowercase hexadecimal, as [`BinaryNumeralString::to_hex`].
+impl fmt::LowerHex for BinaryNumeralString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for b in self.bytes_be() {
+            write!(f, "{:02x}", b)?;
+        }
+        Ok(())
+    }
+}
+
+/// Formats the numerals as uppercase hexadecimal, with each byte in big-endian bit
+/// order.
+impl fmt::UpperHex for BinaryNumeralString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for b in self.bytes_be() {
+            write!(f, "{:02X}", b)?;
+        }
+        Ok(())
+    }
+}
+
+/// Compares the numerals in constant time.
+///
+/// Numeral strings of different lengths compare unequal immediately, as the length is
+/// not secret.
+#[cfg(feature = "subtle")]
+impl subtle::ConstantTimeEq for BinaryNumeralString {
+    fn ct_eq(&self, other: &Self) -> subtle::Choice {
+        self.0.ct_eq(&other.0)
+    }
 }
 
 impl NumeralString for BinaryNumeralString {
@@ -294,18 +989,35 @@ impl NumeralString for BinaryNumeralString {
     }
 
     fn concat(a: Self::Ops, b: Self::Ops) -> Self {
+        let mut data = Vec::new();
+        Self::concat_into(a, b, &mut data);
+        BinaryNumeralString(data)
+    }
+
+    fn reversed(&self) -> Self {
+        // Each byte holds 8 numerals in little-endian bit order, so reversing the bit
+        // string means reversing both the byte order and the bits within each byte.
+        BinaryNumeralString(self.0.iter().rev().map(|b| b.reverse_bits()).collect())
+    }
+}
+
+impl BinaryNumeralString {
+    /// Writes the concatenation of `a` and `b` into `out`, replacing its contents.
+    fn concat_into(a: BinaryOps, b: BinaryOps, out: &mut Vec<u8>) {
         // If you're reading this, you've either already scrolled passed the comment in
         // `Self::split` that explains what we are doing here, or you followed a direct
         // link to this GitHub line. In either case, scroll up if you're confused by what
         // we are doing in this method.
-        BinaryNumeralString(if a.num_bits % 8 == 0 {
+        out.clear();
+        if a.num_bits % 8 == 0 {
             // Simple case: no shifting necessary, just reversing and joining.
-            b.data
-                .into_iter()
-                .chain(a.data.into_iter())
-                .map(|b| b.reverse_bits())
-                .rev()
-                .collect()
+            out.extend(
+                b.data
+                    .into_iter()
+                    .chain(a.data.into_iter())
+                    .map(|b| b.reverse_bits())
+                    .rev(),
+            );
         } else {
             // We need to shift `a` "backward" by 4 bits. We do this by shifting it
             // "forward" by 4 bits before reversing the bytes.
@@ -344,12 +1056,16 @@ impl NumeralString for BinaryNumeralString {
             // use `Iterator::rev` (which only works on known-length iterators). Since we
             // know their concatenation is an integer number of bytes, we perform the
             // byte reversal inside the `Vec` instead.
-            let mut tmp = b_processed.chain(a_processed).collect::<Vec<_>>();
-            tmp.reverse();
-            tmp
-        })
+            out.extend(b_processed.chain(a_processed));
+            out.reverse();
+        }
+    }
+}
+
+impl NumeralStringMut for BinaryNumeralString {
+    fn replace_with(&mut self, a: Self::Ops, b: Self::Ops) {
+        Self::concat_into(a, b, &mut self.0);
     }
-    
 }
 
 pub struct BinaryOps {
@@ -413,10 +1129,13 @@ impl BinaryOps {
 mod tests {
     use aes::{Aes128, Aes192, Aes256};
 
+    use num_bigint::BigUint;
+    use num_traits::Zero;
+
     use super::{BinaryNumeralString, FlexibleNumeralString};
     use crate::ff1::{
         test_vectors::{self, AesType},
-        NumeralString, NumeralStringError, FF1,
+        NumeralString, NumeralStringError, SliceableNumeralString, FF1,
     };
 
     #[test]
@@ -429,6 +1148,396 @@ mod tests {
         assert!(!ns.is_valid(radix));
     }
 
+    #[test]
+    fn decimal_str_round_trip() {
+        let ns = FlexibleNumeralString::from_decimal_str("0123456789").unwrap();
+        assert_eq!(ns.0, vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        assert_eq!(ns.to_decimal_str(), "0123456789");
+
+        assert_eq!(
+            FlexibleNumeralString::from_decimal_str("12a4").unwrap_err(),
+            NumeralStringError::InvalidNumeral {
+                position: 2,
+                value: 'a'.into(),
+                radix: 10,
+            },
+        );
+        assert_eq!(
+            FlexibleNumeralString::from_decimal_str("1\u{e9}a").unwrap_err(),
+            NumeralStringError::InvalidNumeral {
+                position: 1,
+                value: 0xe9,
+                radix: 10,
+            },
+        );
+        assert!(FlexibleNumeralString::from_decimal_str("١٢٣").is_err());
+
+        let ns = FlexibleNumeralString::try_from("123456789").unwrap();
+        assert_eq!(ns.0, vec![1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        assert_eq!(ns.to_string(), "123456789");
+        assert_eq!(
+            "12a4".parse::<FlexibleNumeralString>().unwrap_err(),
+            NumeralStringError::InvalidNumeral {
+                position: 2,
+                value: 'a'.into(),
+                radix: 10,
+            },
+        );
+
+        // Digits are still checked against the FF1 radix.
+        let ff = FF1::<Aes128>::new(&[0; 16], 8).unwrap();
+        let ns = FlexibleNumeralString::from_decimal_str("012345678").unwrap();
+        assert_eq!(
+            ff.encrypt(&[], &ns).unwrap_err(),
+            NumeralStringError::InvalidForRadix(8),
+        );
+
+        let ff = FF1::<Aes128>::new(&[0; 16], 10).unwrap();
+        let ns = FlexibleNumeralString::from_decimal_str("4111111111111111").unwrap();
+        let ct = ff.encrypt(&[], &ns).unwrap();
+        let pt = ff.decrypt(&[], &ct).unwrap();
+        assert_eq!(ct.to_decimal_str().len(), 16);
+        assert_eq!(pt.to_decimal_str(), "4111111111111111");
+    }
+
+    #[cfg(feature = "subtle")]
+    #[test]
+    fn ct_eq() {
+        use subtle::ConstantTimeEq;
+
+        let a = FlexibleNumeralString::from(vec![1, 2, 3, 4]);
+        assert!(bool::from(
+            a.ct_eq(&FlexibleNumeralString::from(vec![1, 2, 3, 4]))
+        ));
+        assert!(!bool::from(
+            a.ct_eq(&FlexibleNumeralString::from(vec![1, 2, 3, 5]))
+        ));
+        assert!(!bool::from(
+            a.ct_eq(&FlexibleNumeralString::from(vec![0, 2, 3, 4]))
+        ));
+        assert!(!bool::from(
+            a.ct_eq(&FlexibleNumeralString::from(vec![1, 2, 3]))
+        ));
+
+        let b = BinaryNumeralString::from_bytes_le(&[0xab, 0xcd]);
+        assert!(bool::from(
+            b.ct_eq(&BinaryNumeralString::from_bytes_le(&[0xab, 0xcd]))
+        ));
+        assert!(!bool::from(
+            b.ct_eq(&BinaryNumeralString::from_bytes_le(&[0xab, 0xcc]))
+        ));
+        assert!(!bool::from(
+            b.ct_eq(&BinaryNumeralString::from_bytes_le(&[0xab]))
+        ));
+    }
+
+    #[test]
+    fn flexible_collection() {
+        let mut ns: FlexibleNumeralString = (0..10).collect();
+        assert_eq!(ns.iter().sum::<u16>(), 45);
+        assert_eq!(ns[3], 3);
+
+        ns[3] = 7;
+        for n in &mut ns {
+            *n = 9 - *n;
+        }
+        assert_eq!(
+            (&ns).into_iter().collect::<Vec<_>>(),
+            [9, 8, 7, 2, 5, 4, 3, 2, 1, 0]
+        );
+        for n in ns.iter_mut().take(2) {
+            *n = 0;
+        }
+        assert_eq!(
+            ns.into_iter().collect::<Vec<_>>(),
+            [0, 0, 7, 2, 5, 4, 3, 2, 1, 0]
+        );
+    }
+
+    #[test]
+    fn binary_ord_hash() {
+        use std::collections::HashSet;
+
+        let set: HashSet<_> = [
+            BinaryNumeralString::from_bytes_be(&[0x12, 0x34]),
+            BinaryNumeralString::from_bytes_be(&[0x12, 0x34]),
+            BinaryNumeralString::from_bytes_le(&[0x48, 0x2c]),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(set.len(), 1);
+
+        // Sorting matches the numeric order of the big-endian values.
+        let mut values = vec![0x01u8, 0x80, 0x02, 0xff, 0x00, 0x7f];
+        let mut sorted: Vec<_> = values
+            .iter()
+            .map(|&b| BinaryNumeralString::from_bytes_be(&[b]))
+            .collect();
+        sorted.sort();
+        values.sort_unstable();
+        assert_eq!(
+            sorted
+                .iter()
+                .map(|ns| ns.to_bytes_be()[0])
+                .collect::<Vec<_>>(),
+            values
+        );
+
+        // Longer numeral strings with an equal prefix are greater.
+        assert!(
+            BinaryNumeralString::from_bytes_be(&[0x80])
+                < BinaryNumeralString::from_bytes_be(&[0x80, 0x00])
+        );
+    }
+
+    #[test]
+    fn formatting() {
+        let ns = FlexibleNumeralString::from(vec![0, 1, 2, 9]);
+        assert_eq!(ns.to_string(), "0129");
+        assert_eq!(format!("{:x}", ns), "0129");
+        assert_eq!(ns.to_string_radix(10), "0129");
+
+        let ns = FlexibleNumeralString::from(vec![0, 10, 15, 1]);
+        assert_eq!(ns.to_string(), "[0, 10, 15, 1]");
+        assert_eq!(format!("{:x}", ns), "0af1");
+        assert_eq!(format!("{:X}", ns), "0AF1");
+        assert_eq!(ns.to_string_radix(16), "0af1");
+        assert_eq!(ns.to_string_radix(36), "0af1");
+
+        let ns = FlexibleNumeralString::from(vec![1, 42, 3]);
+        assert_eq!(format!("{:x}", ns), "[1, 2a, 3]");
+        assert_eq!(format!("{:X}", ns), "[1, 2A, 3]");
+
+        let ns = BinaryNumeralString::from_hex("a50f").unwrap();
+        assert_eq!(ns.to_string(), "0b1010010100001111");
+        assert_eq!(format!("{:x}", ns), "a50f");
+        assert_eq!(format!("{:X}", ns), "A50F");
+        assert_eq!(BinaryNumeralString::from_bytes_le(&[]).to_string(), "0b");
+    }
+
+    #[test]
+    #[should_panic(expected = "numeral is not valid for the radix")]
+    fn to_string_radix_invalid() {
+        FlexibleNumeralString::from(vec![1, 8]).to_string_radix(8);
+    }
+
+    #[test]
+    fn multi_part() {
+        // Encrypts the first and third parts of a numeral string independently.
+        let ff = FF1::<Aes256>::new(&[0; 32], 10).unwrap();
+        let ns = FlexibleNumeralString::from_decimal_str("123456789012345678").unwrap();
+        let (head, tail) = ns.split_at(4);
+        assert_eq!(head.to_decimal_str(), "1234");
+        assert_eq!(tail.to_decimal_str(), "56789012345678");
+
+        let mut parts = ns.split_many(3);
+        parts[0] = ff.encrypt(&[0], &parts[0]).unwrap();
+        parts[2] = ff.encrypt(&[2], &parts[2]).unwrap();
+        let ct = FlexibleNumeralString::concat_many(parts);
+        assert_eq!(&ct.to_decimal_str()[6..12], "789012");
+
+        let mut parts = ct.split_many(3);
+        parts[0] = ff.decrypt(&[0], &parts[0]).unwrap();
+        parts[2] = ff.decrypt(&[2], &parts[2]).unwrap();
+        assert_eq!(FlexibleNumeralString::concat_many(parts), ns);
+    }
+
+    #[test]
+    fn bytes_with_radix() {
+        for radix in [2, 3, 10, 36, 255, 256, 257, 1 << 16] {
+            for bytes in [
+                &[][..],
+                &[0],
+                &[0xff],
+                &[0, 0, 1],
+                &[0x12, 0x34, 0x56, 0x78, 0x9a],
+                &[0xff; 17],
+            ] {
+                let ns = FlexibleNumeralString::from_bytes_with_radix(bytes, radix).unwrap();
+                assert!(ns.is_valid(radix));
+                if radix <= 256 {
+                    assert_eq!(ns.to_bytes_with_radix(radix), bytes);
+                } else {
+                    // Larger numerals can hold several bytes, so the byte length is not
+                    // recoverable and the bytes gain leading zeros.
+                    let decoded = ns.to_bytes_with_radix(radix);
+                    assert!(decoded.len() >= bytes.len());
+                    assert!(decoded[..decoded.len() - bytes.len()]
+                        .iter()
+                        .all(|&b| b == 0));
+                    assert_eq!(decoded[decoded.len() - bytes.len()..], *bytes);
+                }
+            }
+        }
+
+        // Leading zero bytes are preserved.
+        let ns = FlexibleNumeralString::from_bytes_with_radix(&[0, 0, 1], 10).unwrap();
+        assert_eq!(ns.to_decimal_str(), "00000001");
+        assert_eq!(ns.to_bytes_with_radix(10), [0, 0, 1]);
+        assert_eq!(
+            FlexibleNumeralString::from_bytes_with_radix(&[0xff, 0xff], 16)
+                .unwrap()
+                .as_numerals(),
+            [15; 4]
+        );
+
+        assert_eq!(
+            FlexibleNumeralString::from_bytes_with_radix(&[1], 1).unwrap_err(),
+            NumeralStringError::InvalidForRadix(1),
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "numeral string does not fit in 2 bytes")]
+    fn to_bytes_with_radix_too_large() {
+        FlexibleNumeralString::from_decimal_str("65536")
+            .unwrap()
+            .to_bytes_with_radix(10);
+    }
+
+    #[test]
+    fn numerals() {
+        let ns = FlexibleNumeralString::from_numerals(vec![1, 0, 9], 10).unwrap();
+        assert_eq!(ns.as_numerals(), [1, 0, 9]);
+        assert_eq!(ns.into_numerals(), [1, 0, 9]);
+        assert_eq!(
+            FlexibleNumeralString::from_numerals(vec![1, 0, 10, 11], 10).unwrap_err(),
+            NumeralStringError::InvalidNumeral {
+                position: 2,
+                value: 10,
+                radix: 10,
+            },
+        );
+        assert_eq!(
+            FlexibleNumeralString::from(vec![7, 8, 9]).validate(8),
+            Err(NumeralStringError::InvalidNumeral {
+                position: 1,
+                value: 8,
+                radix: 8,
+            }),
+        );
+        assert!(FlexibleNumeralString::from_numerals(vec![], 2).is_ok());
+    }
+
+    #[test]
+    fn padding() {
+        let ns = FlexibleNumeralString::from(vec![4, 2]);
+        assert_eq!(ns.pad_left(5, 0).0, vec![0, 0, 0, 4, 2]);
+        assert_eq!(ns.pad_right(4, 9).0, vec![4, 2, 9, 9]);
+        assert_eq!(ns.pad_left(1, 0).0, vec![4, 2]);
+        assert_eq!(ns.pad_right(2, 0).0, vec![4, 2]);
+        assert_eq!(ns.pad_left(5, 0).unpad_left().0, vec![4, 2]);
+        assert!(FlexibleNumeralString::zero(3).unpad_left().0.is_empty());
+
+        // Radix 10 requires 6 numerals, so that 10^6 >= 1,000,000.
+        let ff = FF1::<Aes128>::new(&[0; 16], 10).unwrap();
+        for len in 1..=7 {
+            let ns = FlexibleNumeralString::from(vec![7; len]);
+            let padded = ns.padded_to_min(&ff).unwrap();
+            assert_eq!(padded.0.len(), len.max(6));
+            assert_eq!(padded.unpad_left().0, ns.0);
+
+            let ct = ff.encrypt(&[], &padded).unwrap();
+            let pt = ff.decrypt(&[], &ct).unwrap();
+            assert_eq!(pt.unpad_left().0, ns.0);
+        }
+        assert!(ff
+            .encrypt(&[], &FlexibleNumeralString::from(vec![7; 5]))
+            .is_err());
+
+        // Leading zeros in the original numeral string are not recovered.
+        let ns = FlexibleNumeralString::from(vec![0, 7]);
+        assert_eq!(ns.padded_to_min(&ff).unwrap().unpad_left().0, vec![7]);
+
+        assert_eq!(
+            FlexibleNumeralString::from(vec![10])
+                .padded_to_min(&ff)
+                .unwrap_err(),
+            NumeralStringError::InvalidForRadix(10),
+        );
+    }
+
+    #[test]
+    fn try_split_at_and_join() {
+        let pan = FlexibleNumeralString::from_decimal_str("4111111111111111").unwrap();
+        let (bin, rest) = pan.try_split_at(6).unwrap();
+        assert_eq!(bin.to_decimal_str(), "411111");
+        let (account, check) = rest.try_split_at(9).unwrap();
+        assert_eq!(account.to_decimal_str(), "111111111");
+        assert_eq!(check.to_decimal_str(), "1");
+
+        let joined = FlexibleNumeralString::join(bin, FlexibleNumeralString::join(account, check));
+        assert_eq!(joined, pan);
+
+        for n in [0, 16] {
+            let (a, b) = pan.try_split_at(n).unwrap();
+            assert_eq!(a.0.len() + b.0.len(), 16);
+            assert_eq!(FlexibleNumeralString::join(a, b), pan);
+        }
+        assert_eq!(
+            pan.try_split_at(17).unwrap_err(),
+            NumeralStringError::InvalidRange {
+                start: 0,
+                end: 17,
+                ns_len: 16,
+            },
+        );
+    }
+
+    #[test]
+    fn vec_deque() {
+        use alloc::collections::VecDeque;
+
+        let ff = FF1::<Aes128>::new(&[0; 16], 10).unwrap();
+        let mut pt: VecDeque<u16> = (1..10).collect();
+        pt.push_front(0);
+
+        let ct = ff.encrypt(b"tweak", &pt).unwrap();
+        assert_eq!(
+            ct.iter().copied().collect::<Vec<_>>(),
+            Vec::from(
+                ff.encrypt(
+                    b"tweak",
+                    &FlexibleNumeralString::from((0..10).collect::<Vec<_>>())
+                )
+                .unwrap()
+            ),
+        );
+        assert_eq!(ff.decrypt(b"tweak", &ct).unwrap(), pt);
+
+        let mut x = pt.clone();
+        ff.encrypt_mut(b"tweak", &mut x).unwrap();
+        assert_eq!(x, ct);
+    }
+
+    #[test]
+    fn biguint_round_trip() {
+        let n = BigUint::from(123456789u64);
+        let ns = FlexibleNumeralString::from_biguint(&n, 10, 9).unwrap();
+        assert_eq!(ns.0, vec![1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        assert_eq!(ns.num_radix(10), n);
+
+        let ns = FlexibleNumeralString::from_biguint(&n, 10, 12).unwrap();
+        assert_eq!(ns.0, vec![0, 0, 0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        assert_eq!(
+            FlexibleNumeralString::from_biguint(&n, 10, 8).unwrap_err(),
+            NumeralStringError::TooLong {
+                ns_len: 9,
+                max_len: 8,
+            },
+        );
+        assert!(FlexibleNumeralString::from_biguint(&BigUint::zero(), 10, 0)
+            .unwrap()
+            .0
+            .is_empty());
+
+        // Radixes above 256 are supported.
+        let n = BigUint::from(u64::MAX);
+        let ns = FlexibleNumeralString::from_biguint(&n, 1 << 16, 4).unwrap();
+        assert_eq!(ns.0, vec![u16::MAX; 4]);
+        assert_eq!(ns.num_radix(1 << 16), n);
+    }
+
     #[test]
     fn radix_2_length_limits() {
         let ff = FF1::<Aes128>::new(&[0; 16], 2).unwrap();
@@ -503,6 +1612,53 @@ mod tests {
             .is_ok());
     }
 
+    #[test]
+    fn arithmetic() {
+        let zero = FlexibleNumeralString::zero(4);
+        assert!(zero.is_zero());
+        assert!(!FlexibleNumeralString::one(4).is_zero());
+        assert_eq!(FlexibleNumeralString::one(4).0, vec![0, 0, 0, 1]);
+
+        // Adding one to the maximum value wraps to zero.
+        let max = FlexibleNumeralString::max_value(10, 4);
+        assert_eq!(max.0, vec![9, 9, 9, 9]);
+        let sum = max.wrapping_add(&FlexibleNumeralString::one(4), 10);
+        assert!(sum.is_zero());
+        assert_eq!(
+            sum.wrapping_sub(&FlexibleNumeralString::one(4), 10).0,
+            max.0,
+        );
+
+        let a = FlexibleNumeralString::from(vec![0, 1, 2, 3]);
+        let b = FlexibleNumeralString::from(vec![0, 0, 9, 9]);
+        assert_eq!(a.wrapping_add(&b, 10).0, vec![0, 2, 2, 2]);
+        assert_eq!(a.wrapping_sub(&b, 10).0, vec![0, 0, 2, 4]);
+        assert_eq!(
+            a.wrapping_add(&b, 10).num_radix(10),
+            a.num_radix(10) + b.num_radix(10),
+        );
+    }
+
+    #[test]
+    fn ordering() {
+        use std::collections::BTreeSet;
+
+        let set: BTreeSet<_> = [vec![1, 2, 4], vec![1, 2, 3], vec![1, 2], vec![1, 2, 3]]
+            .into_iter()
+            .map(FlexibleNumeralString::from)
+            .collect();
+        assert_eq!(
+            set.into_iter().map(Vec::from).collect::<Vec<_>>(),
+            vec![vec![1, 2], vec![1, 2, 3], vec![1, 2, 4]],
+        );
+
+        // Binary numeral strings are ordered by their numerals, not their bytes.
+        assert!(
+            BinaryNumeralString::from_bytes_le(&[0x01, 0xff])
+                > BinaryNumeralString::from_bytes_le(&[0x02, 0x00])
+        );
+    }
+
     #[test]
     fn flexible_split_round_trip() {
         for tv in test_vectors::get() {
@@ -591,4 +1747,63 @@ mod tests {
             assert_eq!(bct.0, tvb.ct);
         }
     }
+
+    #[test]
+    fn binary_hex() {
+        let ns = BinaryNumeralString::from_hex("deadBEEF").unwrap();
+        assert_eq!(ns.to_bytes_be(), [0xde, 0xad, 0xbe, 0xef]);
+        assert_eq!(ns.to_bytes_le(), [0x7b, 0xb5, 0x7d, 0xf7]);
+        assert_eq!(ns.to_hex(), "deadbeef");
+        assert_eq!(
+            BinaryNumeralString::from_bytes_be(&[0xde, 0xad, 0xbe, 0xef]),
+            ns
+        );
+
+        assert_eq!(
+            BinaryNumeralString::from_hex("abc").unwrap_err(),
+            NumeralStringError::InvalidForRadix(16),
+        );
+        assert_eq!(
+            BinaryNumeralString::from_hex("0g").unwrap_err(),
+            NumeralStringError::InvalidNumeral {
+                position: 1,
+                value: 'g'.into(),
+                radix: 16,
+            },
+        );
+        assert_eq!(
+            BinaryNumeralString::from_hex("\u{e9}").unwrap_err(),
+            NumeralStringError::InvalidNumeral {
+                position: 0,
+                value: 0xe9,
+                radix: 16,
+            },
+        );
+
+        // The numerals are the bits in reading order, so from_hex encrypts the same
+        // bit string as the equivalent FlexibleNumeralString.
+        let ff = FF1::<Aes256>::new(&[0; 32], 2).unwrap();
+        let ct = ff
+            .encrypt(
+                &[],
+                &BinaryNumeralString::from_hex("0000000000000000").unwrap(),
+            )
+            .unwrap();
+        let expected = Vec::from(
+            ff.encrypt(&[], &FlexibleNumeralString::from(vec![0; 64]))
+                .unwrap(),
+        );
+        let bits: Vec<u16> = ct
+            .to_bytes_be()
+            .iter()
+            .flat_map(|b| (0..8).rev().map(move |i| u16::from((b >> i) & 1)))
+            .collect();
+        assert_eq!(bits, expected);
+        assert_eq!(
+            ff.decrypt(&[], &BinaryNumeralString::from_hex(&ct.to_hex()).unwrap())
+                .unwrap()
+                .to_hex(),
+            "0000000000000000",
+        );
+    }
 }
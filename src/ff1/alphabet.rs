@@ -0,0 +1,342 @@
+//! Numeral strings over user-defined character sets.
+
+use core::ops::Range;
+
+use alloc::{string::String, vec::Vec};
+use cipher::{BlockCipher, BlockEncrypt, KeyInit};
+
+use super::{
+    AlphabetError, FlexibleNumeralString, InvalidRadix, NumeralString, NumeralStringMut, Radix,
+    SliceableNumeralString, FF1,
+};
+
+const MIN_ALPHABET_LEN: usize = 2;
+const MAX_ALPHABET_LEN: usize = 1 << 16;
+
+/// An ordered set of distinct characters, mapping each character to a numeral.
+///
+/// The characters are stored in sorted order, and the numeral for a character is its
+/// position within that order. The radix for numeral strings over this alphabet is
+/// the number of characters in it.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Alphabet {
+    chars: Vec<char>,
+}
+
+impl Alphabet {
+    /// Creates an alphabet from the given characters.
+    ///
+    /// Returns an error if any character occurs more than once, or if the number of
+    /// characters is not in [2..2^16].
+    pub fn new(chars: &[char]) -> Result<Self, AlphabetError> {
+        if !(MIN_ALPHABET_LEN..=MAX_ALPHABET_LEN).contains(&chars.len()) {
+            return Err(AlphabetError::InvalidLength(chars.len()));
+        }
+
+        let mut chars = chars.to_vec();
+        chars.sort_unstable();
+        if let Some(w) = chars.windows(2).find(|w| w[0] == w[1]) {
+            return Err(AlphabetError::DuplicateCharacter(w[0]));
+        }
+
+        Ok(Alphabet { chars })
+    }
+
+    fn from_preset(chars: &str) -> Self {
+        Alphabet {
+            chars: chars.chars().collect(),
+        }
+    }
+
+    /// The Base58 alphabet used by Bitcoin addresses.
+    pub fn base58() -> Self {
+        Self::from_preset("123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz")
+    }
+
+    /// The decimal digits `0-9`.
+    pub fn decimal() -> Self {
+        Self::from_preset("0123456789")
+    }
+
+    /// The lowercase hexadecimal digits `0-9a-f`.
+    pub fn hex_lower() -> Self {
+        Self::from_preset("0123456789abcdef")
+    }
+
+    /// The ASCII alphanumeric characters `0-9A-Za-z`.
+    pub fn alphanumeric() -> Self {
+        Self::from_preset("0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz")
+    }
+
+    /// Returns the characters in this alphabet, in numeral order.
+    pub fn chars(&self) -> &[char] {
+        &self.chars
+    }
+
+    /// Returns the radix of numeral strings over this alphabet.
+    pub fn radix(&self) -> u32 {
+        self.chars.len() as u32
+    }
+
+    fn numeral(&self, c: char) -> Result<u32, AlphabetError> {
+        self.chars
+            .binary_search(&c)
+            .map(|i| i as u32)
+            .map_err(|_| AlphabetError::UnknownCharacter(c))
+    }
+}
+
+/// A numeral string over an [`Alphabet`].
+///
+/// Each numeral is the position of a character within the alphabet. The alphabet is
+/// not stored in the numeral string, and must be provided when converting to and from
+/// a string. Use [`Alphabet::radix`] as the radix when encrypting.
+#[cfg_attr(test, derive(Debug))]
+pub struct AlphabetNumeralString(Vec<u32>);
+
+impl AlphabetNumeralString {
+    /// Creates a numeral string by mapping each character of `s` to its position in
+    /// `alpha`.
+    ///
+    /// Returns an error if `s` contains a character that is not in `alpha`.
+    pub fn from_str(s: &str, alpha: &Alphabet) -> Result<Self, AlphabetError> {
+        s.chars()
+            .map(|c| alpha.numeral(c))
+            .collect::<Result<_, _>>()
+            .map(AlphabetNumeralString)
+    }
+
+    /// Returns the string formed by mapping each numeral to its character in `alpha`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this numeral string is not valid for the radix of `alpha`.
+    pub fn to_string(&self, alpha: &Alphabet) -> String {
+        self.0.iter().map(|&n| alpha.chars[n as usize]).collect()
+    }
+}
+
+impl From<Vec<u32>> for AlphabetNumeralString {
+    fn from(v: Vec<u32>) -> Self {
+        AlphabetNumeralString(v)
+    }
+}
+
+impl From<AlphabetNumeralString> for Vec<u32> {
+    fn from(ans: AlphabetNumeralString) -> Self {
+        ans.0
+    }
+}
+
+impl NumeralString for AlphabetNumeralString {
+    type Ops = FlexibleNumeralString;
+
+    fn is_valid(&self, radix: u32) -> bool {
+        self.0.iter().all(|n| *n < radix)
+    }
+
+    fn numeral_count(&self) -> usize {
+        self.0.len()
+    }
+
+    fn split(&self) -> (Self::Ops, Self::Ops) {
+        // Numerals are valid by this point, so they are all less than 2^16.
+        FlexibleNumeralString::from(self.0.iter().map(|&n| n as u16).collect::<Vec<_>>()).split()
+    }
+
+    fn concat(a: Self::Ops, b: Self::Ops) -> Self {
+        AlphabetNumeralString(
+            Vec::from(FlexibleNumeralString::concat(a, b))
+                .into_iter()
+                .map(u32::from)
+                .collect(),
+        )
+    }
+
+    fn reversed(&self) -> Self {
+        AlphabetNumeralString(self.0.iter().rev().copied().collect())
+    }
+}
+
+impl NumeralStringMut for AlphabetNumeralString {
+    fn replace_with(&mut self, a: Self::Ops, b: Self::Ops) {
+        self.0.clear();
+        self.0
+            .extend(Vec::from(a).into_iter().chain(Vec::from(b)).map(u32::from));
+    }
+}
+
+impl SliceableNumeralString for AlphabetNumeralString {
+    fn slice(&self, range: Range<usize>) -> Self {
+        AlphabetNumeralString(self.0[range].to_vec())
+    }
+
+    fn splice(&mut self, range: Range<usize>, replacement: Self) {
+        self.0[range].copy_from_slice(&replacement.0);
+    }
+}
+
+impl<CIPH: BlockCipher + BlockEncrypt> FF1<CIPH> {
+    /// Returns the minimum length of numeral strings over the given alphabet.
+    pub fn min_string_len_for_alphabet(alphabet: &Alphabet) -> usize {
+        Radix::from_alphabet_size(alphabet.chars.len())
+            .expect("alphabet length is a valid radix")
+            .min_len()
+    }
+}
+
+impl<CIPH: BlockCipher + BlockEncrypt + Clone + KeyInit> FF1<CIPH> {
+    /// Creates a new FF1 object for the given key, with the radix of the given
+    /// alphabet.
+    ///
+    /// Every [`Alphabet`] has a valid radix, so this only returns an error if the
+    /// alphabet was somehow constructed with an invalid length.
+    pub fn for_alphabet(alphabet: &Alphabet, key: &[u8]) -> Result<Self, InvalidRadix> {
+        let radix = Radix::from_alphabet_size(alphabet.chars.len())?;
+        Self::new(key, radix.to_u32())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aes::{Aes128, Aes192, Aes256};
+
+    use super::{Alphabet, AlphabetNumeralString};
+    use crate::ff1::{
+        test_vectors::{self, AesType},
+        AlphabetError, RadixInfo, FF1,
+    };
+
+    #[test]
+    fn alphabet_new() {
+        let alpha = Alphabet::new(&['c', 'a', 'b']).unwrap();
+        assert_eq!(alpha.chars(), &['a', 'b', 'c']);
+        assert_eq!(alpha.radix(), 3);
+
+        assert_eq!(
+            Alphabet::new(&['a', 'b', 'a']),
+            Err(AlphabetError::DuplicateCharacter('a')),
+        );
+        assert_eq!(Alphabet::new(&[]), Err(AlphabetError::InvalidLength(0)));
+        assert_eq!(Alphabet::new(&['a']), Err(AlphabetError::InvalidLength(1)));
+    }
+
+    #[test]
+    fn presets() {
+        for (alpha, radix) in [
+            (Alphabet::base58(), 58),
+            (Alphabet::decimal(), 10),
+            (Alphabet::hex_lower(), 16),
+            (Alphabet::alphanumeric(), 62),
+        ] {
+            // Presets must be equivalent to what `Alphabet::new` would produce.
+            assert_eq!(Alphabet::new(alpha.chars()).as_ref(), Ok(&alpha));
+            assert_eq!(alpha.radix(), radix);
+        }
+    }
+
+    #[test]
+    fn for_alphabet() {
+        let alpha = Alphabet::base58();
+        let ff = FF1::<Aes256>::for_alphabet(&alpha, &[0; 32]).unwrap();
+        assert_eq!(ff.radix_info().value(), 58);
+        assert_eq!(
+            FF1::<Aes256>::min_string_len_for_alphabet(&alpha),
+            ff.min_numeral_count()
+        );
+        assert_eq!(
+            FF1::<Aes256>::min_string_len_for_alphabet(&Alphabet::decimal()),
+            6
+        );
+        assert_eq!(
+            FF1::<Aes256>::min_string_len_for_alphabet(&Alphabet::hex_lower()),
+            5
+        );
+
+        assert_eq!(RadixInfo::from_alphabet_size(62).unwrap().value(), 62);
+        assert_eq!(RadixInfo::from_alphabet_size(62).unwrap().min_len(), 4);
+        assert!(RadixInfo::from_alphabet_size(1).is_err());
+        assert!(RadixInfo::from_alphabet_size((1 << 16) + 1).is_err());
+    }
+
+    #[test]
+    fn string_round_trip() {
+        let alpha = Alphabet::base58();
+        let ns = AlphabetNumeralString::from_str("1BvBMSEYstWetqTFn5Au4m4", &alpha).unwrap();
+        assert_eq!(ns.0[..3], [0, 10, 53]);
+        assert_eq!(ns.to_string(&alpha), "1BvBMSEYstWetqTFn5Au4m4");
+
+        assert_eq!(
+            AlphabetNumeralString::from_str("10", &alpha).unwrap_err(),
+            AlphabetError::UnknownCharacter('0'),
+        );
+    }
+
+    #[test]
+    fn encrypt_preserves_format() {
+        let alpha = Alphabet::base58();
+        let pt = "1BvBMSEYstWetqTFn5Au4m4";
+        let ff = FF1::<Aes256>::new(&[0; 32], alpha.radix()).unwrap();
+
+        let ct = ff
+            .encrypt(&[], &AlphabetNumeralString::from_str(pt, &alpha).unwrap())
+            .unwrap()
+            .to_string(&alpha);
+        assert_eq!(ct.chars().count(), pt.chars().count());
+        assert!(ct.chars().all(|c| alpha.chars().contains(&c)));
+
+        let decrypted = ff
+            .decrypt(&[], &AlphabetNumeralString::from_str(&ct, &alpha).unwrap())
+            .unwrap()
+            .to_string(&alpha);
+        assert_eq!(decrypted, pt);
+    }
+
+    #[test]
+    fn alphabet() {
+        let base36 = Alphabet::new(
+            &"0123456789abcdefghijklmnopqrstuvwxyz"
+                .chars()
+                .collect::<Vec<_>>(),
+        )
+        .unwrap();
+
+        for tv in test_vectors::get().filter(|tv| tv.radix == 10 || tv.radix == 36) {
+            let alpha = if tv.radix == 10 {
+                Alphabet::decimal()
+            } else {
+                base36.clone()
+            };
+            let to_ns = |v: &[u16]| {
+                AlphabetNumeralString::from(v.iter().map(|&n| u32::from(n)).collect::<Vec<_>>())
+            };
+            let to_str = |v: &[u16]| to_ns(v).to_string(&alpha);
+
+            let (ct, pt) = match tv.aes {
+                AesType::AES128 => {
+                    let ff = FF1::<Aes128>::new(&tv.key, alpha.radix()).unwrap();
+                    (
+                        ff.encrypt(&tv.tweak, &to_ns(&tv.pt)),
+                        ff.decrypt(&tv.tweak, &to_ns(&tv.ct)),
+                    )
+                }
+                AesType::AES192 => {
+                    let ff = FF1::<Aes192>::new(&tv.key, alpha.radix()).unwrap();
+                    (
+                        ff.encrypt(&tv.tweak, &to_ns(&tv.pt)),
+                        ff.decrypt(&tv.tweak, &to_ns(&tv.ct)),
+                    )
+                }
+                AesType::AES256 => {
+                    let ff = FF1::<Aes256>::new(&tv.key, alpha.radix()).unwrap();
+                    (
+                        ff.encrypt(&tv.tweak, &to_ns(&tv.pt)),
+                        ff.decrypt(&tv.tweak, &to_ns(&tv.ct)),
+                    )
+                }
+            };
+            assert_eq!(ct.unwrap().to_string(&alpha), to_str(&tv.ct));
+            assert_eq!(pt.unwrap().to_string(&alpha), to_str(&tv.pt));
+        }
+    }
+}
@@ -0,0 +1,224 @@
+//! FF1 with a truncated HMAC tag, for detecting modified ciphertexts.
+
+use cipher::{BlockCipher, BlockEncrypt, KeyInit};
+use hmac::{
+    digest::{Key, Mac},
+    Hmac,
+};
+use sha2::Sha256;
+
+use super::{
+    AuthenticationError, InspectableOps, InvalidRadix, NumeralString, NumeralStringError, FF1,
+};
+
+/// The HKDF `info` used to derive the MAC key from the FF1 key.
+const DOMAIN_SEPARATOR: &[u8] = b"fpe-auth";
+
+/// A MAC tag over a radix, tweak and ciphertext, produced by
+/// [`AuthenticatedFF1::encrypt_authenticated`].
+#[derive(Clone, Copy, Debug)]
+pub struct Tag([u8; Tag::LEN]);
+
+impl Tag {
+    /// The length of a tag in bytes.
+    pub const LEN: usize = 8;
+
+    /// Creates a tag from its bytes.
+    pub fn from_bytes(bytes: [u8; Tag::LEN]) -> Self {
+        Tag(bytes)
+    }
+
+    /// Returns the bytes of this tag.
+    pub fn to_bytes(self) -> [u8; Tag::LEN] {
+        self.0
+    }
+}
+
+/// An [`FF1`] instance that authenticates each ciphertext with a MAC tag.
+///
+/// FF1 provides no integrity: a modified ciphertext decrypts to a different plaintext
+/// without any error. `AuthenticatedFF1` returns a [`Tag`] alongside each ciphertext,
+/// containing the MAC (HMAC-SHA256 by default) of the radix, the tweak and the
+/// ciphertext numerals, truncated to 8 bytes.
+/// [`AuthenticatedFF1::decrypt_authenticated`] checks the tag before decrypting.
+///
+/// The MAC key is derived from the FF1 key with HKDF-SHA256, so that the two keys are
+/// independent. It does not depend on the block cipher, so a key should not be used
+/// with more than one block cipher.
+pub struct AuthenticatedFF1<CIPH: BlockCipher + BlockEncrypt, M: Mac + KeyInit = Hmac<Sha256>> {
+    ff: FF1<CIPH>,
+    mac: M,
+}
+
+impl<CIPH: BlockCipher + BlockEncrypt + Clone + KeyInit, M: Mac + KeyInit>
+    AuthenticatedFF1<CIPH, M>
+{
+    /// Creates a new authenticated FF1 instance for the given key and radix.
+    ///
+    /// Returns an error if the given radix is not in [2..2^16].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the key has the wrong length for the cipher, as `FF1::new` does.
+    pub fn new(key: &[u8], radix: u32) -> Result<Self, InvalidRadix> {
+        let ff = FF1::new(key, radix)?;
+
+        let mut mac_key = Key::<M>::default();
+        hkdf::Hkdf::<Sha256>::new(None, key)
+            .expand(DOMAIN_SEPARATOR, &mut mac_key)
+            .expect("MAC key is short enough for HKDF-SHA256");
+
+        Ok(AuthenticatedFF1 {
+            ff,
+            mac: <M as KeyInit>::new(&mac_key),
+        })
+    }
+}
+
+impl<CIPH: BlockCipher + BlockEncrypt, M: Mac + KeyInit> AuthenticatedFF1<CIPH, M> {
+    /// Returns the wrapped FF1 instance.
+    pub fn inner(&self) -> &FF1<CIPH> {
+        &self.ff
+    }
+}
+
+impl<CIPH: BlockCipher + BlockEncrypt + Clone, M: Mac + KeyInit + Clone> AuthenticatedFF1<CIPH, M> {
+    /// Returns the MAC over the radix, the tweak and the numerals of `x`.
+    ///
+    /// The radix is included so that a tag is only valid for the radix it was computed
+    /// under. The tweak and numerals are length-prefixed, so that different tweak and
+    /// ciphertext pairs with the same concatenation have different MACs.
+    fn mac<NS>(&self, tweak: &[u8], x: &NS) -> M
+    where
+        NS: NumeralString,
+        NS::Ops: InspectableOps,
+    {
+        let mut mac = self.mac.clone();
+        mac.update(&self.ff.radix.to_u32().to_be_bytes());
+        mac.update(&(tweak.len() as u64).to_be_bytes());
+        mac.update(tweak);
+        mac.update(&(x.numeral_count() as u64).to_be_bytes());
+        let (a, b) = x.split();
+        for &n in a.to_numerals().iter().chain(b.to_numerals()) {
+            let n: u16 = n.into();
+            mac.update(&n.to_be_bytes());
+        }
+        mac
+    }
+
+    /// Encrypts the given numeral string, and returns the ciphertext with a tag over
+    /// the radix, tweak and ciphertext.
+    ///
+    /// Returns an error if the numeral string is not in the required radix.
+    pub fn encrypt_authenticated<NS>(
+        &self,
+        tweak: &[u8],
+        x: &NS,
+    ) -> Result<(NS, Tag), NumeralStringError>
+    where
+        NS: NumeralString,
+        NS::Ops: InspectableOps,
+    {
+        let y = self.ff.encrypt(tweak, x)?;
+        let mut tag = [0; Tag::LEN];
+        tag.copy_from_slice(&self.mac(tweak, &y).finalize().into_bytes()[..Tag::LEN]);
+        Ok((y, Tag(tag)))
+    }
+
+    /// Verifies the tag for the given ciphertext, and decrypts it.
+    ///
+    /// Returns [`AuthenticationError::InvalidTag`] without decrypting if the tag does
+    /// not match the radix, tweak and ciphertext, or an error if the numeral string is not
+    /// in the required radix.
+    pub fn decrypt_authenticated<NS>(
+        &self,
+        tweak: &[u8],
+        x: &NS,
+        tag: &Tag,
+    ) -> Result<NS, AuthenticationError>
+    where
+        NS: NumeralString,
+        NS::Ops: InspectableOps,
+    {
+        // Compares the tag in constant time.
+        self.mac(tweak, x)
+            .verify_truncated_left(&tag.0)
+            .map_err(|_| AuthenticationError::InvalidTag)?;
+        Ok(self.ff.decrypt(tweak, x)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aes::Aes256;
+
+    use super::{AuthenticatedFF1, Tag};
+    use crate::ff1::{AuthenticationError, ByteNumeralString, FlexibleNumeralString, FF1};
+
+    #[test]
+    fn round_trip() {
+        let ff = AuthenticatedFF1::<Aes256>::new(&[1; 32], 10).unwrap();
+        let pt = FlexibleNumeralString::from(vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+
+        let (ct, tag) = ff.encrypt_authenticated(b"tweak", &pt).unwrap();
+        assert_eq!(
+            ff.decrypt_authenticated(b"other", &ct, &tag).unwrap_err(),
+            AuthenticationError::InvalidTag,
+        );
+        assert_eq!(
+            Vec::from(ff.decrypt_authenticated(b"tweak", &ct, &tag).unwrap()),
+            Vec::from(pt),
+        );
+
+        // The ciphertext is the same as for plain FF1.
+        let plain = FF1::<Aes256>::new(&[1; 32], 10).unwrap();
+        let pt = FlexibleNumeralString::from(vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        assert_eq!(
+            Vec::from(ct),
+            Vec::from(plain.encrypt(b"tweak", &pt).unwrap())
+        );
+    }
+
+    #[test]
+    fn radix_bound_to_tag() {
+        let ff10 = AuthenticatedFF1::<Aes256>::new(&[3; 32], 10).unwrap();
+        let ff16 = AuthenticatedFF1::<Aes256>::new(&[3; 32], 16).unwrap();
+        let pt = FlexibleNumeralString::from(vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+
+        // The ciphertext is also valid for radix 16, but the tag is not.
+        let (ct, tag) = ff10.encrypt_authenticated(b"tweak", &pt).unwrap();
+        assert!(ff10.decrypt_authenticated(b"tweak", &ct, &tag).is_ok());
+        assert_eq!(
+            ff16.decrypt_authenticated(b"tweak", &ct, &tag).unwrap_err(),
+            AuthenticationError::InvalidTag,
+        );
+    }
+
+    #[test]
+    fn modified_ciphertext() {
+        let ff = AuthenticatedFF1::<Aes256>::new(&[2; 32], 256).unwrap();
+        let pt = ByteNumeralString::from_bytes(b"account 0123456789");
+        let (ct, tag) = ff.encrypt_authenticated(&[], &pt).unwrap();
+        assert!(ff.decrypt_authenticated(&[], &ct, &tag).is_ok());
+
+        for i in 0..ct.as_bytes().len() {
+            let mut modified = ct.as_bytes().to_vec();
+            modified[i] ^= 1;
+            assert_eq!(
+                ff.decrypt_authenticated(&[], &ByteNumeralString::from(modified), &tag)
+                    .unwrap_err(),
+                AuthenticationError::InvalidTag,
+            );
+        }
+
+        for i in 0..Tag::LEN {
+            let mut modified = tag.to_bytes();
+            modified[i] ^= 1;
+            assert_eq!(
+                ff.decrypt_authenticated(&[], &ct, &Tag::from_bytes(modified))
+                    .unwrap_err(),
+                AuthenticationError::InvalidTag,
+            );
+        }
+    }
+}
@@ -0,0 +1,241 @@
+//! Binary numeral strings of up to 128 bits, computed on with `u128` arithmetic.
+
+use super::{FixedBytes, NumeralString, NumeralStringError, NumeralStringMut, Operations};
+
+/// The maximum number of numerals in a [`BinaryNumeralString128`].
+const MAX_LEN: usize = 128;
+
+/// Returns a mask of the low `len` bits.
+fn mask(len: usize) -> u128 {
+    u128::MAX.checked_shr((MAX_LEN - len) as u32).unwrap_or(0)
+}
+
+/// A numeral string with radix 2 and at most 128 numerals, stored as a `u128`.
+///
+/// The numerals are the low `len` bits of the value, most significant bit first. FF1
+/// computations on the halves use `u128` arithmetic, so encrypting short binary
+/// identifiers (such as 20 to 40 bit IDs) requires neither a global allocator nor a
+/// `BigUint`.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(test, derive(Debug))]
+pub struct BinaryNumeralString128 {
+    value: u128,
+    len: usize,
+}
+
+impl BinaryNumeralString128 {
+    /// Creates a numeral string of `len` bits with the given value.
+    ///
+    /// Returns an error if `len` is greater than 128, or if `value` does not fit in
+    /// `len` bits.
+    pub fn new(value: u128, len: usize) -> Result<Self, NumeralStringError> {
+        if len > MAX_LEN {
+            return Err(NumeralStringError::TooLong {
+                ns_len: len,
+                max_len: MAX_LEN,
+            });
+        }
+        if value & !mask(len) != 0 {
+            return Err(NumeralStringError::InvalidForRadix(2));
+        }
+        Ok(BinaryNumeralString128 { value, len })
+    }
+
+    /// Returns the value of this numeral string.
+    pub fn value(&self) -> u128 {
+        self.value
+    }
+}
+
+impl NumeralString for BinaryNumeralString128 {
+    type Ops = U128Ops;
+
+    fn is_valid(&self, radix: u32) -> bool {
+        radix == 2
+    }
+
+    fn numeral_count(&self) -> usize {
+        self.len
+    }
+
+    fn split(&self) -> (Self::Ops, Self::Ops) {
+        let u = self.len / 2;
+        let v = self.len - u;
+        (
+            U128Ops {
+                value: self.value.checked_shr(v as u32).unwrap_or(0),
+                len: u,
+            },
+            U128Ops {
+                value: self.value & mask(v),
+                len: v,
+            },
+        )
+    }
+
+    fn concat(a: Self::Ops, b: Self::Ops) -> Self {
+        assert!(a.len + b.len <= MAX_LEN);
+        BinaryNumeralString128 {
+            value: a.value.checked_shl(b.len as u32).unwrap_or(0) | b.value,
+            len: a.len + b.len,
+        }
+    }
+
+    fn reversed(&self) -> Self {
+        BinaryNumeralString128 {
+            value: self
+                .value
+                .reverse_bits()
+                .checked_shr((MAX_LEN - self.len) as u32)
+                .unwrap_or(0),
+            len: self.len,
+        }
+    }
+}
+
+impl NumeralStringMut for BinaryNumeralString128 {
+    fn replace_with(&mut self, a: Self::Ops, b: Self::Ops) {
+        *self = Self::concat(a, b);
+    }
+}
+
+/// One half of a [`BinaryNumeralString128`], used for FF1 computations.
+#[derive(Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(test, derive(Debug))]
+pub struct U128Ops {
+    /// The numerals, as the low `len` bits.
+    value: u128,
+    len: usize,
+}
+
+impl U128Ops {
+    /// Returns `y mod 2^m` for the big-endian integer `y`.
+    fn reduce(y: impl Iterator<Item = u8>, m: usize) -> u128 {
+        y.fold(0u128, |acc, byte| (acc << 8) | u128::from(byte)) & mask(m)
+    }
+}
+
+impl Operations for U128Ops {
+    /// Holds up to 16 bytes, which covers the value and the 12-byte numbers used by
+    /// FF3-1, within the `2 * N` bytes of a `FixedBytes<8>` rather than its extra
+    /// capacity.
+    type Bytes = FixedBytes<8>;
+
+    fn numeral_count(&self) -> usize {
+        self.len
+    }
+
+    fn to_be_bytes(&self, radix: u32, b: usize) -> Self::Bytes {
+        assert_eq!(radix, 2);
+        let mut bytes = FixedBytes::new(b);
+        let value = self.value.to_be_bytes();
+        bytes.as_mut().copy_from_slice(&value[value.len() - b..]);
+        bytes
+    }
+
+    fn add_mod_exp(self, other: impl Iterator<Item = u8>, radix: u32, m: usize) -> Self {
+        assert_eq!(radix, 2);
+        assert_eq!(self.len, m);
+        U128Ops {
+            value: self.value.wrapping_add(Self::reduce(other, m)) & mask(m),
+            len: m,
+        }
+    }
+
+    fn sub_mod_exp(self, other: impl Iterator<Item = u8>, radix: u32, m: usize) -> Self {
+        assert_eq!(radix, 2);
+        assert_eq!(self.len, m);
+        U128Ops {
+            value: self.value.wrapping_sub(Self::reduce(other, m)) & mask(m),
+            len: m,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aes::Aes256;
+    use proptest::prelude::*;
+
+    use super::{BinaryNumeralString128, U128Ops};
+    use crate::ff1::{FlexibleNumeralString, NumeralString, NumeralStringError, Operations, FF1};
+
+    fn bits(ns: &BinaryNumeralString128) -> Vec<u16> {
+        (0..ns.len)
+            .rev()
+            .map(|i| ((ns.value >> i) & 1) as u16)
+            .collect()
+    }
+
+    #[test]
+    fn new() {
+        let ns = BinaryNumeralString128::new(0b1011, 6).unwrap();
+        assert_eq!(ns.value(), 0b1011);
+        assert_eq!(bits(&ns), [0, 0, 1, 0, 1, 1]);
+        assert_eq!(
+            BinaryNumeralString128::new(0b1011, 3).unwrap_err(),
+            NumeralStringError::InvalidForRadix(2),
+        );
+        assert_eq!(
+            BinaryNumeralString128::new(0, 129).unwrap_err(),
+            NumeralStringError::TooLong {
+                ns_len: 129,
+                max_len: 128,
+            },
+        );
+        assert!(BinaryNumeralString128::new(u128::MAX, 128).is_ok());
+        assert!(BinaryNumeralString128::new(0, 0).is_ok());
+    }
+
+    #[test]
+    fn split_round_trip() {
+        let ns = BinaryNumeralString128::new(0b10110, 5).unwrap();
+        let (a, b) = ns.split();
+        assert_eq!((a.value, a.len, b.value, b.len), (0b10, 2, 0b110, 3));
+        assert_eq!(BinaryNumeralString128::concat(a, b), ns);
+        assert_eq!(ns.reversed().value(), 0b01101);
+
+        let ns = BinaryNumeralString128::new(u128::MAX - 1, 128).unwrap();
+        let (a, b) = ns.split();
+        assert_eq!(BinaryNumeralString128::concat(a, b), ns);
+        assert_eq!(ns.reversed().value(), u128::MAX >> 1);
+    }
+
+    #[test]
+    fn matches_flexible() {
+        let ff = FF1::<Aes256>::new(&[0; 32], 2).unwrap();
+        for (value, len) in [
+            (0, 20),
+            (0xabcde, 20),
+            (0x12_3456_789a, 40),
+            (u128::MAX, 128),
+        ] {
+            let ns = BinaryNumeralString128::new(value, len).unwrap();
+            let ct = ff.encrypt(b"id", &ns).unwrap();
+            assert_eq!(
+                bits(&ct),
+                Vec::from(
+                    ff.encrypt(b"id", &FlexibleNumeralString::from(bits(&ns)))
+                        .unwrap()
+                ),
+            );
+            assert_eq!(ff.decrypt(b"id", &ct).unwrap(), ns);
+        }
+    }
+
+    proptest! {
+        #[test]
+        fn add_sub_round_trip(
+            m in 0usize..=128,
+            value in any::<u128>(),
+            other in prop::collection::vec(any::<u8>(), 0..40),
+        ) {
+            let ops = U128Ops {
+                value: value & super::mask(m),
+                len: m,
+            };
+            let sum = ops.add_mod_exp(other.iter().copied(), 2, m);
+            assert_eq!(sum.sub_mod_exp(other.iter().copied(), 2, m), ops);
+        }
+    }
+}
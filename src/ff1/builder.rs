@@ -0,0 +1,224 @@
+//! A builder for [`FF1`] instances that validates all parameters.
+
+use core::marker::PhantomData;
+
+use alloc::vec::Vec;
+use cipher::{BlockCipher, BlockEncrypt, KeyInit};
+
+#[cfg(feature = "zeroize")]
+use cipher::zeroize::Zeroize;
+
+use super::{BuildError, DomainSpec, Radix, FF1};
+
+/// The number of Feistel rounds that NIST SP 800-38G mandates for FF1.
+const MIN_FEISTEL_ROUNDS: u8 = 10;
+
+/// A builder for [`FF1`] instances.
+///
+/// Unlike [`FF1::new`], which panics if the key has the wrong length for the cipher,
+/// [`FF1Builder::build`] validates every parameter and returns a [`BuildError`] if any
+/// of them is invalid.
+///
+/// ```
+/// use aes::Aes256;
+/// use fpe::ff1::FF1;
+///
+/// let ff = FF1::<Aes256>::builder()
+///     .key(&[0; 32])
+///     .radix(10)
+///     .build()
+///     .unwrap();
+/// # let _ = ff;
+/// ```
+pub struct FF1Builder<CIPH> {
+    key: Vec<u8>,
+    radix: Option<u32>,
+    domain_radix: Option<u32>,
+    rounds: u8,
+    _cipher: PhantomData<CIPH>,
+}
+
+impl<CIPH> Default for FF1Builder<CIPH> {
+    fn default() -> Self {
+        FF1Builder {
+            key: Vec::new(),
+            radix: None,
+            domain_radix: None,
+            rounds: MIN_FEISTEL_ROUNDS,
+            _cipher: PhantomData,
+        }
+    }
+}
+
+impl<CIPH> FF1Builder<CIPH> {
+    /// Creates a builder with no key or radix, and the standard 10 Feistel rounds.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the key used to initialize the cipher.
+    pub fn key(mut self, key: &[u8]) -> Self {
+        #[cfg(feature = "zeroize")]
+        self.key.zeroize();
+        self.key = key.to_vec();
+        self
+    }
+
+    /// Sets the radix of the numeral strings that will be encrypted.
+    pub fn radix(mut self, radix: u32) -> Self {
+        self.radix = Some(radix);
+        self
+    }
+
+    /// Sets the domain of the numeral strings that will be encrypted.
+    ///
+    /// If no radix is set, the radix of the domain is used. `build` returns an error
+    /// if a radix is also set and does not match the domain.
+    pub fn domain(mut self, domain: &DomainSpec) -> Self {
+        self.domain_radix = Some(domain.to_ff1_radix());
+        self
+    }
+
+    /// Sets the number of Feistel rounds.
+    ///
+    /// `build` returns an error if this is less than 10.
+    pub fn rounds(mut self, rounds: u8) -> Self {
+        self.rounds = rounds;
+        self
+    }
+}
+
+impl<CIPH: BlockCipher + BlockEncrypt + Clone + KeyInit> FF1Builder<CIPH> {
+    /// Validates the parameters and creates the FF1 object.
+    pub fn build(self) -> Result<FF1<CIPH>, BuildError> {
+        let ciph = CIPH::new_from_slice(&self.key)
+            .map_err(|_| BuildError::InvalidKeyLength(self.key.len()))?;
+        let radix = match (self.radix, self.domain_radix) {
+            (Some(radix), Some(domain_radix)) if radix != domain_radix => {
+                return Err(BuildError::DomainRadixMismatch {
+                    radix,
+                    domain_radix,
+                })
+            }
+            (Some(radix), _) | (None, Some(radix)) => radix,
+            (None, None) => return Err(BuildError::MissingRadix),
+        };
+        let radix = Radix::from_u32(radix)?;
+        if self.rounds < MIN_FEISTEL_ROUNDS {
+            return Err(BuildError::InvalidRoundCount {
+                given: self.rounds,
+                minimum: MIN_FEISTEL_ROUNDS,
+            });
+        }
+        Ok(FF1::from_parts(ciph, radix, self.rounds))
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<CIPH> Drop for FF1Builder<CIPH> {
+    fn drop(&mut self) {
+        self.key.as_mut_slice().zeroize();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aes::{Aes128, Aes256};
+
+    use crate::ff1::{BuildError, DomainSpec, FlexibleNumeralString, FF1};
+
+    #[test]
+    fn build_matches_new() {
+        let key = [0x2b; 16];
+        let pt = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9];
+
+        let built = FF1::<Aes128>::builder()
+            .key(&key)
+            .radix(10)
+            .build()
+            .unwrap();
+        let new = FF1::<Aes128>::new(&key, 10).unwrap();
+
+        assert_eq!(
+            Vec::from(
+                built
+                    .encrypt(&[], &FlexibleNumeralString::from(pt.to_vec()))
+                    .unwrap()
+            ),
+            Vec::from(
+                new.encrypt(&[], &FlexibleNumeralString::from(pt.to_vec()))
+                    .unwrap()
+            ),
+        );
+    }
+
+    #[test]
+    fn build_errors() {
+        assert_eq!(
+            FF1::<Aes256>::builder()
+                .key(&[0; 16])
+                .radix(10)
+                .build()
+                .err(),
+            Some(BuildError::InvalidKeyLength(16)),
+        );
+        assert_eq!(
+            FF1::<Aes256>::builder().key(&[0; 32]).build().err(),
+            Some(BuildError::MissingRadix),
+        );
+        assert_eq!(
+            FF1::<Aes256>::builder()
+                .key(&[0; 32])
+                .radix(1)
+                .build()
+                .err(),
+            Some(BuildError::InvalidRadix(1)),
+        );
+        assert_eq!(
+            FF1::<Aes256>::builder()
+                .key(&[0; 32])
+                .radix(10)
+                .rounds(8)
+                .build()
+                .err(),
+            Some(BuildError::InvalidRoundCount {
+                given: 8,
+                minimum: 10,
+            }),
+        );
+        assert!(FF1::<Aes256>::builder()
+            .key(&[0; 32])
+            .radix(10)
+            .rounds(12)
+            .build()
+            .is_ok());
+    }
+
+    #[test]
+    fn domain() {
+        let ff = FF1::<Aes256>::builder()
+            .key(&[0; 32])
+            .domain(&DomainSpec::uuid_hex())
+            .build()
+            .unwrap();
+        assert_eq!(ff.radix.to_u32(), 16);
+        assert!(FF1::<Aes256>::builder()
+            .key(&[0; 32])
+            .radix(10)
+            .domain(&DomainSpec::ssn())
+            .build()
+            .is_ok());
+        assert_eq!(
+            FF1::<Aes256>::builder()
+                .key(&[0; 32])
+                .radix(16)
+                .domain(&DomainSpec::credit_card())
+                .build()
+                .err(),
+            Some(BuildError::DomainRadixMismatch {
+                radix: 16,
+                domain_radix: 10,
+            }),
+        );
+    }
+}
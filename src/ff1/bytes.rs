@@ -0,0 +1,227 @@
+//! A radix-256 numeral string for arbitrary binary data.
+
+use core::ops::Range;
+
+use alloc::{vec, vec::Vec};
+use cipher::{BlockCipher, BlockEncrypt, KeyInit};
+
+use super::{
+    InspectableOps, InvalidRadix, NumeralString, NumeralStringMut, Operations,
+    SliceableNumeralString, FF1,
+};
+
+const RADIX: u32 = 256;
+
+/// A numeral string with radix 256, where each byte is one numeral.
+///
+/// Encrypting a `ByteNumeralString` preserves its length in bytes. FF1 requires at
+/// least 3 numerals in radix 256, as `256^3` is the smallest power of 256 that is at
+/// least 1,000,000.
+///
+/// Radix 256 is a power of two, so all FF1 arithmetic for this numeral string avoids
+/// floating-point operations.
+#[cfg_attr(test, derive(Debug))]
+pub struct ByteNumeralString(Vec<u8>);
+
+impl ByteNumeralString {
+    /// Creates a `ByteNumeralString` from the given bytes.
+    ///
+    /// [`FF1::encrypt`] and [`FF1::decrypt`] return an error if there are fewer than
+    /// 3 bytes.
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        ByteNumeralString(bytes.to_vec())
+    }
+
+    /// Returns the bytes of this numeral string.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl From<Vec<u8>> for ByteNumeralString {
+    fn from(bytes: Vec<u8>) -> Self {
+        ByteNumeralString(bytes)
+    }
+}
+
+impl From<ByteNumeralString> for Vec<u8> {
+    fn from(ns: ByteNumeralString) -> Self {
+        ns.0
+    }
+}
+
+impl NumeralString for ByteNumeralString {
+    type Ops = Self;
+
+    fn is_valid(&self, radix: u32) -> bool {
+        // Every byte is a valid radix-256 numeral.
+        radix == RADIX
+    }
+
+    fn numeral_count(&self) -> usize {
+        self.0.len()
+    }
+
+    fn split(&self) -> (Self, Self) {
+        let mut front = self.0.clone();
+        let back = front.split_off(self.0.len() / 2);
+        (ByteNumeralString(front), ByteNumeralString(back))
+    }
+
+    fn concat(mut a: Self, mut b: Self) -> Self {
+        a.0.append(&mut b.0);
+        a
+    }
+
+    fn reversed(&self) -> Self {
+        ByteNumeralString(self.0.iter().rev().copied().collect())
+    }
+}
+
+impl NumeralStringMut for ByteNumeralString {
+    fn replace_with(&mut self, a: Self, b: Self) {
+        self.0.clear();
+        self.0.extend_from_slice(&a.0);
+        self.0.extend_from_slice(&b.0);
+    }
+}
+
+impl SliceableNumeralString for ByteNumeralString {
+    fn slice(&self, range: Range<usize>) -> Self {
+        ByteNumeralString(self.0[range].to_vec())
+    }
+
+    fn splice(&mut self, range: Range<usize>, replacement: Self) {
+        self.0[range].copy_from_slice(&replacement.0);
+    }
+}
+
+/// Returns the last `m` bytes of the big-endian integer `bytes`, which is the integer
+/// reduced modulo `256^m`.
+fn reduce_be_bytes(bytes: impl Iterator<Item = u8>, m: usize) -> Vec<u8> {
+    let mut y: Vec<u8> = bytes.collect();
+    if y.len() > m {
+        y.drain(..y.len() - m);
+    }
+    y
+}
+
+impl Operations for ByteNumeralString {
+    type Bytes = Vec<u8>;
+
+    fn numeral_count(&self) -> usize {
+        self.0.len()
+    }
+
+    fn to_be_bytes(&self, radix: u32, b: usize) -> Self::Bytes {
+        assert_eq!(radix, RADIX);
+        // In radix 256 the numerals are already the big-endian bytes of the number.
+        let mut bytes = vec![0; b];
+        bytes[b - self.0.len()..].copy_from_slice(&self.0);
+        bytes
+    }
+
+    fn add_mod_exp(mut self, other: impl Iterator<Item = u8>, radix: u32, m: usize) -> Self {
+        assert_eq!(radix, RADIX);
+        assert_eq!(self.0.len(), m);
+        let y = reduce_be_bytes(other, m);
+        // Carries out of the most significant byte are dropped, reducing mod 256^m.
+        let mut carry = false;
+        let mut y = y.iter().rev();
+        for x in self.0.iter_mut().rev() {
+            let (t, c1) = x.overflowing_add(y.next().copied().unwrap_or(0));
+            let (t, c2) = t.overflowing_add(u8::from(carry));
+            *x = t;
+            carry = c1 || c2;
+        }
+        self
+    }
+
+    fn sub_mod_exp(mut self, other: impl Iterator<Item = u8>, radix: u32, m: usize) -> Self {
+        assert_eq!(radix, RADIX);
+        assert_eq!(self.0.len(), m);
+        let y = reduce_be_bytes(other, m);
+        // Borrows out of the most significant byte are dropped, reducing mod 256^m.
+        let mut borrow = false;
+        let mut y = y.iter().rev();
+        for x in self.0.iter_mut().rev() {
+            let (t, b1) = x.overflowing_sub(y.next().copied().unwrap_or(0));
+            let (t, b2) = t.overflowing_sub(u8::from(borrow));
+            *x = t;
+            borrow = b1 || b2;
+        }
+        self
+    }
+}
+
+impl InspectableOps for ByteNumeralString {
+    type Numeral = u8;
+
+    fn to_numerals(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl<CIPH: BlockCipher + BlockEncrypt + Clone + KeyInit> FF1<CIPH> {
+    /// Creates a new FF1 object for the given key, with radix 256 for encrypting
+    /// [`ByteNumeralString`]s.
+    pub fn new_byte_level(key: &[u8]) -> Result<Self, InvalidRadix> {
+        Self::new(key, RADIX)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aes::Aes128;
+
+    use super::ByteNumeralString;
+    use crate::ff1::{FlexibleNumeralString, NumeralStringError, FF1};
+
+    #[test]
+    fn matches_flexible() {
+        let ff = FF1::<Aes128>::new_byte_level(&[0x2b; 16]).unwrap();
+        assert_eq!(ff.min_numeral_count(), 3);
+
+        for pt in [
+            &[0x00, 0x00, 0x00][..],
+            &[0xff, 0xff, 0xff, 0xff],
+            b"arbitrary binary blob \x00\x01\xfe\xff",
+        ] {
+            let ct = ff
+                .encrypt(b"tweak", &ByteNumeralString::from_bytes(pt))
+                .unwrap();
+            let expected = ff
+                .encrypt(
+                    b"tweak",
+                    &FlexibleNumeralString::from(
+                        pt.iter().map(|&b| u16::from(b)).collect::<Vec<_>>(),
+                    ),
+                )
+                .unwrap();
+            assert_eq!(ct.as_bytes().len(), pt.len());
+            assert_eq!(
+                ct.as_bytes(),
+                Vec::from(expected)
+                    .into_iter()
+                    .map(|n| n as u8)
+                    .collect::<Vec<_>>(),
+            );
+
+            let decrypted = ff.decrypt(b"tweak", &ct).unwrap();
+            assert_eq!(decrypted.as_bytes(), pt);
+        }
+    }
+
+    #[test]
+    fn too_short() {
+        let ff = FF1::<Aes128>::new_byte_level(&[0; 16]).unwrap();
+        assert_eq!(
+            ff.encrypt(&[], &ByteNumeralString::from_bytes(&[1, 2]))
+                .unwrap_err(),
+            NumeralStringError::TooShort {
+                ns_len: 2,
+                min_len: 3,
+            },
+        );
+    }
+}
@@ -0,0 +1,128 @@
+//! A cache of FF1 instances, keyed by radix and key.
+
+use std::collections::HashMap;
+
+use cipher::{BlockCipher, BlockEncrypt, KeyInit};
+
+#[cfg(feature = "zeroize")]
+use cipher::zeroize::Zeroize;
+
+use super::{InvalidRadix, FF1};
+
+/// A cache of [`FF1`] instances, so that the key schedule and PRF precomputation are
+/// shared between requests that use the same key and radix.
+///
+/// The cache holds a copy of each key. With the `zeroize` feature flag, the copies are
+/// zeroized when the cache is dropped.
+pub struct FF1Cache<CIPH: BlockCipher + BlockEncrypt> {
+    /// Instances by radix, then by key, so that lookups do not copy the key.
+    instances: HashMap<u32, HashMap<Vec<u8>, FF1<CIPH>>>,
+}
+
+impl<CIPH: BlockCipher + BlockEncrypt> Default for FF1Cache<CIPH> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<CIPH: BlockCipher + BlockEncrypt> FF1Cache<CIPH> {
+    /// Creates an empty cache.
+    pub fn new() -> Self {
+        FF1Cache {
+            instances: HashMap::new(),
+        }
+    }
+
+    /// Returns the number of cached instances.
+    pub fn len(&self) -> usize {
+        self.instances.values().map(HashMap::len).sum()
+    }
+
+    /// Returns `true` if the cache holds no instances.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<CIPH: BlockCipher + BlockEncrypt + Clone + KeyInit> FF1Cache<CIPH> {
+    /// Returns the cached FF1 instance for the given key and radix, creating it with
+    /// [`FF1::new`] if it is not cached.
+    ///
+    /// Returns an error if the given radix is not in [2..2^16].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the key has the wrong length for the cipher.
+    pub fn get_or_insert(&mut self, key: &[u8], radix: u32) -> Result<&FF1<CIPH>, InvalidRadix> {
+        let by_key = self.instances.entry(radix).or_default();
+        if !by_key.contains_key(key) {
+            let ff = FF1::new(key, radix)?;
+            by_key.insert(key.to_vec(), ff);
+        }
+        Ok(&by_key[key])
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<CIPH: BlockCipher + BlockEncrypt> Drop for FF1Cache<CIPH> {
+    fn drop(&mut self) {
+        for by_key in self.instances.values_mut() {
+            for (mut key, _) in by_key.drain() {
+                key.zeroize();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ptr;
+
+    use aes::Aes256;
+
+    use super::FF1Cache;
+    use crate::ff1::{FlexibleNumeralString, FF1};
+
+    #[test]
+    fn get_or_insert() {
+        let pt = FlexibleNumeralString::from(vec![1, 2, 3, 4, 5, 6]);
+        let encrypt = |ff: &FF1<Aes256>| Vec::from(ff.encrypt(&[], &pt).unwrap());
+
+        let mut cache = FF1Cache::<Aes256>::new();
+        assert!(cache.is_empty());
+
+        let ff: *const _ = cache.get_or_insert(&[0; 32], 10).unwrap();
+        assert!(ptr::eq(cache.get_or_insert(&[0; 32], 10).unwrap(), ff));
+        assert_eq!(cache.len(), 1);
+        assert_eq!(
+            encrypt(cache.get_or_insert(&[0; 32], 10).unwrap()),
+            encrypt(&FF1::new(&[0; 32], 10).unwrap()),
+        );
+
+        let other = encrypt(cache.get_or_insert(&[1; 32], 10).unwrap());
+        assert_eq!(other, encrypt(&FF1::new(&[1; 32], 10).unwrap()));
+        cache.get_or_insert(&[0; 32], 16).unwrap();
+        assert_eq!(cache.len(), 3);
+
+        assert!(cache.get_or_insert(&[0; 32], 1).is_err());
+        assert_eq!(cache.len(), 3);
+    }
+
+    #[cfg(feature = "subtle")]
+    #[test]
+    fn ff1_hash_eq() {
+        use aes::Aes128;
+        use std::collections::HashSet;
+
+        let set: HashSet<_> = [
+            FF1::<Aes128>::new(&[0; 16], 10).unwrap(),
+            FF1::new(&[0; 16], 10).unwrap(),
+            FF1::new(&[1; 16], 10).unwrap(),
+            FF1::new(&[0; 16], 2).unwrap(),
+            FF1::new_with_faistel_rounds(&[0; 16], 10, 12).unwrap(),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(set.len(), 4);
+    }
+}
@@ -0,0 +1,308 @@
+//! FF1 that keeps a valid check digit at the end of each numeral string.
+
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+
+use cipher::{BlockCipher, BlockEncrypt};
+
+use super::{
+    pan::luhn_check_digit, FlexibleNumeralString, NumeralStringError, VerificationError, FF1,
+};
+
+const RADIX: u32 = 10;
+
+/// A check digit algorithm for decimal numeral strings.
+pub trait ChecksumAlgo {
+    /// Returns the check digit that should follow `digits`.
+    ///
+    /// Every element of `digits` is a decimal digit.
+    fn compute(digits: &[u16]) -> u16;
+}
+
+/// The Luhn algorithm, used by payment card numbers.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LuhnAlgo;
+
+impl ChecksumAlgo for LuhnAlgo {
+    fn compute(digits: &[u16]) -> u16 {
+        luhn_check_digit(digits)
+    }
+}
+
+/// The Damm algorithm, which detects every single-digit error and every transposition
+/// of adjacent digits.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DammAlgo;
+
+/// A totally anti-symmetric quasigroup of order 10.
+const DAMM_TABLE: [[u8; 10]; 10] = [
+    [0, 3, 1, 7, 5, 9, 8, 6, 4, 2],
+    [7, 0, 9, 2, 1, 5, 4, 8, 6, 3],
+    [4, 2, 0, 6, 8, 7, 1, 3, 5, 9],
+    [1, 7, 5, 0, 9, 8, 3, 4, 2, 6],
+    [6, 1, 2, 3, 0, 4, 5, 9, 7, 8],
+    [3, 6, 7, 4, 2, 0, 9, 5, 8, 1],
+    [5, 8, 6, 9, 7, 2, 0, 1, 3, 4],
+    [8, 9, 4, 5, 3, 6, 2, 0, 1, 7],
+    [9, 4, 3, 8, 6, 1, 7, 2, 0, 5],
+    [2, 5, 8, 1, 4, 3, 6, 7, 9, 0],
+];
+
+impl ChecksumAlgo for DammAlgo {
+    fn compute(digits: &[u16]) -> u16 {
+        // The table has zeroes on its diagonal, so the check digit is the interim
+        // digit after the whole body.
+        let interim = digits.iter().fold(0, |interim, &d| {
+            DAMM_TABLE[usize::from(interim)][usize::from(d)]
+        });
+        u16::from(interim)
+    }
+}
+
+/// The Verhoeff algorithm, which detects every single-digit error and every
+/// transposition of adjacent digits.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct VerhoeffAlgo;
+
+/// The multiplication table of the dihedral group D5.
+const VERHOEFF_D: [[u8; 10]; 10] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9],
+    [1, 2, 3, 4, 0, 6, 7, 8, 9, 5],
+    [2, 3, 4, 0, 1, 7, 8, 9, 5, 6],
+    [3, 4, 0, 1, 2, 8, 9, 5, 6, 7],
+    [4, 0, 1, 2, 3, 9, 5, 6, 7, 8],
+    [5, 9, 8, 7, 6, 0, 4, 3, 2, 1],
+    [6, 5, 9, 8, 7, 1, 0, 4, 3, 2],
+    [7, 6, 5, 9, 8, 2, 1, 0, 4, 3],
+    [8, 7, 6, 5, 9, 3, 2, 1, 0, 4],
+    [9, 8, 7, 6, 5, 4, 3, 2, 1, 0],
+];
+
+/// The permutation applied to each digit, by position modulo 8.
+const VERHOEFF_P: [[u8; 10]; 8] = [
+    [0, 1, 2, 3, 4, 5, 6, 7, 8, 9],
+    [1, 5, 7, 6, 2, 8, 3, 0, 9, 4],
+    [5, 8, 0, 3, 7, 9, 6, 1, 4, 2],
+    [8, 9, 1, 6, 0, 4, 3, 5, 2, 7],
+    [9, 4, 5, 3, 1, 2, 6, 8, 7, 0],
+    [4, 2, 8, 6, 5, 7, 3, 9, 0, 1],
+    [2, 7, 9, 3, 8, 0, 6, 4, 1, 5],
+    [7, 0, 4, 6, 9, 1, 3, 2, 5, 8],
+];
+
+/// The inverses in D5.
+const VERHOEFF_INV: [u8; 10] = [0, 4, 3, 2, 1, 5, 6, 7, 8, 9];
+
+impl ChecksumAlgo for VerhoeffAlgo {
+    fn compute(digits: &[u16]) -> u16 {
+        // Position 0 is reserved for the check digit, so the body starts at 1.
+        let c = digits.iter().rev().enumerate().fold(0, |c, (i, &d)| {
+            VERHOEFF_D[usize::from(c)][usize::from(VERHOEFF_P[(i + 1) % 8][usize::from(d)])]
+        });
+        u16::from(VERHOEFF_INV[usize::from(c)])
+    }
+}
+
+/// An [`FF1`] instance that encrypts all but the last digit of each decimal numeral
+/// string, which is replaced by a check digit computed with `C`.
+///
+/// Tokens therefore pass the same check digit validation as the plaintexts, such as
+/// the Luhn check of payment card numbers. Decryption only recovers the original
+/// numeral string if its check digit was itself valid.
+///
+/// The FF1 instance must have been created with radix 10.
+pub struct ChecksumPreservingFF1<CIPH: BlockCipher + BlockEncrypt, C> {
+    ff: FF1<CIPH>,
+    _algo: PhantomData<fn() -> C>,
+}
+
+impl<CIPH: BlockCipher + BlockEncrypt, C> ChecksumPreservingFF1<CIPH, C> {
+    /// Wraps the given FF1 instance.
+    pub fn new(ff: FF1<CIPH>) -> Self {
+        ChecksumPreservingFF1 {
+            ff,
+            _algo: PhantomData,
+        }
+    }
+
+    /// Returns the wrapped FF1 instance.
+    pub fn inner(&self) -> &FF1<CIPH> {
+        &self.ff
+    }
+}
+
+impl<CIPH: BlockCipher + BlockEncrypt + Clone, C: ChecksumAlgo> ChecksumPreservingFF1<CIPH, C> {
+    /// Encrypts all but the last digit of the given numeral string, and appends the
+    /// check digit of the result.
+    ///
+    /// Returns an error if the radix is not 10, if the numeral string is not in radix
+    /// 10, or if fewer digits remain to be encrypted than radix 10 requires.
+    pub fn encrypt(
+        &self,
+        tweak: &[u8],
+        x: &FlexibleNumeralString,
+    ) -> Result<FlexibleNumeralString, NumeralStringError> {
+        let body = self.check(x)?;
+        Ok(with_check_digit::<C>(self.ff.encrypt(tweak, &body)?))
+    }
+
+    /// Decrypts all but the last digit of the given token, and appends the check digit
+    /// of the result.
+    ///
+    /// Returns [`VerificationError::ChecksumVerificationFailed`] if the last digit of
+    /// the token is not its check digit, or an error as for
+    /// [`ChecksumPreservingFF1::encrypt`].
+    pub fn decrypt(
+        &self,
+        tweak: &[u8],
+        x: &FlexibleNumeralString,
+    ) -> Result<FlexibleNumeralString, VerificationError> {
+        let body = self.check(x)?;
+        if C::compute(&body.0) != x.0[body.0.len()] {
+            return Err(VerificationError::ChecksumVerificationFailed);
+        }
+        Ok(with_check_digit::<C>(self.ff.decrypt(tweak, &body)?))
+    }
+
+    /// Checks that `x` can be transformed, and returns all but its last digit.
+    fn check(
+        &self,
+        x: &FlexibleNumeralString,
+    ) -> Result<FlexibleNumeralString, NumeralStringError> {
+        if self.ff.radix.to_u32() != RADIX {
+            return Err(NumeralStringError::InvalidForRadix(self.ff.radix.to_u32()));
+        }
+        let ns_len = x.0.len();
+        let min_len = self.ff.radix.min_len() + 1;
+        if ns_len < min_len {
+            return Err(NumeralStringError::TooShort { ns_len, min_len });
+        }
+        x.validate(RADIX)?;
+        Ok(FlexibleNumeralString(x.0[..ns_len - 1].to_vec()))
+    }
+}
+
+/// Appends the check digit of `body`.
+fn with_check_digit<C: ChecksumAlgo>(body: FlexibleNumeralString) -> FlexibleNumeralString {
+    let mut digits: Vec<u16> = body.into();
+    digits.push(C::compute(&digits));
+    digits.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use aes::Aes256;
+
+    use super::{ChecksumAlgo, ChecksumPreservingFF1, DammAlgo, LuhnAlgo, VerhoeffAlgo};
+    use crate::ff1::{FlexibleNumeralString, NumeralStringError, VerificationError, FF1};
+
+    fn digits(s: &str) -> Vec<u16> {
+        s.bytes().map(|b| u16::from(b - b'0')).collect()
+    }
+
+    fn ns(s: &str) -> FlexibleNumeralString {
+        FlexibleNumeralString::from_decimal_str(s).unwrap()
+    }
+
+    #[test]
+    fn check_digits() {
+        assert_eq!(LuhnAlgo::compute(&digits("7992739871")), 3);
+        assert_eq!(DammAlgo::compute(&digits("572")), 4);
+        assert_eq!(VerhoeffAlgo::compute(&digits("236")), 3);
+        assert_eq!(VerhoeffAlgo::compute(&digits("12345")), 1);
+
+        // Damm and Verhoeff detect every single-digit error and adjacent transposition.
+        fn detects_errors<C: ChecksumAlgo>() {
+            let body = digits("8473629150");
+            let check = C::compute(&body);
+            for i in 0..body.len() {
+                for d in 0..10 {
+                    let mut changed = body.clone();
+                    changed[i] = d;
+                    assert_eq!(C::compute(&changed) == check, d == body[i]);
+                }
+                if i + 1 < body.len() {
+                    let mut swapped = body.clone();
+                    swapped.swap(i, i + 1);
+                    assert_ne!(C::compute(&swapped), check);
+                }
+            }
+        }
+        detects_errors::<DammAlgo>();
+        detects_errors::<VerhoeffAlgo>();
+    }
+
+    fn round_trip<C: ChecksumAlgo>() {
+        let ff = ChecksumPreservingFF1::<_, C>::new(FF1::<Aes256>::new(&[0; 32], 10).unwrap());
+        let mut pt = digits("411111111111111");
+        pt.push(C::compute(&pt));
+        let pt = FlexibleNumeralString::from(pt);
+
+        let ct = ff.encrypt(b"tweak", &pt).unwrap();
+        let ct_digits: Vec<u16> = ff.encrypt(b"tweak", &pt).unwrap().into();
+        let (body, check) = ct_digits.split_at(15);
+        assert_eq!(C::compute(body), check[0]);
+        assert_eq!(
+            body,
+            Vec::from(
+                ff.inner()
+                    .encrypt(b"tweak", &ns("411111111111111"))
+                    .unwrap()
+            )
+        );
+
+        assert_eq!(ff.decrypt(b"tweak", &ct).unwrap(), pt);
+
+        let mut invalid = ct_digits.clone();
+        invalid[15] = (invalid[15] + 1) % 10;
+        assert_eq!(
+            ff.decrypt(b"tweak", &FlexibleNumeralString::from(invalid))
+                .unwrap_err(),
+            VerificationError::ChecksumVerificationFailed
+        );
+    }
+
+    #[test]
+    fn encrypt_decrypt() {
+        round_trip::<LuhnAlgo>();
+        round_trip::<DammAlgo>();
+        round_trip::<VerhoeffAlgo>();
+    }
+
+    #[test]
+    fn errors() {
+        let ff =
+            ChecksumPreservingFF1::<_, LuhnAlgo>::new(FF1::<Aes256>::new(&[0; 32], 10).unwrap());
+        assert!(ff.encrypt(&[], &ns("1234567")).is_ok());
+        assert_eq!(
+            ff.encrypt(&[], &ns("123456")).unwrap_err(),
+            NumeralStringError::TooShort {
+                ns_len: 6,
+                min_len: 7,
+            }
+        );
+        assert_eq!(
+            ff.encrypt(&[], &FlexibleNumeralString::from(vec![10; 7]))
+                .unwrap_err(),
+            NumeralStringError::InvalidNumeral {
+                position: 0,
+                value: 10,
+                radix: 10,
+            }
+        );
+        assert_eq!(
+            ff.decrypt(&[], &ns("123456")).unwrap_err(),
+            VerificationError::NumeralString(NumeralStringError::TooShort {
+                ns_len: 6,
+                min_len: 7,
+            })
+        );
+
+        let ff =
+            ChecksumPreservingFF1::<_, LuhnAlgo>::new(FF1::<Aes256>::new(&[0; 32], 16).unwrap());
+        assert_eq!(
+            ff.encrypt(&[], &ns("1234567")).unwrap_err(),
+            NumeralStringError::InvalidForRadix(16)
+        );
+    }
+}
@@ -0,0 +1,241 @@
+//! Evidence that an FF1 instance meets the requirements of NIST SP 800-38G.
+
+use alloc::{format, string::String, vec::Vec};
+use core::fmt::Write;
+
+use cipher::{BlockCipher, BlockEncrypt};
+
+use super::{
+    Prf, FF1, MAX_NS_LEN, MAX_RADIX, MAX_TWEAK_LEN, MIN_NS_DOMAIN_SIZE, MIN_RADIX, NIST_BLOCK_SIZE,
+};
+
+/// The result of checking one requirement of NIST SP 800-38G.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConformanceCheck {
+    /// The identifier of the requirement, such as `"radix"`.
+    pub requirement: String,
+    /// Whether the FF1 instance satisfies the requirement.
+    pub satisfied: bool,
+    /// A description of what was checked, and the values that were found.
+    pub evidence: String,
+}
+
+/// A report of the NIST SP 800-38G requirements that an [`FF1`] instance satisfies.
+///
+/// The report checks the following requirements, with these identifiers:
+///
+/// - `block-size`: the block cipher has a 128-bit block size.
+/// - `radix`: the radix is in [2..2^16].
+/// - `domain-size`: numeral strings of the minimum length have at least one million
+///   values, that is `radix^minlen >= 10^6`.
+/// - `tweak-length`: tweaks are bounded by `maxTlen`, and numeral strings by `maxlen`,
+///   both of which are less than 2^32.
+/// - `rounds`: the Feistel network has 10 rounds.
+/// - `algorithm-id`: the PRF input block `P` begins with the algorithm identifier
+///   `[1, 2, 1]`, followed by the radix and `[10]`.
+///
+/// This is not a compliance certification: it only gives auditors evidence for the
+/// parameters of an instance, and a starting point for their own review.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConformanceReport {
+    checks: Vec<ConformanceCheck>,
+}
+
+impl ConformanceReport {
+    /// Checks the given FF1 instance against each requirement.
+    pub fn generate<CIPH: BlockCipher + BlockEncrypt + Clone>(ff: &FF1<CIPH>) -> Self {
+        let mut checks = Vec::new();
+        let mut check = |requirement: &str, satisfied: bool, evidence: String| {
+            checks.push(ConformanceCheck {
+                requirement: requirement.into(),
+                satisfied,
+                evidence,
+            })
+        };
+
+        let block_size = CIPH::block_size();
+        check(
+            "block-size",
+            block_size == NIST_BLOCK_SIZE,
+            format!(
+                "the block cipher has a {}-bit block size; FF1 requires {} bits",
+                8 * block_size,
+                8 * NIST_BLOCK_SIZE,
+            ),
+        );
+
+        let (radix, min_len) = (ff.radix.to_u32(), ff.radix.min_len());
+        check(
+            "radix",
+            (MIN_RADIX..=MAX_RADIX).contains(&radix),
+            format!("radix {} is in [{}..{}]", radix, MIN_RADIX, MAX_RADIX),
+        );
+
+        let domain = (0..min_len).fold(1u64, |d, _| d.saturating_mul(u64::from(radix)));
+        check(
+            "domain-size",
+            domain >= MIN_NS_DOMAIN_SIZE,
+            format!(
+                "radix^minlen = {}^{} = {}, which must be at least {}",
+                radix, min_len, domain, MIN_NS_DOMAIN_SIZE,
+            ),
+        );
+
+        check(
+            "tweak-length",
+            MAX_TWEAK_LEN <= u32::MAX as usize && MAX_NS_LEN <= u32::MAX as usize,
+            format!(
+                "tweaks longer than maxTlen = {} bytes and numeral strings longer than \
+                 maxlen = {} numerals are rejected",
+                MAX_TWEAK_LEN, MAX_NS_LEN,
+            ),
+        );
+
+        check(
+            "rounds",
+            ff.faistel_rounds == 10,
+            format!(
+                "the Feistel network has {} rounds; FF1 requires 10",
+                ff.faistel_rounds,
+            ),
+        );
+
+        // Compare the cached PRF state, which has processed the start of P, against
+        // one that processes the expected bytes.
+        let mut expected_p = [1, 2, 1, 0, 0, 0, 10, 0, 0, 0, 0, 0, 0, 0, 0, 0];
+        expected_p[3..6].copy_from_slice(&radix.to_be_bytes()[1..]);
+        let mut expected = Prf::new(&ff.ciph);
+        expected.update(&expected_p);
+        let mut actual = ff.prf_static.clone();
+        actual.update(&expected_p[7..]);
+        let mut p_hex = String::new();
+        for b in &expected_p[..7] {
+            write!(p_hex, "{:02x}", b).expect("writing to a String cannot fail");
+        }
+        check(
+            "algorithm-id",
+            actual.finalize_into_array() == expected.finalize_into_array(),
+            format!(
+                "the PRF input block P begins with {} (algorithm identifier 010201, radix \
+                 and 0a)",
+                p_hex,
+            ),
+        );
+
+        ConformanceReport { checks }
+    }
+
+    /// Returns the result of each check.
+    pub fn checks(&self) -> &[ConformanceCheck] {
+        &self.checks
+    }
+
+    /// Returns `true` if every requirement is satisfied.
+    pub fn is_conformant(&self) -> bool {
+        self.checks.iter().all(|c| c.satisfied)
+    }
+
+    /// Returns this report as a JSON object, for machine-readable audit records.
+    ///
+    /// The object has a `conformant` boolean, and a `checks` array of objects with the
+    /// fields of [`ConformanceCheck`].
+    pub fn to_json(&self) -> String {
+        let mut json = format!("{{\"conformant\":{},\"checks\":[", self.is_conformant());
+        for (i, c) in self.checks.iter().enumerate() {
+            if i > 0 {
+                json.push(',');
+            }
+            json.push_str("{\"requirement\":");
+            push_json_string(&mut json, &c.requirement);
+            write!(json, ",\"satisfied\":{},\"evidence\":", c.satisfied)
+                .expect("writing to a String cannot fail");
+            push_json_string(&mut json, &c.evidence);
+            json.push('}');
+        }
+        json.push_str("]}");
+        json
+    }
+}
+
+/// Appends `s` to `json` as a JSON string literal.
+fn push_json_string(json: &mut String, s: &str) {
+    json.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            c if u32::from(c) < 0x20 => {
+                write!(json, "\\u{:04x}", u32::from(c)).expect("writing to a String cannot fail")
+            }
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+}
+
+#[cfg(test)]
+mod tests {
+    use aes::Aes256;
+
+    use super::{push_json_string, ConformanceReport};
+    use crate::ff1::FF1;
+
+    #[test]
+    fn conformant() {
+        let report = ConformanceReport::generate(&FF1::<Aes256>::new(&[0; 32], 10).unwrap());
+        assert!(report.is_conformant());
+        assert_eq!(
+            report
+                .checks()
+                .iter()
+                .map(|c| c.requirement.as_str())
+                .collect::<Vec<_>>(),
+            [
+                "block-size",
+                "radix",
+                "domain-size",
+                "tweak-length",
+                "rounds",
+                "algorithm-id"
+            ],
+        );
+        assert_eq!(
+            report.checks()[2].evidence,
+            "radix^minlen = 10^6 = 1000000, which must be at least 1000000"
+        );
+        assert_eq!(
+            report.checks()[5].evidence,
+            "the PRF input block P begins with 01020100000a0a (algorithm identifier \
+             010201, radix and 0a)"
+        );
+
+        let json: serde_json::Value = serde_json::from_str(&report.to_json()).unwrap();
+        assert_eq!(json["conformant"], true);
+        assert_eq!(json["checks"][1]["requirement"], "radix");
+        assert_eq!(json["checks"][1]["satisfied"], true);
+        assert_eq!(json["checks"][1]["evidence"], "radix 10 is in [2..65536]");
+    }
+
+    #[test]
+    fn reduced_rounds() {
+        let ff = FF1::<Aes256>::new_with_faistel_rounds(&[0; 32], 10, 8).unwrap();
+        let report = ConformanceReport::generate(&ff);
+        assert!(!report.is_conformant());
+        let failed: Vec<_> = report.checks().iter().filter(|c| !c.satisfied).collect();
+        assert_eq!(failed.len(), 1);
+        assert_eq!(failed[0].requirement, "rounds");
+        assert_eq!(
+            failed[0].evidence,
+            "the Feistel network has 8 rounds; FF1 requires 10"
+        );
+        assert!(report.to_json().starts_with("{\"conformant\":false,"));
+    }
+
+    #[test]
+    fn json_strings() {
+        let mut json = String::new();
+        push_json_string(&mut json, "a\"b\\c\nd\u{1}é");
+        assert_eq!(json, r#""a\"b\\c\nd\u0001é""#);
+    }
+}
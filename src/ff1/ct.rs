@@ -0,0 +1,239 @@
+//! A numeral string whose FF1 arithmetic is constant-time, backed by `crypto-bigint`.
+
+use core::{num::NonZeroU32, ops::Range};
+
+use alloc::{vec, vec::Vec};
+use crypto_bigint::{Limb, NonZero, Uint};
+
+use super::{InspectableOps, NumeralString, NumeralStringMut, Operations, SliceableNumeralString};
+
+/// A numeral string whose FF1 arithmetic runs in constant time.
+///
+/// [`FlexibleNumeralString`](super::FlexibleNumeralString) performs its modular
+/// arithmetic with `BigUint`, whose running time depends on the values being operated
+/// on. `CtNumeralString` instead converts each half of the numeral string into a
+/// fixed-size [`Uint<LIMBS>`], and uses [`Uint::add_mod`] and [`Uint::sub_mod`]. The
+/// sequence of operations depends only on the radix and the lengths of the numeral
+/// string and tweak, not on the values of the numerals.
+///
+/// `LIMBS` must be large enough to hold `radix^v`, where `v` is the length of the
+/// longer half of the numeral string (half the length, rounded up). For example, a
+/// 32-digit decimal numeral string needs `10^16 < 2^64`, which is one limb on 64-bit
+/// targets and two limbs on 32-bit targets.
+///
+/// This requires the `ct-ops` feature flag.
+#[derive(Clone)]
+#[cfg_attr(test, derive(Debug))]
+pub struct CtNumeralString<const LIMBS: usize>(Vec<u16>);
+
+impl<const LIMBS: usize> From<Vec<u16>> for CtNumeralString<LIMBS> {
+    fn from(v: Vec<u16>) -> Self {
+        CtNumeralString(v)
+    }
+}
+
+impl<const LIMBS: usize> From<CtNumeralString<LIMBS>> for Vec<u16> {
+    fn from(ns: CtNumeralString<LIMBS>) -> Self {
+        ns.0
+    }
+}
+
+impl<const LIMBS: usize> NumeralString for CtNumeralString<LIMBS> {
+    type Ops = Self;
+
+    fn is_valid(&self, radix: u32) -> bool {
+        self.0.iter().all(|n| (u32::from(*n) < radix))
+    }
+
+    fn numeral_count(&self) -> usize {
+        self.0.len()
+    }
+
+    fn split(&self) -> (Self, Self) {
+        let mut front = self.0.clone();
+        let back = front.split_off(self.0.len() / 2);
+        (CtNumeralString(front), CtNumeralString(back))
+    }
+
+    fn concat(mut a: Self, mut b: Self) -> Self {
+        a.0.append(&mut b.0);
+        a
+    }
+
+    fn reversed(&self) -> Self {
+        CtNumeralString(self.0.iter().rev().copied().collect())
+    }
+}
+
+impl<const LIMBS: usize> NumeralStringMut for CtNumeralString<LIMBS> {
+    fn replace_with(&mut self, a: Self, b: Self) {
+        self.0.clear();
+        self.0.extend_from_slice(&a.0);
+        self.0.extend_from_slice(&b.0);
+    }
+}
+
+impl<const LIMBS: usize> SliceableNumeralString for CtNumeralString<LIMBS> {
+    fn slice(&self, range: Range<usize>) -> Self {
+        CtNumeralString(self.0[range].to_vec())
+    }
+
+    fn splice(&mut self, range: Range<usize>, replacement: Self) {
+        self.0[range].copy_from_slice(&replacement.0);
+    }
+}
+
+/// Returns `radix^m`.
+///
+/// # Panics
+///
+/// Panics if `radix^m` does not fit in a `Uint<LIMBS>`. This only depends on public
+/// values, so branching on it does not leak anything about the numerals.
+fn modulus<const LIMBS: usize>(radix: u32, m: usize) -> Uint<LIMBS> {
+    let radix = Uint::<LIMBS>::from_u32(radix);
+    (0..m).fold(Uint::ONE, |p, _| {
+        let (lo, hi) = p.mul_wide(&radix);
+        assert_eq!(hi, Uint::ZERO, "radix^m does not fit in {} limbs", LIMBS);
+        lo
+    })
+}
+
+impl<const LIMBS: usize> CtNumeralString<LIMBS> {
+    /// Returns $NUM_{radix}(X)$.
+    fn to_uint(&self, radix: u32) -> Uint<LIMBS> {
+        let radix = Uint::<LIMBS>::from_u32(radix);
+        self.0.iter().fold(Uint::ZERO, |acc, &n| {
+            acc.wrapping_mul(&radix).wrapping_add(&Uint::from_u16(n))
+        })
+    }
+
+    /// Returns $STR^m_{radix}(x)$.
+    fn from_uint(mut x: Uint<LIMBS>, radix: u32, m: usize) -> Self {
+        let radix = NonZero::<Limb>::from(NonZeroU32::new(radix).expect("radix is non-zero"));
+        let mut numerals = vec![0; m];
+        for n in numerals.iter_mut().rev() {
+            let (q, r) = x.div_rem_limb(radix);
+            // The remainder is less than the radix, which fits in a u16.
+            *n = r.0 as u16;
+            x = q;
+        }
+        CtNumeralString(numerals)
+    }
+}
+
+/// Returns the big-endian integer `bytes`, reduced modulo `p`.
+///
+/// The integer is processed one bit at a time, so that every intermediate value is
+/// already reduced and [`Uint::add_mod`]'s preconditions hold.
+fn reduce_be_bytes<const LIMBS: usize>(
+    bytes: impl Iterator<Item = u8>,
+    p: &Uint<LIMBS>,
+) -> Uint<LIMBS> {
+    let mut acc = Uint::ZERO;
+    for byte in bytes {
+        for i in (0..8).rev() {
+            acc = acc.add_mod(&acc, p);
+            acc = acc.add_mod(&Uint::from_u8((byte >> i) & 1), p);
+        }
+    }
+    acc
+}
+
+impl<const LIMBS: usize> Operations for CtNumeralString<LIMBS> {
+    type Bytes = Vec<u8>;
+
+    fn numeral_count(&self) -> usize {
+        self.0.len()
+    }
+
+    fn to_be_bytes(&self, radix: u32, b: usize) -> Self::Bytes {
+        let x = self.to_uint(radix);
+        let limbs = x.as_limbs();
+        (0..b)
+            .rev()
+            .map(|i| match limbs.get(i / Limb::BYTES) {
+                Some(limb) => (limb.0 >> (8 * (i % Limb::BYTES))) as u8,
+                None => 0,
+            })
+            .collect()
+    }
+
+    fn add_mod_exp(self, other: impl Iterator<Item = u8>, radix: u32, m: usize) -> Self {
+        let p = modulus(radix, m);
+        let y = reduce_be_bytes(other, &p);
+        Self::from_uint(self.to_uint(radix).add_mod(&y, &p), radix, m)
+    }
+
+    fn sub_mod_exp(self, other: impl Iterator<Item = u8>, radix: u32, m: usize) -> Self {
+        let p = modulus(radix, m);
+        let y = reduce_be_bytes(other, &p);
+        Self::from_uint(self.to_uint(radix).sub_mod(&y, &p), radix, m)
+    }
+}
+
+impl<const LIMBS: usize> InspectableOps for CtNumeralString<LIMBS> {
+    type Numeral = u16;
+
+    fn to_numerals(&self) -> &[u16] {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aes::{Aes128, Aes192, Aes256};
+
+    use super::CtNumeralString;
+    use crate::ff1::{
+        test_vectors::{self, AesType},
+        FlexibleNumeralString, FF1,
+    };
+
+    #[test]
+    fn test_vectors() {
+        for tv in test_vectors::get() {
+            // 64 limbs hold the halves of every test vector.
+            let pt = CtNumeralString::<64>::from(tv.pt.clone());
+            let ct = CtNumeralString::<64>::from(tv.ct.clone());
+            let (ct, pt) = match tv.aes {
+                AesType::AES128 => {
+                    let ff = FF1::<Aes128>::new(&tv.key, tv.radix).unwrap();
+                    (ff.encrypt(&tv.tweak, &pt), ff.decrypt(&tv.tweak, &ct))
+                }
+                AesType::AES192 => {
+                    let ff = FF1::<Aes192>::new(&tv.key, tv.radix).unwrap();
+                    (ff.encrypt(&tv.tweak, &pt), ff.decrypt(&tv.tweak, &ct))
+                }
+                AesType::AES256 => {
+                    let ff = FF1::<Aes256>::new(&tv.key, tv.radix).unwrap();
+                    (ff.encrypt(&tv.tweak, &pt), ff.decrypt(&tv.tweak, &ct))
+                }
+            };
+            assert_eq!(Vec::from(ct.unwrap()), tv.ct);
+            assert_eq!(Vec::from(pt.unwrap()), tv.pt);
+        }
+    }
+
+    #[test]
+    fn matches_flexible() {
+        let ff = FF1::<Aes256>::new(&[7; 32], 10).unwrap();
+        let pt: Vec<u16> = (0..32).map(|i| (i * 7) % 10).collect();
+        let ct = ff
+            .encrypt(b"tweak", &CtNumeralString::<2>::from(pt.clone()))
+            .unwrap();
+        assert_eq!(
+            Vec::from(ct),
+            Vec::from(
+                ff.encrypt(b"tweak", &FlexibleNumeralString::from(pt))
+                    .unwrap()
+            ),
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "does not fit")]
+    fn too_few_limbs() {
+        let ff = FF1::<Aes256>::new(&[0; 32], 10).unwrap();
+        let _ = ff.encrypt(&[], &CtNumeralString::<1>::from(vec![0; 64]));
+    }
+}
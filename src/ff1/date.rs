@@ -0,0 +1,306 @@
+//! Format-preserving encryption of calendar dates.
+
+use core::fmt;
+
+use alloc::vec::Vec;
+use cipher::{BlockCipher, BlockEncrypt};
+
+use super::{DateError, FlexibleNumeralString, NumeralString, NumeralStringError, FF1};
+
+const RADIX: u32 = 10;
+const DATE_LEN: usize = 8;
+
+/// The order of the year, month and day digits in a [`DateNumeralString`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DateFormat {
+    /// `YYYYMMDD`.
+    Ymd,
+    /// `MMDDYYYY`.
+    Mdy,
+    /// `DDMMYYYY`.
+    Dmy,
+}
+
+impl DateFormat {
+    /// Returns the offsets of the year, month and day digits.
+    fn offsets(self) -> (usize, usize, usize) {
+        match self {
+            DateFormat::Ymd => (0, 4, 6),
+            DateFormat::Mdy => (4, 0, 2),
+            DateFormat::Dmy => (4, 2, 0),
+        }
+    }
+}
+
+/// A date in the years 0 to 9999, as a string of 8 decimal digits.
+///
+/// The numeral string does not record its [`DateFormat`], which must instead be given
+/// to each method. Encrypting a date with [`FF1::encrypt`] generally produces digits
+/// that are not a valid date; use [`FF1::encrypt_date`] to obtain a valid date.
+#[cfg_attr(test, derive(Debug))]
+pub struct DateNumeralString(Vec<u16>);
+
+fn is_leap_year(year: u16) -> bool {
+    year % 4 == 0 && (year % 100 != 0 || year % 400 == 0)
+}
+
+fn days_in_month(year: u16, month: u8) -> u8 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+impl DateNumeralString {
+    /// Creates a `DateNumeralString` for the given year, month and day, in the given
+    /// format.
+    ///
+    /// Returns `None` if the year is greater than 9999, or if the month and day are not
+    /// a valid date in that year of the proleptic Gregorian calendar.
+    pub fn from_ymd(year: u16, month: u8, day: u8, format: DateFormat) -> Option<Self> {
+        if year > 9999 || !(1..=12).contains(&month) || day == 0 {
+            return None;
+        }
+        if day > days_in_month(year, month) {
+            return None;
+        }
+
+        let mut digits = [0; DATE_LEN];
+        let (y, m, d) = format.offsets();
+        let write = |digits: &mut [u16; DATE_LEN], offset, len, value: u16| {
+            let mut value = value;
+            for digit in digits[offset..offset + len].iter_mut().rev() {
+                *digit = value % 10;
+                value /= 10;
+            }
+        };
+        write(&mut digits, y, 4, year);
+        write(&mut digits, m, 2, month.into());
+        write(&mut digits, d, 2, day.into());
+        Some(DateNumeralString(digits.to_vec()))
+    }
+
+    /// Returns the year, month and day of this numeral string in the given format.
+    ///
+    /// Returns `None` if the digits are not a valid date, for example because the month
+    /// is 13.
+    pub fn to_ymd(&self, format: DateFormat) -> Option<(u16, u8, u8)> {
+        if self.0.len() != DATE_LEN || !self.is_valid(RADIX) {
+            return None;
+        }
+        let read = |offset: usize, len: usize| {
+            self.0[offset..offset + len]
+                .iter()
+                .fold(0, |acc, &digit| acc * 10 + digit)
+        };
+        let (y, m, d) = format.offsets();
+        let (year, month, day) = (read(y, 4), read(m, 2) as u8, read(d, 2) as u8);
+        if (1..=12).contains(&month) && (1..=days_in_month(year, month)).contains(&day) {
+            Some((year, month, day))
+        } else {
+            None
+        }
+    }
+
+    /// Returns `true` if this numeral string is a valid date in the given format.
+    pub fn is_valid_date(&self, format: DateFormat) -> bool {
+        self.to_ymd(format).is_some()
+    }
+
+    /// Creates a `DateNumeralString` for the given date, in the given format.
+    ///
+    /// Returns `None` if the year is not in the range 0 to 9999.
+    #[cfg(feature = "chrono")]
+    pub fn from_date(date: chrono::NaiveDate, format: DateFormat) -> Option<Self> {
+        use chrono::Datelike;
+
+        let year = u16::try_from(date.year()).ok()?;
+        Self::from_ymd(year, date.month() as u8, date.day() as u8, format)
+    }
+
+    /// Returns the date of this numeral string in the given format, or `None` if the
+    /// digits are not a valid date.
+    #[cfg(feature = "chrono")]
+    pub fn to_date(&self, format: DateFormat) -> Option<chrono::NaiveDate> {
+        let (year, month, day) = self.to_ymd(format)?;
+        chrono::NaiveDate::from_ymd_opt(year.into(), month.into(), day.into())
+    }
+}
+
+impl fmt::Display for DateNumeralString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for d in &self.0 {
+            write!(f, "{}", d)?;
+        }
+        Ok(())
+    }
+}
+
+impl NumeralString for DateNumeralString {
+    type Ops = FlexibleNumeralString;
+
+    fn is_valid(&self, radix: u32) -> bool {
+        self.0.iter().all(|n| u32::from(*n) < radix)
+    }
+
+    fn numeral_count(&self) -> usize {
+        self.0.len()
+    }
+
+    fn split(&self) -> (Self::Ops, Self::Ops) {
+        FlexibleNumeralString::from(self.0.clone()).split()
+    }
+
+    fn concat(a: Self::Ops, b: Self::Ops) -> Self {
+        DateNumeralString(FlexibleNumeralString::concat(a, b).into())
+    }
+
+    fn reversed(&self) -> Self {
+        DateNumeralString(self.0.iter().rev().copied().collect())
+    }
+}
+
+impl<CIPH: BlockCipher + BlockEncrypt + Clone> FF1<CIPH> {
+    /// Encrypts the given date, returning a valid date in the same format.
+    ///
+    /// Most 8-digit numeral strings are not valid dates, so the numeral string is
+    /// encrypted repeatedly until the result is a valid date ("cycle walking"). About
+    /// 1 in 27 numeral strings is a valid date, so this takes 27 encryptions on
+    /// average.
+    ///
+    /// The FF1 instance must have been created with radix 10. Returns an error if the
+    /// radix is not 10, if `date` is not a valid date in `format`, or if no valid date
+    /// was reached within `max_attempts` encryptions.
+    pub fn encrypt_date(
+        &self,
+        tweak: &[u8],
+        date: &DateNumeralString,
+        format: DateFormat,
+        max_attempts: usize,
+    ) -> Result<DateNumeralString, DateError> {
+        self.cycle_walk_date(date, format, max_attempts, |x| self.encrypt(tweak, x))
+    }
+
+    /// Decrypts the given date, as encrypted by [`FF1::encrypt_date`].
+    ///
+    /// Decryption takes the same number of FF1 operations as the encryption did, so
+    /// succeeds if given the same `max_attempts`.
+    pub fn decrypt_date(
+        &self,
+        tweak: &[u8],
+        date: &DateNumeralString,
+        format: DateFormat,
+        max_attempts: usize,
+    ) -> Result<DateNumeralString, DateError> {
+        self.cycle_walk_date(date, format, max_attempts, |x| self.decrypt(tweak, x))
+    }
+
+    fn cycle_walk_date(
+        &self,
+        date: &DateNumeralString,
+        format: DateFormat,
+        max_attempts: usize,
+        f: impl Fn(&DateNumeralString) -> Result<DateNumeralString, NumeralStringError>,
+    ) -> Result<DateNumeralString, DateError> {
+        if self.radix.to_u32() != RADIX {
+            return Err(NumeralStringError::InvalidForRadix(self.radix.to_u32()).into());
+        }
+        // Cycle walking only forms a permutation of the valid dates if it starts from
+        // one.
+        if !date.is_valid_date(format) {
+            return Err(DateError::InvalidDate);
+        }
+
+        let mut x = None;
+        for _ in 0..max_attempts {
+            let y = f(x.as_ref().unwrap_or(date))?;
+            if y.is_valid_date(format) {
+                return Ok(y);
+            }
+            x = Some(y);
+        }
+        Err(DateError::TooManyAttempts(max_attempts))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aes::Aes256;
+
+    use super::{DateFormat, DateNumeralString};
+    use crate::ff1::{DateError, NumeralStringError, FF1};
+
+    #[test]
+    fn ymd() {
+        let date = DateNumeralString::from_ymd(2023, 11, 25, DateFormat::Ymd).unwrap();
+        assert_eq!(date.to_string(), "20231125");
+        assert_eq!(date.to_ymd(DateFormat::Ymd), Some((2023, 11, 25)));
+        // The same digits are not a valid date with the month and day first.
+        assert_eq!(date.to_ymd(DateFormat::Mdy), None);
+
+        let date = DateNumeralString::from_ymd(2024, 2, 29, DateFormat::Mdy).unwrap();
+        assert_eq!(date.to_string(), "02292024");
+        let date = DateNumeralString::from_ymd(7, 3, 1, DateFormat::Dmy).unwrap();
+        assert_eq!(date.to_string(), "01030007");
+
+        assert!(DateNumeralString::from_ymd(2023, 2, 29, DateFormat::Ymd).is_none());
+        assert!(DateNumeralString::from_ymd(1900, 2, 29, DateFormat::Ymd).is_none());
+        assert!(DateNumeralString::from_ymd(2000, 2, 29, DateFormat::Ymd).is_some());
+        assert!(DateNumeralString::from_ymd(2023, 13, 1, DateFormat::Ymd).is_none());
+        assert!(DateNumeralString::from_ymd(2023, 4, 31, DateFormat::Ymd).is_none());
+        assert!(DateNumeralString::from_ymd(10000, 1, 1, DateFormat::Ymd).is_none());
+    }
+
+    #[test]
+    fn round_trip() {
+        let ff = FF1::<Aes256>::new(&[0; 32], 10).unwrap();
+        for format in [DateFormat::Ymd, DateFormat::Mdy, DateFormat::Dmy] {
+            for day in 1..=28 {
+                let date = DateNumeralString::from_ymd(2023, 2, day, format).unwrap();
+                let ct = ff.encrypt_date(b"dob", &date, format, 1000).unwrap();
+                assert!(ct.is_valid_date(format));
+                let pt = ff.decrypt_date(b"dob", &ct, format, 1000).unwrap();
+                assert_eq!(pt.to_ymd(format), Some((2023, 2, day)));
+            }
+        }
+    }
+
+    #[test]
+    fn errors() {
+        let ff = FF1::<Aes256>::new(&[0; 32], 10).unwrap();
+        let date = DateNumeralString::from_ymd(2023, 11, 25, DateFormat::Ymd).unwrap();
+        assert_eq!(
+            ff.encrypt_date(&[], &date, DateFormat::Mdy, 1000)
+                .unwrap_err(),
+            DateError::InvalidDate,
+        );
+        assert_eq!(
+            ff.encrypt_date(&[], &date, DateFormat::Ymd, 0).unwrap_err(),
+            DateError::TooManyAttempts(0),
+        );
+
+        let ff = FF1::<Aes256>::new(&[0; 32], 16).unwrap();
+        assert_eq!(
+            ff.encrypt_date(&[], &date, DateFormat::Ymd, 1000)
+                .unwrap_err(),
+            DateError::NumeralString(NumeralStringError::InvalidForRadix(16)),
+        );
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn chrono() {
+        use chrono::NaiveDate;
+
+        let d = NaiveDate::from_ymd_opt(2023, 11, 25).unwrap();
+        let date = DateNumeralString::from_date(d, DateFormat::Dmy).unwrap();
+        assert_eq!(date.to_string(), "25112023");
+        assert_eq!(date.to_date(DateFormat::Dmy), Some(d));
+        assert_eq!(date.to_date(DateFormat::Ymd), None);
+
+        let d = NaiveDate::from_ymd_opt(-1, 1, 1).unwrap();
+        assert!(DateNumeralString::from_date(d, DateFormat::Ymd).is_none());
+    }
+}
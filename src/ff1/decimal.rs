@@ -0,0 +1,262 @@
+//! A radix-10 numeral string that operates directly on decimal digits.
+
+use core::{convert::TryFrom, fmt, ops::Range};
+
+use alloc::{vec, vec::Vec};
+
+use super::{
+    digits, InspectableOps, NumeralString, NumeralStringError, NumeralStringMut, Operations,
+    SliceableNumeralString,
+};
+
+const RADIX: u32 = 10;
+
+/// A numeral string with radix 10, backed by one byte per decimal digit.
+///
+/// Unlike [`FlexibleNumeralString`](super::FlexibleNumeralString), all FF1 arithmetic
+/// is performed directly on the digits, without converting them to a `BigUint`.
+#[cfg_attr(test, derive(Debug))]
+pub struct DecimalNumeralString(Vec<u8>);
+
+impl DecimalNumeralString {
+    /// Creates a `DecimalNumeralString` from a string of ASCII digits.
+    ///
+    /// Returns [`NumeralStringError::InvalidNumeral`] for the first byte that is not in
+    /// `b'0'..=b'9'`.
+    pub fn from_ascii(s: &[u8]) -> Result<Self, NumeralStringError> {
+        s.iter()
+            .enumerate()
+            .map(|(position, &c)| match c {
+                b'0'..=b'9' => Ok(c - b'0'),
+                _ => Err(NumeralStringError::InvalidNumeral {
+                    position,
+                    value: c.into(),
+                    radix: RADIX,
+                }),
+            })
+            .collect::<Result<_, _>>()
+            .map(DecimalNumeralString)
+    }
+
+    /// Returns the ASCII digit string for this numeral string.
+    pub fn to_ascii(&self) -> Vec<u8> {
+        self.0.iter().map(|d| d + b'0').collect()
+    }
+}
+
+impl TryFrom<&str> for DecimalNumeralString {
+    type Error = NumeralStringError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        DecimalNumeralString::from_ascii(s.as_bytes())
+    }
+}
+
+impl fmt::Display for DecimalNumeralString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for d in &self.0 {
+            write!(f, "{}", d)?;
+        }
+        Ok(())
+    }
+}
+
+impl NumeralString for DecimalNumeralString {
+    type Ops = Self;
+
+    fn is_valid(&self, radix: u32) -> bool {
+        // The digits are valid for radix 10 by construction.
+        radix == RADIX
+    }
+
+    fn numeral_count(&self) -> usize {
+        self.0.len()
+    }
+
+    fn split(&self) -> (Self, Self) {
+        let mut front = self.0.clone();
+        let back = front.split_off(self.0.len() / 2);
+        (DecimalNumeralString(front), DecimalNumeralString(back))
+    }
+
+    fn concat(mut a: Self, mut b: Self) -> Self {
+        a.0.append(&mut b.0);
+        a
+    }
+
+    fn reversed(&self) -> Self {
+        DecimalNumeralString(self.0.iter().rev().copied().collect())
+    }
+}
+
+impl NumeralStringMut for DecimalNumeralString {
+    fn replace_with(&mut self, a: Self, b: Self) {
+        self.0.clear();
+        self.0.extend_from_slice(&a.0);
+        self.0.extend_from_slice(&b.0);
+    }
+}
+
+impl SliceableNumeralString for DecimalNumeralString {
+    fn slice(&self, range: Range<usize>) -> Self {
+        DecimalNumeralString(self.0[range].to_vec())
+    }
+
+    fn splice(&mut self, range: Range<usize>, replacement: Self) {
+        self.0[range].copy_from_slice(&replacement.0);
+    }
+}
+
+impl Operations for DecimalNumeralString {
+    type Bytes = Vec<u8>;
+
+    fn numeral_count(&self) -> usize {
+        self.0.len()
+    }
+
+    fn to_be_bytes(&self, radix: u32, b: usize) -> Self::Bytes {
+        assert_eq!(radix, RADIX);
+        let mut bytes = vec![0; b];
+        digits::to_be_bytes(&self.0, RADIX, &mut bytes);
+        bytes
+    }
+
+    fn add_mod_exp(mut self, other: impl Iterator<Item = u8>, radix: u32, m: usize) -> Self {
+        assert_eq!(radix, RADIX);
+        let mut y = vec![0; m];
+        digits::reduce_be_bytes(other, RADIX, &mut y);
+        digits::add_mod(&mut self.0, &y, RADIX);
+        self
+    }
+
+    fn sub_mod_exp(mut self, other: impl Iterator<Item = u8>, radix: u32, m: usize) -> Self {
+        assert_eq!(radix, RADIX);
+        let mut y = vec![0; m];
+        digits::reduce_be_bytes(other, RADIX, &mut y);
+        digits::sub_mod(&mut self.0, &y, RADIX);
+        self
+    }
+}
+
+impl InspectableOps for DecimalNumeralString {
+    type Numeral = u8;
+
+    fn to_numerals(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::convert::TryFrom;
+
+    use aes::{Aes128, Aes192, Aes256};
+
+    use super::DecimalNumeralString;
+    use crate::ff1::{
+        test_vectors::{self, AesType},
+        FlexibleNumeralString, InspectableOps, NumeralString, NumeralStringError, Operations, FF1,
+    };
+
+    #[test]
+    fn from_ascii() {
+        let ns = DecimalNumeralString::from_ascii(b"0123456789").unwrap();
+        assert_eq!(ns.0, vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        assert_eq!(ns.to_ascii(), b"0123456789");
+
+        assert_eq!(
+            DecimalNumeralString::from_ascii(b"12a4").unwrap_err(),
+            NumeralStringError::InvalidNumeral {
+                position: 2,
+                value: 'a'.into(),
+                radix: 10,
+            },
+        );
+        assert!(DecimalNumeralString::from_ascii(b"12 4").is_err());
+    }
+
+    #[test]
+    fn display_and_try_from() {
+        let ns = DecimalNumeralString::try_from("1234567890").unwrap();
+        assert_eq!(ns.to_string(), "1234567890");
+        assert!(DecimalNumeralString::try_from("-1").is_err());
+    }
+
+    #[test]
+    fn ops_match_flexible() {
+        let digits = b"0918273645546372";
+        let other = [0xff, 0x01, 0x80, 0x7f, 0x00, 0x33, 0xc4, 0x5a, 0xff, 0x09];
+        let m = digits.len();
+
+        let to_flexible = |s: &[u8]| {
+            FlexibleNumeralString::from(s.iter().map(|c| u16::from(c - b'0')).collect::<Vec<_>>())
+        };
+        let from_flexible = |ns: FlexibleNumeralString| {
+            Vec::from(ns)
+                .into_iter()
+                .map(|d| d as u8 + b'0')
+                .collect::<Vec<_>>()
+        };
+
+        let dns = DecimalNumeralString::from_ascii(digits).unwrap();
+        let fns = to_flexible(digits);
+        assert_eq!(
+            Operations::to_be_bytes(&dns, 10, 7),
+            Operations::to_be_bytes(&fns, 10, 7),
+        );
+
+        let dns = DecimalNumeralString::from_ascii(digits).unwrap();
+        let fns = to_flexible(digits);
+        assert_eq!(
+            dns.add_mod_exp(other.iter().copied(), 10, m).to_ascii(),
+            from_flexible(fns.add_mod_exp(other.iter().copied(), 10, m)),
+        );
+
+        let dns = DecimalNumeralString::from_ascii(digits).unwrap();
+        let fns = to_flexible(digits);
+        assert_eq!(
+            dns.sub_mod_exp(other.iter().copied(), 10, m).to_ascii(),
+            from_flexible(fns.sub_mod_exp(other.iter().copied(), 10, m)),
+        );
+    }
+
+    #[test]
+    fn split_round_trip() {
+        let ns = DecimalNumeralString::try_from("12345678901").unwrap();
+        let (a, b) = ns.split();
+        assert_eq!(a.to_ascii(), b"12345");
+        assert_eq!(b.to_ascii(), b"678901");
+        assert_eq!(a.to_numerals(), &[1, 2, 3, 4, 5]);
+        assert_eq!(b.get_numeral(5), Some(1));
+        assert_eq!(b.get_numeral(6), None);
+        assert_eq!(
+            DecimalNumeralString::concat(a, b).to_ascii(),
+            b"12345678901"
+        );
+    }
+
+    #[test]
+    fn decimal() {
+        for tv in test_vectors::get().filter(|tv| tv.radix == 10) {
+            let pt = DecimalNumeralString(tv.pt.iter().map(|&d| d as u8).collect());
+            let ct = DecimalNumeralString(tv.ct.iter().map(|&d| d as u8).collect());
+            let (ct, pt) = match tv.aes {
+                AesType::AES128 => {
+                    let ff = FF1::<Aes128>::new(&tv.key, tv.radix).unwrap();
+                    (ff.encrypt(&tv.tweak, &pt), ff.decrypt(&tv.tweak, &ct))
+                }
+                AesType::AES192 => {
+                    let ff = FF1::<Aes192>::new(&tv.key, tv.radix).unwrap();
+                    (ff.encrypt(&tv.tweak, &pt), ff.decrypt(&tv.tweak, &ct))
+                }
+                AesType::AES256 => {
+                    let ff = FF1::<Aes256>::new(&tv.key, tv.radix).unwrap();
+                    (ff.encrypt(&tv.tweak, &pt), ff.decrypt(&tv.tweak, &ct))
+                }
+            };
+            let expected = |v: &[u16]| v.iter().map(|&d| d as u8).collect::<Vec<_>>();
+            assert_eq!(ct.unwrap().0, expected(&tv.ct));
+            assert_eq!(pt.unwrap().0, expected(&tv.pt));
+        }
+    }
+}
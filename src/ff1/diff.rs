@@ -0,0 +1,134 @@
+//! Position-by-position comparison of plaintexts and ciphertexts, for analysing FF1's
+//! diffusion.
+
+use alloc::vec::Vec;
+use cipher::{BlockCipher, BlockEncrypt};
+
+use super::{InspectableOps, NumeralString, NumeralStringError, FF1};
+
+/// Returns the numerals of `x`, in order.
+fn numerals<NS>(x: &NS) -> Vec<u16>
+where
+    NS: NumeralString,
+    NS::Ops: InspectableOps,
+{
+    let (a, b) = x.split();
+    a.to_numerals()
+        .iter()
+        .chain(b.to_numerals())
+        .map(|&n| n.into())
+        .collect()
+}
+
+impl<CIPH: BlockCipher + BlockEncrypt + Clone> FF1<CIPH> {
+    /// Encrypts the given numeral string, and returns `(position, plaintext numeral,
+    /// ciphertext numeral)` for every position where the numeral changed.
+    ///
+    /// For a random key, each position is unchanged with probability `1 / radix`; see
+    /// [`FF1::fixed_point_positions`] for the positions that are.
+    ///
+    /// Returns an error if the numeral string is not in the required radix.
+    pub fn diff<NS>(
+        &self,
+        tweak: &[u8],
+        plaintext: &NS,
+    ) -> Result<Vec<(usize, u16, u16)>, NumeralStringError>
+    where
+        NS: NumeralString,
+        NS::Ops: InspectableOps,
+    {
+        let ciphertext = self.encrypt(tweak, plaintext)?;
+        Ok(numerals(plaintext)
+            .into_iter()
+            .zip(numerals(&ciphertext))
+            .enumerate()
+            .filter(|(_, (p, c))| p != c)
+            .map(|(i, (p, c))| (i, p, c))
+            .collect())
+    }
+
+    /// Encrypts the given numeral string, and returns the positions where the
+    /// ciphertext numeral is the same as the plaintext numeral.
+    ///
+    /// These are the positions not returned by [`FF1::diff`]. Returns an error if the
+    /// numeral string is not in the required radix.
+    pub fn fixed_point_positions<NS>(
+        &self,
+        tweak: &[u8],
+        plaintext: &NS,
+    ) -> Result<Vec<usize>, NumeralStringError>
+    where
+        NS: NumeralString,
+        NS::Ops: InspectableOps,
+    {
+        let ciphertext = self.encrypt(tweak, plaintext)?;
+        Ok(numerals(plaintext)
+            .into_iter()
+            .zip(numerals(&ciphertext))
+            .enumerate()
+            .filter(|(_, (p, c))| p == c)
+            .map(|(i, _)| i)
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aes::Aes256;
+
+    use crate::ff1::{FlexibleNumeralString, NumeralStringError, FF1};
+
+    #[test]
+    fn diff() {
+        let ff = FF1::<Aes256>::new(&[0; 32], 10).unwrap();
+        let pt = FlexibleNumeralString::from(vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        let ct = Vec::from(ff.encrypt(b"tweak", &pt).unwrap());
+
+        let diff = ff.diff(b"tweak", &pt).unwrap();
+        let fixed = ff.fixed_point_positions(b"tweak", &pt).unwrap();
+        assert_eq!(diff.len() + fixed.len(), 10);
+        for (i, p, c) in diff {
+            assert_eq!((p, c), (pt.0[i], ct[i]));
+            assert_ne!(p, c);
+        }
+        for i in fixed {
+            assert_eq!(pt.0[i], ct[i]);
+        }
+
+        assert_eq!(
+            ff.diff(&[], &FlexibleNumeralString::from(vec![10; 6]))
+                .unwrap_err(),
+            NumeralStringError::InvalidForRadix(10),
+        );
+    }
+
+    #[test]
+    fn fixed_points_are_binomial() {
+        // Each of the 10 positions is a fixed point with probability 1/10, so the
+        // number of fixed points is Binomial(10, 0.1), with mean 1 and variance 0.9.
+        let ff = FF1::<Aes256>::new(&[1; 32], 10).unwrap();
+        let trials = 1000;
+        let counts: Vec<usize> = (0..trials)
+            .map(|i: u32| {
+                let pt = FlexibleNumeralString::from(
+                    (0..10)
+                        .map(|j| ((i * 7 + j * j * 3) % 10) as u16)
+                        .collect::<Vec<_>>(),
+                );
+                ff.fixed_point_positions(&i.to_be_bytes(), &pt)
+                    .unwrap()
+                    .len()
+            })
+            .collect();
+
+        let mean = counts.iter().sum::<usize>() as f64 / f64::from(trials);
+        let variance = counts
+            .iter()
+            .map(|&c| (c as f64 - mean).powi(2))
+            .sum::<f64>()
+            / f64::from(trials);
+        // The standard error of the mean is 0.03.
+        assert!((mean - 1.0).abs() < 0.15, "mean {}", mean);
+        assert!((variance - 0.9).abs() < 0.3, "variance {}", variance);
+    }
+}
@@ -0,0 +1,95 @@
+//! Radix arithmetic performed directly on big-endian numeral slices.
+//!
+//! These helpers let numeral string types implement [`Operations`](super::Operations)
+//! without converting to and from `BigUint`, and without allocating.
+
+/// A numeral stored in a fixed-width integer.
+pub(super) trait Digit: Copy {
+    fn to_u32(self) -> u32;
+    fn from_u32(v: u32) -> Self;
+}
+
+impl Digit for u8 {
+    fn to_u32(self) -> u32 {
+        u32::from(self)
+    }
+
+    fn from_u32(v: u32) -> Self {
+        v as u8
+    }
+}
+
+impl Digit for u16 {
+    fn to_u32(self) -> u32 {
+        u32::from(self)
+    }
+
+    fn from_u32(v: u32) -> Self {
+        v as u16
+    }
+}
+
+/// Writes $STR^{b}_{256}(NUM_{radix}(x))$ into `out`, where `b = out.len()`.
+///
+/// The caller MUST ensure that the number fits into `b` bytes.
+pub(super) fn to_be_bytes<D: Digit>(x: &[D], radix: u32, out: &mut [u8]) {
+    out.fill(0);
+    for d in x {
+        // out = out * radix + d
+        let mut carry = d.to_u32();
+        for byte in out.iter_mut().rev() {
+            let t = u32::from(*byte) * radix + carry;
+            *byte = t as u8;
+            carry = t >> 8;
+        }
+        debug_assert_eq!(carry, 0);
+    }
+}
+
+/// Writes the big-endian integer `bytes`, reduced modulo `radix^m`, into `out` as `m`
+/// numerals in base `radix`, where `m = out.len()`.
+pub(super) fn reduce_be_bytes<D: Digit>(
+    bytes: impl Iterator<Item = u8>,
+    radix: u32,
+    out: &mut [D],
+) {
+    out.fill(D::from_u32(0));
+    for byte in bytes {
+        // out = (out * 256 + byte) mod radix^m
+        let mut carry = u32::from(byte);
+        for d in out.iter_mut().rev() {
+            let t = d.to_u32() * 256 + carry;
+            *d = D::from_u32(t % radix);
+            carry = t / radix;
+        }
+    }
+}
+
+/// Computes `x = (x + y) mod radix^m`, where `m = x.len() = y.len()`.
+pub(super) fn add_mod<D: Digit>(x: &mut [D], y: &[D], radix: u32) {
+    assert_eq!(x.len(), y.len());
+    // Carries out of the most significant numeral are dropped, reducing mod radix^m.
+    let mut carry = 0;
+    for (x, y) in x.iter_mut().rev().zip(y.iter().rev()) {
+        let t = x.to_u32() + y.to_u32() + carry;
+        *x = D::from_u32(t % radix);
+        carry = t / radix;
+    }
+}
+
+/// Computes `x = (x - y) mod radix^m`, where `m = x.len() = y.len()`.
+pub(super) fn sub_mod<D: Digit>(x: &mut [D], y: &[D], radix: u32) {
+    assert_eq!(x.len(), y.len());
+    // Borrows out of the most significant numeral are dropped, reducing mod radix^m.
+    let mut borrow = 0;
+    for (x, y) in x.iter_mut().rev().zip(y.iter().rev()) {
+        let (xv, t) = (x.to_u32(), y.to_u32() + borrow);
+        if xv >= t {
+            *x = D::from_u32(xv - t);
+            borrow = 0;
+        } else {
+            *x = D::from_u32(xv + radix - t);
+            borrow = 1;
+        }
+    }
+}
@@ -0,0 +1,192 @@
+//! Descriptions of the numeral strings that an application encrypts.
+
+use super::{Alphabet, DomainSpecError, InvalidRadix, Radix};
+
+/// The set of strings that an application encrypts with FF1: a radix, a range of
+/// lengths, and optionally the [`Alphabet`] that maps characters to numerals.
+///
+/// Without an alphabet, the characters are the digits `0-9` followed by the letters
+/// `a-z` (in either case), as for [`char::is_digit`].
+///
+/// A `DomainSpec` can be given to [`FF1Builder::domain`](super::FF1Builder::domain)
+/// to create an FF1 instance with the matching radix.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DomainSpec {
+    radix: u32,
+    min_len: usize,
+    max_len: usize,
+    alphabet: Option<Alphabet>,
+}
+
+impl DomainSpec {
+    /// Creates a domain of strings in the given radix with between `min_len` and
+    /// `max_len` characters.
+    ///
+    /// Returns an error if the given radix is not in [2..2^16].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `min_len > max_len`.
+    pub fn new(radix: u32, min_len: usize, max_len: usize) -> Result<Self, InvalidRadix> {
+        Radix::from_u32(radix)?;
+        assert!(min_len <= max_len, "min_len must be at most max_len");
+        Ok(DomainSpec {
+            radix,
+            min_len,
+            max_len,
+            alphabet: None,
+        })
+    }
+
+    /// Creates a domain of strings over the given alphabet with between `min_len` and
+    /// `max_len` characters.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `min_len > max_len`.
+    pub fn with_alphabet(alphabet: Alphabet, min_len: usize, max_len: usize) -> Self {
+        assert!(min_len <= max_len, "min_len must be at most max_len");
+        DomainSpec {
+            radix: alphabet.radix(),
+            min_len,
+            max_len,
+            alphabet: Some(alphabet),
+        }
+    }
+
+    /// Payment card numbers: 13 to 19 decimal digits.
+    pub fn credit_card() -> Self {
+        Self::with_alphabet(Alphabet::decimal(), 13, 19)
+    }
+
+    /// US Social Security numbers without separators: 9 decimal digits.
+    pub fn ssn() -> Self {
+        Self::with_alphabet(Alphabet::decimal(), 9, 9)
+    }
+
+    /// UUIDs without hyphens: 32 lowercase hexadecimal digits.
+    pub fn uuid_hex() -> Self {
+        Self::with_alphabet(Alphabet::hex_lower(), 32, 32)
+    }
+
+    /// Returns the radix to use for FF1 with this domain.
+    pub fn to_ff1_radix(&self) -> u32 {
+        self.radix
+    }
+
+    /// Returns the minimum number of characters in a string in this domain.
+    pub fn min_len(&self) -> usize {
+        self.min_len
+    }
+
+    /// Returns the maximum number of characters in a string in this domain.
+    pub fn max_len(&self) -> usize {
+        self.max_len
+    }
+
+    /// Returns the alphabet of this domain, if it has one.
+    pub fn alphabet(&self) -> Option<&Alphabet> {
+        self.alphabet.as_ref()
+    }
+
+    /// Checks that the given string is in this domain.
+    ///
+    /// Returns an error if the number of characters is out of bounds, or if any
+    /// character is not in the domain's character set.
+    pub fn validate_str(&self, s: &str) -> Result<(), DomainSpecError> {
+        let len = s.chars().count();
+        if len < self.min_len {
+            return Err(DomainSpecError::TooShort {
+                len,
+                min_len: self.min_len,
+            });
+        }
+        if len > self.max_len {
+            return Err(DomainSpecError::TooLong {
+                len,
+                max_len: self.max_len,
+            });
+        }
+
+        let is_valid = |c: char| match &self.alphabet {
+            // The characters of an alphabet are sorted.
+            Some(alphabet) => alphabet.chars().binary_search(&c).is_ok(),
+            // char::is_digit panics for radixes above 36.
+            None => self.radix <= 36 && c.is_digit(self.radix),
+        };
+        match s.chars().find(|&c| !is_valid(c)) {
+            Some(c) => Err(DomainSpecError::InvalidCharacter(c)),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::DomainSpec;
+    use crate::ff1::{Alphabet, DomainSpecError};
+
+    #[test]
+    fn presets() {
+        let cc = DomainSpec::credit_card();
+        assert_eq!(cc.to_ff1_radix(), 10);
+        assert_eq!((cc.min_len(), cc.max_len()), (13, 19));
+        assert_eq!(cc.validate_str("4111111111111111"), Ok(()));
+        assert_eq!(
+            cc.validate_str("411111111111"),
+            Err(DomainSpecError::TooShort {
+                len: 12,
+                min_len: 13
+            })
+        );
+        assert_eq!(
+            cc.validate_str("41111111111111111111"),
+            Err(DomainSpecError::TooLong {
+                len: 20,
+                max_len: 19
+            })
+        );
+
+        let ssn = DomainSpec::ssn();
+        assert_eq!(ssn.validate_str("123456789"), Ok(()));
+        assert_eq!(
+            ssn.validate_str("123-45-67"),
+            Err(DomainSpecError::InvalidCharacter('-'))
+        );
+
+        let uuid = DomainSpec::uuid_hex();
+        assert_eq!(uuid.to_ff1_radix(), 16);
+        assert_eq!(
+            uuid.validate_str("67e5504410b1426f9247bb680e5fe0c8"),
+            Ok(())
+        );
+        assert_eq!(
+            uuid.validate_str("67E5504410B1426F9247BB680E5FE0C8"),
+            Err(DomainSpecError::InvalidCharacter('E'))
+        );
+    }
+
+    #[test]
+    fn custom() {
+        let spec = DomainSpec::new(36, 4, 8).unwrap();
+        assert_eq!(spec.validate_str("AbC9z"), Ok(()));
+        assert_eq!(
+            spec.validate_str("ab_c"),
+            Err(DomainSpecError::InvalidCharacter('_'))
+        );
+        assert!(DomainSpec::new(1, 4, 8).is_err());
+        // Without an alphabet, no characters are valid for radixes above 36.
+        assert_eq!(
+            DomainSpec::new(100, 1, 1).unwrap().validate_str("a"),
+            Err(DomainSpecError::InvalidCharacter('a'))
+        );
+
+        let spec = DomainSpec::with_alphabet(Alphabet::base58(), 1, 4);
+        assert_eq!(spec.to_ff1_radix(), 58);
+        assert_eq!(spec.validate_str("abc"), Ok(()));
+        assert_eq!(
+            spec.validate_str("0"),
+            Err(DomainSpecError::InvalidCharacter('0'))
+        );
+    }
+}
@@ -0,0 +1,93 @@
+//! Encrypted fields for domain models.
+
+use core::marker::PhantomData;
+
+use cipher::{BlockCipher, BlockEncrypt};
+
+use super::{NumeralString, NumeralStringError, FF1};
+
+/// The ciphertext of a value of type `T`, encrypted as the numeral string `NS`.
+///
+/// This can be used as the type of an encrypted field in a domain model. With the
+/// `serde` feature flag, it is serialized in the same way as its ciphertext, so
+/// models can be stored without ever holding the plaintext.
+///
+/// The tweak is not stored, so that it can be derived from the context of the field
+/// (for example with a [`TweakDeriver`](super::TweakDeriver)); the same tweak must be
+/// given to [`EncryptedValue::decrypt`].
+pub struct EncryptedValue<T, NS> {
+    ciphertext: NS,
+    _plaintext: PhantomData<fn() -> T>,
+}
+
+impl<T, NS> EncryptedValue<T, NS> {
+    /// Wraps a numeral string that is already encrypted.
+    pub fn from_ciphertext(ciphertext: NS) -> Self {
+        EncryptedValue {
+            ciphertext,
+            _plaintext: PhantomData,
+        }
+    }
+
+    /// Returns the ciphertext.
+    pub fn ciphertext(&self) -> &NS {
+        &self.ciphertext
+    }
+
+    /// Returns the ciphertext, consuming this value.
+    pub fn into_ciphertext(self) -> NS {
+        self.ciphertext
+    }
+}
+
+impl<T: From<NS>, NS: NumeralString> EncryptedValue<T, NS> {
+    /// Encrypts the given numeral string.
+    ///
+    /// Returns an error if the numeral string is not in the required radix.
+    pub fn encrypt<CIPH: BlockCipher + BlockEncrypt + Clone>(
+        plain: &NS,
+        ff: &FF1<CIPH>,
+        tweak: &[u8],
+    ) -> Result<Self, NumeralStringError> {
+        ff.encrypt(tweak, plain).map(Self::from_ciphertext)
+    }
+
+    /// Decrypts this value.
+    ///
+    /// Returns an error if the numeral string is not in the required radix.
+    pub fn decrypt<CIPH: BlockCipher + BlockEncrypt + Clone>(
+        &self,
+        ff: &FF1<CIPH>,
+        tweak: &[u8],
+    ) -> Result<T, NumeralStringError> {
+        ff.decrypt(tweak, &self.ciphertext).map(T::from)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aes::Aes256;
+
+    use super::EncryptedValue;
+    use crate::ff1::{FlexibleNumeralString, FF1};
+
+    #[test]
+    fn round_trip() {
+        let ff = FF1::<Aes256>::new(&[0; 32], 10).unwrap();
+        let pt = FlexibleNumeralString::from(vec![4, 1, 1, 1, 1, 1, 1, 1]);
+
+        let value = EncryptedValue::<Vec<u16>, _>::encrypt(&pt, &ff, b"card").unwrap();
+        assert_eq!(
+            Vec::from(ff.decrypt(b"card", value.ciphertext()).unwrap()),
+            vec![4, 1, 1, 1, 1, 1, 1, 1],
+        );
+        assert_eq!(
+            value.decrypt(&ff, b"card").unwrap(),
+            vec![4, 1, 1, 1, 1, 1, 1, 1]
+        );
+        assert_ne!(
+            value.decrypt(&ff, b"other").unwrap(),
+            vec![4, 1, 1, 1, 1, 1, 1, 1]
+        );
+    }
+}
@@ -0,0 +1,158 @@
+//! Tweaks that change every epoch, for tokens that are only valid for a limited time.
+
+use std::time::{Duration, SystemTime};
+
+use cipher::{BlockCipher, BlockEncrypt};
+
+use super::{NumeralString, NumeralStringError, FF1};
+
+/// A tweak that changes every `epoch_duration`, such as daily or weekly.
+///
+/// The tweak for a given time is `base_tweak` followed by the number of whole epochs
+/// since `reference_time`, as a 4-byte big-endian integer. Values encrypted with
+/// [`FF1::encrypt_with_epoch_tweak`] therefore produce different tokens in each epoch,
+/// and a token cannot be decrypted with the tweak for a later epoch.
+///
+/// To decrypt a token, the caller must know the epoch in which it was encrypted, and
+/// pass [`EpochTweak::tweak_for_epoch`] to [`FF1::decrypt`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EpochTweak {
+    base_tweak: Vec<u8>,
+    epoch_duration: Duration,
+    reference_time: SystemTime,
+}
+
+impl EpochTweak {
+    /// Creates an epoch tweak whose epoch 0 starts at `reference_time`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `epoch_duration` is zero.
+    pub fn new(base_tweak: &[u8], epoch_duration: Duration, reference_time: SystemTime) -> Self {
+        assert!(!epoch_duration.is_zero(), "epoch duration must be non-zero");
+        EpochTweak {
+            base_tweak: base_tweak.to_vec(),
+            epoch_duration,
+            reference_time,
+        }
+    }
+
+    /// Returns the epoch containing the time `t`.
+    ///
+    /// Times before the reference time are in epoch 0, and epochs after `u32::MAX`
+    /// saturate to `u32::MAX`.
+    pub fn epoch_for_time(&self, t: SystemTime) -> u32 {
+        let elapsed = t
+            .duration_since(self.reference_time)
+            .unwrap_or(Duration::ZERO);
+        let epoch = elapsed.as_nanos() / self.epoch_duration.as_nanos();
+        u32::try_from(epoch).unwrap_or(u32::MAX)
+    }
+
+    /// Returns the current epoch.
+    pub fn current_epoch(&self) -> u32 {
+        self.epoch_for_time(SystemTime::now())
+    }
+
+    /// Returns the tweak for the given epoch.
+    pub fn tweak_for_epoch(&self, epoch: u32) -> Vec<u8> {
+        let mut tweak = Vec::with_capacity(self.base_tweak.len() + 4);
+        tweak.extend_from_slice(&self.base_tweak);
+        tweak.extend_from_slice(&epoch.to_be_bytes());
+        tweak
+    }
+
+    /// Returns the tweak for the epoch containing the time `t`.
+    pub fn tweak_for_time(&self, t: SystemTime) -> Vec<u8> {
+        self.tweak_for_epoch(self.epoch_for_time(t))
+    }
+
+    /// Returns the tweak for the current epoch.
+    ///
+    /// If the caller needs to record the epoch, it should call
+    /// [`EpochTweak::current_epoch`] once and use [`EpochTweak::tweak_for_epoch`], as
+    /// the epoch may change between two calls.
+    pub fn current_tweak(&self) -> Vec<u8> {
+        self.tweak_for_time(SystemTime::now())
+    }
+}
+
+impl<CIPH: BlockCipher + BlockEncrypt + Clone> FF1<CIPH> {
+    /// Encrypts the given numeral string, using the tweak for the current epoch.
+    ///
+    /// Returns an error if the numeral string is not in the required radix.
+    pub fn encrypt_with_epoch_tweak<NS: NumeralString>(
+        &self,
+        tweak: &EpochTweak,
+        x: &NS,
+    ) -> Result<NS, NumeralStringError> {
+        self.encrypt(&tweak.current_tweak(), x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime};
+
+    use aes::Aes256;
+
+    use super::EpochTweak;
+    use crate::ff1::{FlexibleNumeralString, FF1};
+
+    const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+    #[test]
+    fn epochs() {
+        let start = SystemTime::UNIX_EPOCH + 1000 * DAY;
+        let tweak = EpochTweak::new(b"ssn", DAY, start);
+
+        assert_eq!(tweak.epoch_for_time(start), 0);
+        assert_eq!(
+            tweak.epoch_for_time(start + DAY - Duration::from_nanos(1)),
+            0
+        );
+        assert_eq!(tweak.epoch_for_time(start + DAY), 1);
+        assert_eq!(tweak.epoch_for_time(start + 7 * DAY), 7);
+        assert_eq!(tweak.epoch_for_time(start - DAY), 0);
+        assert_eq!(
+            tweak.epoch_for_time(start + DAY * u32::MAX + 2 * DAY),
+            u32::MAX
+        );
+
+        assert_eq!(tweak.tweak_for_time(start + 7 * DAY), b"ssn\0\0\0\x07");
+        assert_eq!(tweak.tweak_for_epoch(0x01020304), b"ssn\x01\x02\x03\x04");
+    }
+
+    #[test]
+    fn encrypt() {
+        // The current epoch does not change during the test.
+        let tweak = EpochTweak::new(b"ssn", 1000 * 365 * DAY, SystemTime::UNIX_EPOCH);
+        assert_eq!(tweak.current_epoch(), 0);
+
+        let ff = FF1::<Aes256>::new(&[0; 32], 10).unwrap();
+        let pt = FlexibleNumeralString::from_decimal_str("123456789").unwrap();
+        let ct = ff.encrypt_with_epoch_tweak(&tweak, &pt).unwrap();
+        assert_eq!(
+            ct.to_decimal_str(),
+            ff.encrypt(b"ssn\0\0\0\0", &pt).unwrap().to_decimal_str(),
+        );
+        assert_eq!(
+            ff.decrypt(&tweak.tweak_for_epoch(0), &ct)
+                .unwrap()
+                .to_decimal_str(),
+            "123456789",
+        );
+        assert_ne!(
+            ff.decrypt(&tweak.tweak_for_epoch(1), &ct)
+                .unwrap()
+                .to_decimal_str(),
+            "123456789",
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "epoch duration must be non-zero")]
+    fn zero_duration() {
+        EpochTweak::new(&[], Duration::ZERO, SystemTime::UNIX_EPOCH);
+    }
+}
@@ -10,6 +10,18 @@ impl fmt::Display for InvalidRadix {
     }
 }
 
+impl InvalidRadix {
+    /// Returns the minimum radix supported by FF1.
+    pub fn min_valid_radix() -> u32 {
+        super::MIN_RADIX
+    }
+
+    /// Returns the maximum radix supported by FF1.
+    pub fn max_valid_radix() -> u32 {
+        super::MAX_RADIX
+    }
+}
+
 #[cfg(feature = "std")]
 impl std::error::Error for InvalidRadix {}
 
@@ -17,7 +29,22 @@ impl std::error::Error for InvalidRadix {}
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
 pub enum NumeralStringError {
     /// The numeral string was not compatible with the configured radix.
+    ///
+    /// [`NumeralString`](super::NumeralString) only reports whether a numeral string is
+    /// valid, so this cannot name the offending numeral. With the `alloc` feature,
+    /// `FlexibleNumeralString::validate` finds it, returning
+    /// [`NumeralStringError::InvalidNumeral`].
     InvalidForRadix(u32),
+    /// A numeral was not valid for the radix.
+    InvalidNumeral {
+        /// The index of the first invalid numeral.
+        position: usize,
+        /// The invalid numeral. When parsing a string, this is the Unicode scalar value
+        /// of the invalid character.
+        value: u32,
+        /// The radix that the numeral was checked against.
+        radix: u32,
+    },
     /// The numeral string was longer than the maximum allowed length for FF1.
     TooLong {
         /// The length of the numeral string.
@@ -32,6 +59,22 @@ pub enum NumeralStringError {
         /// The minimum length allowed (in numerals) for a numeral string of its radix.
         min_len: usize,
     },
+    /// The tweak was longer than the maximum allowed length for FF1.
+    TweakTooLong {
+        /// The length of the tweak, in bytes.
+        tweak_len: usize,
+        /// The maximum length allowed (in bytes) for a tweak.
+        max_len: usize,
+    },
+    /// The range of numerals to encrypt was not within the numeral string.
+    InvalidRange {
+        /// The start of the range.
+        start: usize,
+        /// The end of the range.
+        end: usize,
+        /// The length of the numeral string.
+        ns_len: usize,
+    },
 }
 
 impl fmt::Display for NumeralStringError {
@@ -40,6 +83,15 @@ impl fmt::Display for NumeralStringError {
             NumeralStringError::InvalidForRadix(radix) => {
                 write!(f, "The given numeral string is invalid for radix {}", radix)
             }
+            NumeralStringError::InvalidNumeral {
+                position,
+                value,
+                radix,
+            } => write!(
+                f,
+                "The numeral {} at position {} is invalid for radix {}",
+                value, position, radix,
+            ),
             NumeralStringError::TooLong { ns_len, max_len } => write!(
                 f,
                 "The given numeral string is too long for FF1 ({} > {})",
@@ -50,9 +102,435 @@ impl fmt::Display for NumeralStringError {
                 "The given numeral string is too short for FF1 ({} < {})",
                 ns_len, min_len,
             ),
+            NumeralStringError::TweakTooLong { tweak_len, max_len } => write!(
+                f,
+                "The given tweak is too long for FF1 ({} > {})",
+                tweak_len, max_len,
+            ),
+            NumeralStringError::InvalidRange { start, end, ns_len } => write!(
+                f,
+                "The range {}..{} is invalid for a numeral string of length {}",
+                start, end, ns_len,
+            ),
         }
     }
 }
 
 #[cfg(feature = "std")]
 impl std::error::Error for NumeralStringError {}
+
+/// Errors that can occur while constructing or using an `Alphabet`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AlphabetError {
+    /// The alphabet contained the given character more than once.
+    DuplicateCharacter(char),
+    /// The alphabet had a number of characters outside the range `2..=(1 << 16)`.
+    InvalidLength(usize),
+    /// The given character is not part of the alphabet.
+    UnknownCharacter(char),
+}
+
+impl fmt::Display for AlphabetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AlphabetError::DuplicateCharacter(c) => {
+                write!(
+                    f,
+                    "The character {:?} occurs more than once in the alphabet",
+                    c
+                )
+            }
+            AlphabetError::InvalidLength(len) => write!(
+                f,
+                "The alphabet length {} is not in the range 2..=(1 << 16)",
+                len
+            ),
+            AlphabetError::UnknownCharacter(c) => {
+                write!(f, "The character {:?} is not in the alphabet", c)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for AlphabetError {}
+
+/// Errors that can occur while building an [`FF1`](super::FF1) instance with an
+/// `FF1Builder`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum BuildError {
+    /// The key had a length that the cipher does not support.
+    InvalidKeyLength(usize),
+    /// The radix was not in the range `2..=(1 << 16)`.
+    InvalidRadix(u32),
+    /// No radix was provided.
+    MissingRadix,
+    /// The number of Feistel rounds was below the minimum required for full security.
+    InvalidRoundCount {
+        /// The number of rounds that was requested.
+        given: u8,
+        /// The minimum number of rounds allowed.
+        minimum: u8,
+    },
+    /// The radix did not match the radix of the domain.
+    DomainRadixMismatch {
+        /// The radix that was set.
+        radix: u32,
+        /// The radix of the domain.
+        domain_radix: u32,
+    },
+}
+
+impl From<InvalidRadix> for BuildError {
+    fn from(e: InvalidRadix) -> Self {
+        BuildError::InvalidRadix(e.0)
+    }
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BuildError::InvalidKeyLength(len) => {
+                write!(f, "The key length {} is invalid for the cipher", len)
+            }
+            BuildError::InvalidRadix(radix) => InvalidRadix(*radix).fmt(f),
+            BuildError::MissingRadix => write!(f, "No radix was provided"),
+            BuildError::InvalidRoundCount { given, minimum } => write!(
+                f,
+                "The number of Feistel rounds is too low for FF1 ({} < {})",
+                given, minimum,
+            ),
+            BuildError::DomainRadixMismatch {
+                radix,
+                domain_radix,
+            } => write!(
+                f,
+                "The radix {} does not match the domain's radix {}",
+                radix, domain_radix,
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for BuildError {}
+
+/// Errors that can occur while parsing a
+/// `SegmentedNumeralString` from a format template.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FormatError {
+    /// The value had a different number of characters than the template.
+    LengthMismatch {
+        /// The number of characters in the template.
+        template_len: usize,
+        /// The number of characters in the value.
+        value_len: usize,
+    },
+    /// The value did not contain the template's literal character at the given
+    /// position.
+    LiteralMismatch {
+        /// The position of the character, in characters.
+        position: usize,
+        /// The literal character in the template.
+        expected: char,
+        /// The character in the value.
+        found: char,
+    },
+    /// The value did not contain a decimal digit at the given position.
+    InvalidDigit {
+        /// The position of the character, in characters.
+        position: usize,
+        /// The character in the value.
+        found: char,
+    },
+}
+
+impl fmt::Display for FormatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FormatError::LengthMismatch {
+                template_len,
+                value_len,
+            } => write!(
+                f,
+                "The value has {} characters but the template has {}",
+                value_len, template_len,
+            ),
+            FormatError::LiteralMismatch {
+                position,
+                expected,
+                found,
+            } => write!(
+                f,
+                "Expected {:?} at position {} of the value, found {:?}",
+                expected, position, found,
+            ),
+            FormatError::InvalidDigit { position, found } => write!(
+                f,
+                "Expected a decimal digit at position {} of the value, found {:?}",
+                position, found,
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for FormatError {}
+
+/// A precondition of NIST SP 800-38G that an FF1 operation would violate.
+///
+/// Returned by `FF1::verify_nist_constraints`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum NistViolation {
+    /// The tweak was longer than the maximum allowed length.
+    TweakTooLong {
+        /// The length of the tweak, in bytes.
+        tweak_len: usize,
+        /// The maximum length allowed (in bytes) for a tweak.
+        max_len: usize,
+    },
+    /// The numeral string was shorter than the minimum length for its radix.
+    NumeralStringTooShort {
+        /// The length of the numeral string.
+        actual: usize,
+        /// The minimum length allowed (in numerals) for the radix.
+        min: usize,
+    },
+    /// The numeral string was longer than the maximum length.
+    NumeralStringTooLong {
+        /// The length of the numeral string.
+        actual: usize,
+        /// The maximum length allowed (in numerals).
+        max: usize,
+    },
+    /// The numeral string contained a numeral that is not valid for the radix.
+    InvalidForRadix(u32),
+}
+
+impl fmt::Display for NistViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NistViolation::TweakTooLong { tweak_len, max_len } => write!(
+                f,
+                "The tweak is too long for FF1 ({} > {} bytes)",
+                tweak_len, max_len,
+            ),
+            NistViolation::NumeralStringTooShort { actual, min } => write!(
+                f,
+                "The numeral string is too short for FF1 ({} < {})",
+                actual, min,
+            ),
+            NistViolation::NumeralStringTooLong { actual, max } => write!(
+                f,
+                "The numeral string is too long for FF1 ({} > {})",
+                actual, max,
+            ),
+            NistViolation::InvalidForRadix(radix) => {
+                write!(f, "The numeral string is invalid for radix {}", radix)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for NistViolation {}
+
+/// Errors that can occur while decrypting with an `AuthenticatedFF1`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AuthenticationError {
+    /// The tag did not match the tweak and ciphertext.
+    InvalidTag,
+    /// The ciphertext could not be decrypted.
+    NumeralString(NumeralStringError),
+}
+
+impl From<NumeralStringError> for AuthenticationError {
+    fn from(e: NumeralStringError) -> Self {
+        AuthenticationError::NumeralString(e)
+    }
+}
+
+impl fmt::Display for AuthenticationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AuthenticationError::InvalidTag => write!(f, "The authentication tag is invalid"),
+            AuthenticationError::NumeralString(e) => e.fmt(f),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for AuthenticationError {}
+
+/// Errors that can occur while decrypting with a
+/// `VerifiedDecrypt` or a
+/// `ChecksumPreservingFF1`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VerificationError {
+    /// The decrypted numeral string was rejected by the validator.
+    ValidationFailed,
+    /// The check digit of the ciphertext was not valid.
+    ChecksumVerificationFailed,
+    /// The ciphertext could not be decrypted.
+    NumeralString(NumeralStringError),
+}
+
+impl From<NumeralStringError> for VerificationError {
+    fn from(e: NumeralStringError) -> Self {
+        VerificationError::NumeralString(e)
+    }
+}
+
+impl fmt::Display for VerificationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerificationError::ValidationFailed => {
+                write!(f, "The decrypted numeral string failed validation")
+            }
+            VerificationError::ChecksumVerificationFailed => {
+                write!(f, "The check digit of the ciphertext is invalid")
+            }
+            VerificationError::NumeralString(e) => e.fmt(f),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for VerificationError {}
+
+/// Errors that can occur while adding a key to a `KeyRotation`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InvalidKeyError {
+    /// The key had a length that the cipher does not support.
+    InvalidLength(usize),
+    /// A key with this version has already been added.
+    DuplicateVersion(u32),
+}
+
+impl fmt::Display for InvalidKeyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InvalidKeyError::InvalidLength(len) => {
+                write!(f, "The key length {} is invalid for the cipher", len)
+            }
+            InvalidKeyError::DuplicateVersion(version) => {
+                write!(f, "A key with version {} already exists", version)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for InvalidKeyError {}
+
+/// Errors that can occur while encrypting or decrypting with a
+/// `KeyRotation`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeyRotationError {
+    /// No keys have been added.
+    NoKeys,
+    /// There is no key with the given version, for example because it was removed.
+    KeyNotFound(u32),
+    /// The numeral string could not be encrypted or decrypted.
+    NumeralString(NumeralStringError),
+}
+
+impl From<NumeralStringError> for KeyRotationError {
+    fn from(e: NumeralStringError) -> Self {
+        KeyRotationError::NumeralString(e)
+    }
+}
+
+impl fmt::Display for KeyRotationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            KeyRotationError::NoKeys => write!(f, "No keys have been added"),
+            KeyRotationError::KeyNotFound(version) => {
+                write!(f, "There is no key with version {}", version)
+            }
+            KeyRotationError::NumeralString(e) => e.fmt(f),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for KeyRotationError {}
+
+/// Errors that can occur while validating a string against a
+/// `DomainSpec`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DomainSpecError {
+    /// The string had fewer characters than the domain allows.
+    TooShort {
+        /// The number of characters in the string.
+        len: usize,
+        /// The minimum number of characters allowed.
+        min_len: usize,
+    },
+    /// The string had more characters than the domain allows.
+    TooLong {
+        /// The number of characters in the string.
+        len: usize,
+        /// The maximum number of characters allowed.
+        max_len: usize,
+    },
+    /// The string contained a character that is not in the domain's character set.
+    InvalidCharacter(char),
+}
+
+impl fmt::Display for DomainSpecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DomainSpecError::TooShort { len, min_len } => write!(
+                f,
+                "The string is too short for the domain ({} < {})",
+                len, min_len,
+            ),
+            DomainSpecError::TooLong { len, max_len } => write!(
+                f,
+                "The string is too long for the domain ({} > {})",
+                len, max_len,
+            ),
+            DomainSpecError::InvalidCharacter(c) => {
+                write!(f, "The character {:?} is not in the domain", c)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DomainSpecError {}
+
+/// Errors that can occur while encrypting or decrypting a `DateNumeralString`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DateError {
+    /// The numeral string to encrypt or decrypt was not a valid date in the given
+    /// format.
+    InvalidDate,
+    /// No valid date was reached within the given number of FF1 operations.
+    TooManyAttempts(usize),
+    /// The numeral string could not be encrypted or decrypted.
+    NumeralString(NumeralStringError),
+}
+
+impl From<NumeralStringError> for DateError {
+    fn from(e: NumeralStringError) -> Self {
+        DateError::NumeralString(e)
+    }
+}
+
+impl fmt::Display for DateError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DateError::InvalidDate => write!(f, "The numeral string is not a valid date"),
+            DateError::TooManyAttempts(attempts) => {
+                write!(f, "No valid date was reached in {} attempts", attempts)
+            }
+            DateError::NumeralString(e) => e.fmt(f),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DateError {}
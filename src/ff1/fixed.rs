@@ -0,0 +1,359 @@
+//! Stack-allocated numeral strings that do not require a global allocator.
+
+use core::{cmp::Ordering, slice};
+
+use super::{
+    digits, InspectableOps, NumeralString, NumeralStringError, NumeralStringMut, Operations,
+};
+
+/// Number of bytes that [`FixedBytes`] can hold beyond `2 * N`.
+///
+/// This covers the 12-byte numbers used by FF3-1, for which the half-length of a
+/// numeral string may be much smaller than the byte representation.
+const EXTRA_BYTES: usize = 12;
+
+/// A numeral string of exactly `N` numerals, stored on the stack.
+///
+/// Supports radixes in [2..2^16].
+#[cfg_attr(test, derive(Debug))]
+pub struct FixedNumeralString<const N: usize>([u16; N]);
+
+impl<const N: usize> FixedNumeralString<N> {
+    /// Creates a `FixedNumeralString` from the given numerals.
+    ///
+    /// Returns an error if `numerals` does not contain exactly `N` numerals.
+    pub fn from_slice(numerals: &[u16]) -> Result<Self, NumeralStringError> {
+        let ns_len = numerals.len();
+        match ns_len.cmp(&N) {
+            Ordering::Less => Err(NumeralStringError::TooShort { ns_len, min_len: N }),
+            Ordering::Greater => Err(NumeralStringError::TooLong { ns_len, max_len: N }),
+            Ordering::Equal => {
+                let mut ns = [0; N];
+                ns.copy_from_slice(numerals);
+                Ok(FixedNumeralString(ns))
+            }
+        }
+    }
+
+    /// Returns the numerals of this numeral string.
+    pub fn as_slice(&self) -> &[u16] {
+        &self.0
+    }
+}
+
+impl<const N: usize> From<[u16; N]> for FixedNumeralString<N> {
+    fn from(ns: [u16; N]) -> Self {
+        FixedNumeralString(ns)
+    }
+}
+
+impl<const N: usize> NumeralString for FixedNumeralString<N> {
+    type Ops = FixedHalf<N>;
+
+    fn is_valid(&self, radix: u32) -> bool {
+        self.0.iter().all(|n| (u32::from(*n) < radix))
+    }
+
+    fn numeral_count(&self) -> usize {
+        N
+    }
+
+    fn split(&self) -> (Self::Ops, Self::Ops) {
+        let (a, b) = self.0.split_at(N / 2);
+        (FixedHalf::from_slice(a), FixedHalf::from_slice(b))
+    }
+
+    fn concat(a: Self::Ops, b: Self::Ops) -> Self {
+        assert_eq!(a.len + b.len, N);
+        let mut ns = [0; N];
+        ns[..a.len].copy_from_slice(a.as_slice());
+        ns[a.len..].copy_from_slice(b.as_slice());
+        FixedNumeralString(ns)
+    }
+
+    fn reversed(&self) -> Self {
+        let mut ns = self.0;
+        ns.reverse();
+        FixedNumeralString(ns)
+    }
+}
+
+impl<const N: usize> NumeralStringMut for FixedNumeralString<N> {
+    fn replace_with(&mut self, a: Self::Ops, b: Self::Ops) {
+        assert_eq!(a.len + b.len, N);
+        self.0[..a.len].copy_from_slice(a.as_slice());
+        self.0[a.len..].copy_from_slice(b.as_slice());
+    }
+}
+
+/// An array of `N` numerals is a numeral string, with the same stack-allocated halves
+/// as [`FixedNumeralString`].
+impl<const N: usize> NumeralString for [u16; N] {
+    type Ops = FixedHalf<N>;
+
+    fn is_valid(&self, radix: u32) -> bool {
+        self.iter().all(|n| (u32::from(*n) < radix))
+    }
+
+    fn numeral_count(&self) -> usize {
+        N
+    }
+
+    fn split(&self) -> (Self::Ops, Self::Ops) {
+        let (a, b) = self.split_at(N / 2);
+        (FixedHalf::from_slice(a), FixedHalf::from_slice(b))
+    }
+
+    fn concat(a: Self::Ops, b: Self::Ops) -> Self {
+        FixedNumeralString::concat(a, b).0
+    }
+
+    fn reversed(&self) -> Self {
+        let mut ns = *self;
+        ns.reverse();
+        ns
+    }
+}
+
+impl<const N: usize> NumeralStringMut for [u16; N] {
+    fn replace_with(&mut self, a: Self::Ops, b: Self::Ops) {
+        assert_eq!(a.len + b.len, N);
+        self[..a.len].copy_from_slice(a.as_slice());
+        self[a.len..].copy_from_slice(b.as_slice());
+    }
+}
+
+/// One half of a [`FixedNumeralString`] or `[u16; N]`, used for FF1 computations.
+///
+/// Stable Rust cannot yet compute `N / 2` in a type, so each half has capacity for `N`
+/// numerals and tracks how many of them are in use.
+#[cfg_attr(test, derive(Debug))]
+pub struct FixedHalf<const N: usize> {
+    numerals: [u16; N],
+    len: usize,
+}
+
+impl<const N: usize> FixedHalf<N> {
+    fn from_slice(numerals: &[u16]) -> Self {
+        let mut half = FixedHalf {
+            numerals: [0; N],
+            len: numerals.len(),
+        };
+        half.numerals[..half.len].copy_from_slice(numerals);
+        half
+    }
+
+    fn as_slice(&self) -> &[u16] {
+        &self.numerals[..self.len]
+    }
+
+    fn as_mut_slice(&mut self) -> &mut [u16] {
+        &mut self.numerals[..self.len]
+    }
+}
+
+impl<const N: usize> Operations for FixedHalf<N> {
+    type Bytes = FixedBytes<N>;
+
+    fn numeral_count(&self) -> usize {
+        self.len
+    }
+
+    fn to_be_bytes(&self, radix: u32, b: usize) -> Self::Bytes {
+        let mut bytes = FixedBytes::new(b);
+        digits::to_be_bytes(self.as_slice(), radix, bytes.as_mut());
+        bytes
+    }
+
+    fn add_mod_exp(mut self, other: impl Iterator<Item = u8>, radix: u32, m: usize) -> Self {
+        assert_eq!(self.len, m);
+        let mut y = [0; N];
+        digits::reduce_be_bytes(other, radix, &mut y[..m]);
+        digits::add_mod(self.as_mut_slice(), &y[..m], radix);
+        self
+    }
+
+    fn sub_mod_exp(mut self, other: impl Iterator<Item = u8>, radix: u32, m: usize) -> Self {
+        assert_eq!(self.len, m);
+        let mut y = [0; N];
+        digits::reduce_be_bytes(other, radix, &mut y[..m]);
+        digits::sub_mod(self.as_mut_slice(), &y[..m], radix);
+        self
+    }
+}
+
+impl<const N: usize> InspectableOps for FixedHalf<N> {
+    type Numeral = u16;
+
+    fn to_numerals(&self) -> &[u16] {
+        self.as_slice()
+    }
+}
+
+/// A stack-allocated byte string, used for the byte representation of a [`FixedHalf`].
+///
+/// Holds up to `2 * N + 12` bytes, which is enough for any half of an `N`-numeral
+/// string in radixes up to 2^16.
+#[repr(C)]
+pub struct FixedBytes<const N: usize> {
+    // `repr(C)` lays out these two byte arrays contiguously (both have alignment 1),
+    // which lets us view them as a single slice.
+    head: [[u8; 2]; N],
+    tail: [u8; EXTRA_BYTES],
+    len: usize,
+}
+
+impl<const N: usize> FixedBytes<N> {
+    const CAPACITY: usize = 2 * N + EXTRA_BYTES;
+
+    pub(super) fn new(len: usize) -> Self {
+        assert!(len <= Self::CAPACITY);
+        FixedBytes {
+            head: [[0; 2]; N],
+            tail: [0; EXTRA_BYTES],
+            len,
+        }
+    }
+}
+
+impl<const N: usize> AsRef<[u8]> for FixedBytes<N> {
+    fn as_ref(&self) -> &[u8] {
+        // SAFETY: `head` is at offset 0 of this `repr(C)` struct and is immediately
+        // followed by `tail`, so the first `CAPACITY` bytes are initialized `u8`s, and
+        // `len <= CAPACITY` is checked in `FixedBytes::new`. The pointer is derived
+        // from the whole struct rather than from `head`, so that it may also be used
+        // to access `tail`.
+        unsafe { slice::from_raw_parts(self as *const Self as *const u8, self.len) }
+    }
+}
+
+impl<const N: usize> AsMut<[u8]> for FixedBytes<N> {
+    fn as_mut(&mut self) -> &mut [u8] {
+        // SAFETY: see `AsRef::as_ref` above.
+        unsafe { slice::from_raw_parts_mut(self as *mut Self as *mut u8, self.len) }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aes::{Aes128, Aes192, Aes256};
+
+    use super::{FixedBytes, FixedNumeralString, EXTRA_BYTES};
+    use crate::ff1::{
+        test_vectors::{self, AesType},
+        InspectableOps, NumeralString, NumeralStringError, FF1,
+    };
+    use crate::ff3::FF3_1;
+
+    #[test]
+    fn bytes_span_head_and_tail() {
+        // Bytes beyond `2 * N` are stored in `tail`.
+        let mut bytes = FixedBytes::<1>::new(2 + EXTRA_BYTES);
+        for (i, b) in bytes.as_mut().iter_mut().enumerate() {
+            *b = i as u8;
+        }
+        assert_eq!(bytes.head, [[0, 1]]);
+        assert_eq!(bytes.tail[EXTRA_BYTES - 1], EXTRA_BYTES as u8 + 1);
+        assert!(bytes.as_ref().iter().copied().eq(0..2 + EXTRA_BYTES as u8));
+    }
+
+    #[test]
+    fn from_slice() {
+        assert_eq!(
+            FixedNumeralString::<4>::from_slice(&[1, 2, 3, 4])
+                .unwrap()
+                .as_slice(),
+            &[1, 2, 3, 4],
+        );
+        assert_eq!(
+            FixedNumeralString::<4>::from_slice(&[1, 2, 3]).unwrap_err(),
+            NumeralStringError::TooShort {
+                ns_len: 3,
+                min_len: 4,
+            },
+        );
+        assert_eq!(
+            FixedNumeralString::<4>::from_slice(&[1, 2, 3, 4, 5]).unwrap_err(),
+            NumeralStringError::TooLong {
+                ns_len: 5,
+                max_len: 4,
+            },
+        );
+    }
+
+    #[test]
+    fn split_round_trip() {
+        let ns = FixedNumeralString::from([1, 2, 3, 4, 5]);
+        let (a, b) = ns.split();
+        assert_eq!(a.as_slice(), &[1, 2]);
+        assert_eq!(b.as_slice(), &[3, 4, 5]);
+        assert_eq!(b.to_numerals(), &[3, 4, 5]);
+        assert_eq!(b.get_numeral(2), Some(5));
+        assert_eq!(
+            FixedNumeralString::concat(a, b).as_slice(),
+            &[1, 2, 3, 4, 5]
+        );
+    }
+
+    fn check<const N: usize>(tv: &test_vectors::TestVector) {
+        let pt = FixedNumeralString::<N>::from_slice(&tv.pt).unwrap();
+        let ct = FixedNumeralString::<N>::from_slice(&tv.ct).unwrap();
+        let (ct, pt) = match tv.aes {
+            AesType::AES128 => {
+                let ff = FF1::<Aes128>::new(&tv.key, tv.radix).unwrap();
+                (ff.encrypt(&tv.tweak, &pt), ff.decrypt(&tv.tweak, &ct))
+            }
+            AesType::AES192 => {
+                let ff = FF1::<Aes192>::new(&tv.key, tv.radix).unwrap();
+                (ff.encrypt(&tv.tweak, &pt), ff.decrypt(&tv.tweak, &ct))
+            }
+            AesType::AES256 => {
+                let ff = FF1::<Aes256>::new(&tv.key, tv.radix).unwrap();
+                (ff.encrypt(&tv.tweak, &pt), ff.decrypt(&tv.tweak, &ct))
+            }
+        };
+        assert_eq!(ct.unwrap().as_slice(), &tv.ct[..]);
+        assert_eq!(pt.unwrap().as_slice(), &tv.pt[..]);
+    }
+
+    #[test]
+    fn fixed() {
+        let mut checked = 0;
+        for tv in test_vectors::get() {
+            match tv.pt.len() {
+                10 => check::<10>(&tv),
+                19 => check::<19>(&tv),
+                _ => continue,
+            }
+            checked += 1;
+        }
+        assert!(checked >= 9);
+    }
+
+    #[test]
+    fn array() {
+        for tv in test_vectors::get().filter(|tv| tv.pt.len() == 10) {
+            let ff = FF1::<Aes256>::new(&[0; 32], tv.radix).unwrap();
+            let mut pt = [0; 10];
+            pt.copy_from_slice(&tv.pt);
+            let ct: [u16; 10] = ff.encrypt(&tv.tweak, &pt).unwrap();
+            assert_eq!(
+                &ct[..],
+                ff.encrypt(&tv.tweak, &FixedNumeralString::from(pt))
+                    .unwrap()
+                    .as_slice()
+            );
+            assert_eq!(ff.decrypt(&tv.tweak, &ct).unwrap(), pt);
+        }
+    }
+
+    #[test]
+    fn ff3_1_round_trip() {
+        // With a large radix, the halves are much shorter than the 12-byte numbers FF3-1
+        // computes on.
+        let ff = FF3_1::<Aes128>::new(&[0; 16], 1 << 16).unwrap();
+        let pt = FixedNumeralString::from([0xffff, 0x1234, 0]);
+        let ct = ff.encrypt(&[0; 7], &pt).unwrap();
+        assert_eq!(ff.decrypt(&[0; 7], &ct).unwrap().as_slice(), pt.as_slice());
+    }
+}
@@ -0,0 +1,192 @@
+//! FF1 NumeralString implementations backed by `heapless` collections.
+
+use core::ops::Range;
+
+use heapless::Vec;
+
+use super::{
+    digits, FixedBytes, InspectableOps, NumeralString, NumeralStringError, NumeralStringMut,
+    Operations, SliceableNumeralString,
+};
+
+/// A numeral string of at most `CAP` numerals, stored on the stack.
+///
+/// Supports radixes in [2..2^16]. Encrypting and decrypting a `HeaplessNumeralString`
+/// never invokes the global allocator.
+#[cfg_attr(test, derive(Debug))]
+pub struct HeaplessNumeralString<const CAP: usize>(Vec<u16, CAP>);
+
+impl<const CAP: usize> HeaplessNumeralString<CAP> {
+    /// Creates a `HeaplessNumeralString` from the given numerals.
+    ///
+    /// Returns an error if `numerals` contains more than `CAP` numerals.
+    pub fn from_slice(numerals: &[u16]) -> Result<Self, NumeralStringError> {
+        Vec::from_slice(numerals)
+            .map(HeaplessNumeralString)
+            .map_err(|_| NumeralStringError::TooLong {
+                ns_len: numerals.len(),
+                max_len: CAP,
+            })
+    }
+
+    /// Returns the numerals of this numeral string.
+    pub fn as_slice(&self) -> &[u16] {
+        &self.0
+    }
+}
+
+impl<const CAP: usize> From<Vec<u16, CAP>> for HeaplessNumeralString<CAP> {
+    fn from(v: Vec<u16, CAP>) -> Self {
+        HeaplessNumeralString(v)
+    }
+}
+
+impl<const CAP: usize> From<HeaplessNumeralString<CAP>> for Vec<u16, CAP> {
+    fn from(hns: HeaplessNumeralString<CAP>) -> Self {
+        hns.0
+    }
+}
+
+impl<const CAP: usize> NumeralString for HeaplessNumeralString<CAP> {
+    type Ops = Self;
+
+    fn is_valid(&self, radix: u32) -> bool {
+        self.0.iter().all(|n| (u32::from(*n) < radix))
+    }
+
+    fn numeral_count(&self) -> usize {
+        self.0.len()
+    }
+
+    fn split(&self) -> (Self, Self) {
+        // Both halves are no longer than `self`, so they always fit.
+        let (front, back) = self.0.split_at(self.0.len() / 2);
+        (
+            HeaplessNumeralString(Vec::from_slice(front).unwrap()),
+            HeaplessNumeralString(Vec::from_slice(back).unwrap()),
+        )
+    }
+
+    fn concat(mut a: Self, b: Self) -> Self {
+        a.0.extend_from_slice(&b.0)
+            .expect("halves were split from a string of at most CAP numerals");
+        a
+    }
+
+    fn reversed(&self) -> Self {
+        HeaplessNumeralString(self.0.iter().rev().copied().collect())
+    }
+}
+
+impl<const CAP: usize> NumeralStringMut for HeaplessNumeralString<CAP> {
+    fn replace_with(&mut self, a: Self, b: Self) {
+        self.0.clear();
+        self.0
+            .extend_from_slice(&a.0)
+            .and_then(|()| self.0.extend_from_slice(&b.0))
+            .expect("halves were split from a string of at most CAP numerals");
+    }
+}
+
+impl<const CAP: usize> SliceableNumeralString for HeaplessNumeralString<CAP> {
+    fn slice(&self, range: Range<usize>) -> Self {
+        // The slice is no longer than `self`, so it always fits.
+        HeaplessNumeralString(Vec::from_slice(&self.0[range]).unwrap())
+    }
+
+    fn splice(&mut self, range: Range<usize>, replacement: Self) {
+        self.0[range].copy_from_slice(&replacement.0);
+    }
+}
+
+impl<const CAP: usize> Operations for HeaplessNumeralString<CAP> {
+    // `heapless::Vec<u8, BUF>` would need `BUF` to be computed from `CAP`, which stable
+    // Rust does not support; `FixedBytes` is sized from `CAP` instead.
+    type Bytes = FixedBytes<CAP>;
+
+    fn numeral_count(&self) -> usize {
+        self.0.len()
+    }
+
+    fn to_be_bytes(&self, radix: u32, b: usize) -> Self::Bytes {
+        let mut bytes = FixedBytes::new(b);
+        digits::to_be_bytes(&self.0, radix, bytes.as_mut());
+        bytes
+    }
+
+    fn add_mod_exp(mut self, other: impl Iterator<Item = u8>, radix: u32, m: usize) -> Self {
+        assert_eq!(self.0.len(), m);
+        let mut y = [0; CAP];
+        digits::reduce_be_bytes(other, radix, &mut y[..m]);
+        digits::add_mod(&mut self.0, &y[..m], radix);
+        self
+    }
+
+    fn sub_mod_exp(mut self, other: impl Iterator<Item = u8>, radix: u32, m: usize) -> Self {
+        assert_eq!(self.0.len(), m);
+        let mut y = [0; CAP];
+        digits::reduce_be_bytes(other, radix, &mut y[..m]);
+        digits::sub_mod(&mut self.0, &y[..m], radix);
+        self
+    }
+}
+
+impl<const CAP: usize> InspectableOps for HeaplessNumeralString<CAP> {
+    type Numeral = u16;
+
+    fn to_numerals(&self) -> &[u16] {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aes::{Aes128, Aes192, Aes256};
+
+    use super::HeaplessNumeralString;
+    use crate::ff1::{
+        test_vectors::{self, AesType},
+        NumeralStringError, FF1,
+    };
+
+    #[test]
+    fn from_slice() {
+        assert_eq!(
+            HeaplessNumeralString::<4>::from_slice(&[1, 2, 3])
+                .unwrap()
+                .as_slice(),
+            &[1, 2, 3],
+        );
+        assert_eq!(
+            HeaplessNumeralString::<4>::from_slice(&[1, 2, 3, 4, 5]).unwrap_err(),
+            NumeralStringError::TooLong {
+                ns_len: 5,
+                max_len: 4,
+            },
+        );
+    }
+
+    #[test]
+    fn heapless() {
+        for tv in test_vectors::get() {
+            let pt = HeaplessNumeralString::<256>::from_slice(&tv.pt).unwrap();
+            let ct = HeaplessNumeralString::<256>::from_slice(&tv.ct).unwrap();
+            let (ct, pt) = match tv.aes {
+                AesType::AES128 => {
+                    let ff = FF1::<Aes128>::new(&tv.key, tv.radix).unwrap();
+                    (ff.encrypt(&tv.tweak, &pt), ff.decrypt(&tv.tweak, &ct))
+                }
+                AesType::AES192 => {
+                    let ff = FF1::<Aes192>::new(&tv.key, tv.radix).unwrap();
+                    (ff.encrypt(&tv.tweak, &pt), ff.decrypt(&tv.tweak, &ct))
+                }
+                AesType::AES256 => {
+                    let ff = FF1::<Aes256>::new(&tv.key, tv.radix).unwrap();
+                    (ff.encrypt(&tv.tweak, &pt), ff.decrypt(&tv.tweak, &ct))
+                }
+            };
+            assert_eq!(ct.unwrap().as_slice(), &tv.ct[..]);
+            assert_eq!(pt.unwrap().as_slice(), &tv.pt[..]);
+        }
+    }
+}
@@ -0,0 +1,246 @@
+//! A radix-16 numeral string for hexadecimal strings.
+
+use core::{ops::Range, str::FromStr};
+
+use alloc::{string::String, vec, vec::Vec};
+
+use super::{
+    digits, InspectableOps, NumeralString, NumeralStringError, NumeralStringMut, Operations,
+    SliceableNumeralString,
+};
+
+const RADIX: u32 = 16;
+
+/// A numeral string with radix 16, backed by one byte per hexadecimal digit.
+///
+/// Radix 16 is a power of two, so all FF1 arithmetic for this numeral string avoids
+/// floating-point operations.
+#[cfg_attr(test, derive(Debug))]
+pub struct HexNumeralString(Vec<u8>);
+
+impl HexNumeralString {
+    /// Creates a `HexNumeralString` from a string of hexadecimal digits.
+    ///
+    /// Both uppercase and lowercase digits are accepted. Returns
+    /// [`NumeralStringError::InvalidNumeral`] for the first character that is not a
+    /// hexadecimal digit.
+    pub fn from_hex_str(s: &str) -> Result<Self, NumeralStringError> {
+        s.chars()
+            .enumerate()
+            .map(|(position, c)| match c.to_digit(RADIX) {
+                Some(d) => Ok(d as u8),
+                None => Err(NumeralStringError::InvalidNumeral {
+                    position,
+                    value: c.into(),
+                    radix: RADIX,
+                }),
+            })
+            .collect::<Result<_, _>>()
+            .map(HexNumeralString)
+    }
+
+    /// Creates a `HexNumeralString` from the 32 hexadecimal digits of a UUID.
+    #[cfg(feature = "uuid")]
+    pub fn from_uuid(uuid: uuid::Uuid) -> Self {
+        HexNumeralString(
+            uuid.as_bytes()
+                .iter()
+                .flat_map(|b| [b >> 4, b & 0x0f])
+                .collect(),
+        )
+    }
+
+    /// Returns the lowercase hexadecimal string for this numeral string.
+    pub fn to_hex_str_lower(&self) -> String {
+        self.to_hex_str(b"0123456789abcdef")
+    }
+
+    /// Returns the uppercase hexadecimal string for this numeral string.
+    pub fn to_hex_str_upper(&self) -> String {
+        self.to_hex_str(b"0123456789ABCDEF")
+    }
+
+    fn to_hex_str(&self, chars: &[u8; 16]) -> String {
+        self.0
+            .iter()
+            .map(|&d| char::from(chars[d as usize]))
+            .collect()
+    }
+}
+
+/// Parses a hexadecimal string, as [`HexNumeralString::from_hex_str`].
+impl TryFrom<&str> for HexNumeralString {
+    type Error = NumeralStringError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        HexNumeralString::from_hex_str(s)
+    }
+}
+
+/// Parses a hexadecimal string, as [`HexNumeralString::from_hex_str`].
+impl FromStr for HexNumeralString {
+    type Err = NumeralStringError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        HexNumeralString::try_from(s)
+    }
+}
+
+impl NumeralString for HexNumeralString {
+    type Ops = Self;
+
+    fn is_valid(&self, radix: u32) -> bool {
+        // The digits are valid for radix 16 by construction.
+        radix == RADIX
+    }
+
+    fn numeral_count(&self) -> usize {
+        self.0.len()
+    }
+
+    fn split(&self) -> (Self, Self) {
+        let mut front = self.0.clone();
+        let back = front.split_off(self.0.len() / 2);
+        (HexNumeralString(front), HexNumeralString(back))
+    }
+
+    fn concat(mut a: Self, mut b: Self) -> Self {
+        a.0.append(&mut b.0);
+        a
+    }
+
+    fn reversed(&self) -> Self {
+        HexNumeralString(self.0.iter().rev().copied().collect())
+    }
+}
+
+impl NumeralStringMut for HexNumeralString {
+    fn replace_with(&mut self, a: Self, b: Self) {
+        self.0.clear();
+        self.0.extend_from_slice(&a.0);
+        self.0.extend_from_slice(&b.0);
+    }
+}
+
+impl SliceableNumeralString for HexNumeralString {
+    fn slice(&self, range: Range<usize>) -> Self {
+        HexNumeralString(self.0[range].to_vec())
+    }
+
+    fn splice(&mut self, range: Range<usize>, replacement: Self) {
+        self.0[range].copy_from_slice(&replacement.0);
+    }
+}
+
+impl Operations for HexNumeralString {
+    type Bytes = Vec<u8>;
+
+    fn numeral_count(&self) -> usize {
+        self.0.len()
+    }
+
+    fn to_be_bytes(&self, radix: u32, b: usize) -> Self::Bytes {
+        assert_eq!(radix, RADIX);
+        let mut bytes = vec![0; b];
+        digits::to_be_bytes(&self.0, RADIX, &mut bytes);
+        bytes
+    }
+
+    fn add_mod_exp(mut self, other: impl Iterator<Item = u8>, radix: u32, m: usize) -> Self {
+        assert_eq!(radix, RADIX);
+        let mut y = vec![0; m];
+        digits::reduce_be_bytes(other, RADIX, &mut y);
+        digits::add_mod(&mut self.0, &y, RADIX);
+        self
+    }
+
+    fn sub_mod_exp(mut self, other: impl Iterator<Item = u8>, radix: u32, m: usize) -> Self {
+        assert_eq!(radix, RADIX);
+        let mut y = vec![0; m];
+        digits::reduce_be_bytes(other, RADIX, &mut y);
+        digits::sub_mod(&mut self.0, &y, RADIX);
+        self
+    }
+}
+
+impl InspectableOps for HexNumeralString {
+    type Numeral = u8;
+
+    fn to_numerals(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aes::Aes128;
+
+    use super::HexNumeralString;
+    use crate::ff1::{FlexibleNumeralString, NumeralStringError, FF1};
+
+    #[test]
+    fn hex_str_round_trip() {
+        let ns = HexNumeralString::from_hex_str("09afAF").unwrap();
+        assert_eq!(ns.0, vec![0, 9, 10, 15, 10, 15]);
+        assert_eq!(ns.to_hex_str_lower(), "09afaf");
+        assert_eq!(ns.to_hex_str_upper(), "09AFAF");
+        assert_eq!(
+            HexNumeralString::try_from("09afAF").unwrap().0,
+            "09afaf".parse::<HexNumeralString>().unwrap().0,
+        );
+
+        assert_eq!(
+            HexNumeralString::from_hex_str("12g4").unwrap_err(),
+            NumeralStringError::InvalidNumeral {
+                position: 2,
+                value: 'g'.into(),
+                radix: 16,
+            },
+        );
+    }
+
+    #[test]
+    fn matches_flexible() {
+        let ff = FF1::<Aes128>::new(&[0x2b; 16], 16).unwrap();
+        let pt = "0123456789abcdef0123";
+
+        let ct = ff
+            .encrypt(&[1, 2, 3], &HexNumeralString::from_hex_str(pt).unwrap())
+            .unwrap();
+        let expected = ff
+            .encrypt(
+                &[1, 2, 3],
+                &FlexibleNumeralString::from(
+                    pt.chars()
+                        .map(|c| c.to_digit(16).unwrap() as u16)
+                        .collect::<Vec<_>>(),
+                ),
+            )
+            .unwrap();
+        assert_eq!(
+            ct.0,
+            Vec::from(expected)
+                .into_iter()
+                .map(|d| d as u8)
+                .collect::<Vec<_>>(),
+        );
+
+        let decrypted = ff.decrypt(&[1, 2, 3], &ct).unwrap();
+        assert_eq!(decrypted.to_hex_str_lower(), pt);
+    }
+
+    #[cfg(feature = "uuid")]
+    #[test]
+    fn uuid() {
+        use aes::Aes256;
+
+        let uuid = uuid::Uuid::from_u128(0x67e5_5044_10b1_426f_9247_bb68_0e5f_e0c8);
+        let ns = HexNumeralString::from_uuid(uuid);
+        assert_eq!(ns.to_hex_str_lower(), "67e5504410b1426f9247bb680e5fe0c8");
+
+        let ff = FF1::<Aes256>::new(&[0; 32], 16).unwrap();
+        let ct = ff.encrypt(&[], &ns).unwrap().to_hex_str_lower();
+        assert_eq!(ct.len(), 32);
+        assert!(ct.chars().all(|c| c.is_ascii_hexdigit()));
+    }
+}
@@ -0,0 +1,141 @@
+//! Tracing of the Feistel rounds of FF1, for debugging and education.
+
+use alloc::vec::Vec;
+use cipher::{BlockCipher, BlockEncrypt};
+
+use super::{check_tweak, Inspector, NumeralString, NumeralStringError, FF1};
+
+/// The state of FF1 at the end of a Feistel round, passed to the inspector of
+/// [`FF1::encrypt_with_inspector`] and [`FF1::decrypt_with_inspector`].
+#[derive(Debug)]
+pub struct RoundState<'a, O> {
+    /// The round number `i`. Decryption runs the rounds in descending order.
+    pub round: u8,
+    /// The half `A` after the round.
+    pub a: &'a O,
+    /// The half `B` after the round.
+    pub b: &'a O,
+    /// The bytes `S` that were added to (or subtracted from) the half.
+    pub y_bytes: &'a [u8],
+    /// The number of numerals `m` in the half that was modified.
+    pub m: usize,
+}
+
+/// Adapts a closure to an [`Inspector`].
+struct FnInspector<F>(F);
+
+impl<O, F: FnMut(RoundState<'_, O>)> Inspector<O> for FnInspector<F> {
+    fn inspect(&mut self, i: u8, x_a: &O, x_b: &O, s: impl Iterator<Item = u8> + Clone, m: usize) {
+        let y_bytes: Vec<u8> = s.collect();
+        (self.0)(RoundState {
+            round: i,
+            a: x_a,
+            b: x_b,
+            y_bytes: &y_bytes,
+            m,
+        });
+    }
+}
+
+impl<CIPH: BlockCipher + BlockEncrypt + Clone> FF1<CIPH> {
+    /// Encrypts the given numeral string, calling `inspector` with the state at the end
+    /// of each Feistel round.
+    ///
+    /// The inspector cannot affect the ciphertext, which is the same as
+    /// [`FF1::encrypt`] returns. Use [`InspectableOps`](super::InspectableOps) to read
+    /// the numerals of the halves.
+    ///
+    /// Returns an error if the numeral string is not in the required radix.
+    pub fn encrypt_with_inspector<NS, F>(
+        &self,
+        tweak: &[u8],
+        x: &NS,
+        inspector: F,
+    ) -> Result<NS, NumeralStringError>
+    where
+        NS: NumeralString,
+        F: FnMut(RoundState<'_, NS::Ops>),
+    {
+        check_tweak(tweak)?;
+        self.check_ns(x)?;
+        let prf = self.prf_prefix(x.numeral_count(), tweak);
+        let (x_a, x_b) = self.encrypt_with_inspector_inner(&prf, x, &mut FnInspector(inspector));
+        Ok(NS::concat(x_a, x_b))
+    }
+
+    /// Decrypts the given numeral string, calling `inspector` with the state at the end
+    /// of each Feistel round.
+    ///
+    /// The inspector cannot affect the plaintext, which is the same as [`FF1::decrypt`]
+    /// returns.
+    ///
+    /// Returns an error if the numeral string is not in the required radix.
+    pub fn decrypt_with_inspector<NS, F>(
+        &self,
+        tweak: &[u8],
+        x: &NS,
+        inspector: F,
+    ) -> Result<NS, NumeralStringError>
+    where
+        NS: NumeralString,
+        F: FnMut(RoundState<'_, NS::Ops>),
+    {
+        check_tweak(tweak)?;
+        self.check_ns(x)?;
+        let prf = self.prf_prefix(x.numeral_count(), tweak);
+        let (x_a, x_b) = self.decrypt_with_inspector_inner(&prf, x, &mut FnInspector(inspector));
+        Ok(NS::concat(x_a, x_b))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aes::Aes256;
+
+    use super::RoundState;
+    use crate::ff1::{FlexibleNumeralString, InspectableOps, FF1};
+
+    #[test]
+    fn rounds() {
+        let ff = FF1::<Aes256>::new(&[0; 32], 10).unwrap();
+        let pt = FlexibleNumeralString::from_decimal_str("0123456789").unwrap();
+
+        let mut states = vec![];
+        let ct = ff
+            .encrypt_with_inspector(b"tweak", &pt, |state: RoundState<'_, _>| {
+                states.push((
+                    state.round,
+                    state.a.to_numerals().to_vec(),
+                    state.b.to_numerals().to_vec(),
+                    state.y_bytes.len(),
+                    state.m,
+                ))
+            })
+            .unwrap();
+        assert_eq!(
+            ct.to_decimal_str(),
+            ff.encrypt(b"tweak", &pt).unwrap().to_decimal_str()
+        );
+
+        // For n = 10, b = 3 and d = 8, and the halves have 5 numerals.
+        assert_eq!(states.len(), 10);
+        for (i, (round, a, b, d, m)) in states.iter().enumerate() {
+            assert_eq!(*round as usize, i);
+            assert_eq!((a.len(), b.len(), *d, *m), (5, 5, 8, 5));
+        }
+        // Each round's A is the previous round's B.
+        for w in states.windows(2) {
+            assert_eq!(w[1].1, w[0].2);
+        }
+        // The last round's halves are the ciphertext.
+        let (_, a, b, _, _) = &states[9];
+        assert_eq!(ct.as_numerals(), [&a[..], &b[..]].concat());
+
+        let mut rounds = vec![];
+        let decrypted = ff
+            .decrypt_with_inspector(b"tweak", &ct, |state| rounds.push(state.round))
+            .unwrap();
+        assert_eq!(decrypted.to_decimal_str(), "0123456789");
+        assert_eq!(rounds, (0..10).rev().collect::<Vec<_>>());
+    }
+}
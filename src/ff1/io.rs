@@ -0,0 +1,231 @@
+//! [`std::io`] adapters that encrypt and decrypt fixed-length byte records.
+
+use std::io::{self, Read, Write};
+
+use cipher::{BlockCipher, BlockEncrypt};
+
+use super::{ByteNumeralString, FF1};
+
+fn invalid_input(e: impl Into<Box<dyn std::error::Error + Send + Sync>>) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidInput, e)
+}
+
+/// A writer that encrypts records of a fixed length with FF1 in radix 256, and writes
+/// the ciphertexts to an underlying writer.
+///
+/// FF1 is not a stream cipher, so each record is buffered until it is complete. The
+/// record is encrypted and written when [`FF1Writer::flush`] is called, after which the
+/// next record can be written.
+///
+/// The FF1 instance must have been created with radix 256, for example with
+/// [`FF1::new_byte_level`].
+pub struct FF1Writer<CIPH: BlockCipher + BlockEncrypt, W: Write> {
+    ff: FF1<CIPH>,
+    tweak: Vec<u8>,
+    len: usize,
+    buf: Vec<u8>,
+    inner: W,
+}
+
+impl<CIPH: BlockCipher + BlockEncrypt + Clone, W: Write> FF1Writer<CIPH, W> {
+    /// Creates a writer that encrypts records of `len` bytes with the given tweak.
+    pub fn new(ff: FF1<CIPH>, tweak: &[u8], len: usize, inner: W) -> Self {
+        FF1Writer {
+            ff,
+            tweak: tweak.to_vec(),
+            len,
+            buf: Vec::with_capacity(len),
+            inner,
+        }
+    }
+
+    /// Returns the underlying writer, discarding any incomplete record.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<CIPH: BlockCipher + BlockEncrypt + Clone, W: Write> Write for FF1Writer<CIPH, W> {
+    /// Buffers bytes of the current record.
+    ///
+    /// Returns an error if the record would become longer than its declared length.
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        if !buf.is_empty() && self.buf.len() == self.len {
+            return Err(invalid_input(format!(
+                "The record is already complete ({} bytes)",
+                self.len
+            )));
+        }
+        let n = buf.len().min(self.len - self.buf.len());
+        self.buf.extend_from_slice(&buf[..n]);
+        Ok(n)
+    }
+
+    /// Encrypts the current record and writes it to the underlying writer.
+    ///
+    /// Does nothing but flush the underlying writer if no bytes of a record have been
+    /// written. Returns an error if the record is incomplete, or if its length is
+    /// invalid for FF1 in radix 256, which requires at least 3 bytes.
+    fn flush(&mut self) -> io::Result<()> {
+        if !self.buf.is_empty() {
+            if self.buf.len() < self.len {
+                return Err(invalid_input(format!(
+                    "The record is incomplete ({} of {} bytes)",
+                    self.buf.len(),
+                    self.len
+                )));
+            }
+            let ct = self
+                .ff
+                .encrypt(&self.tweak, &ByteNumeralString::from_bytes(&self.buf))
+                .map_err(invalid_input)?;
+            self.inner.write_all(ct.as_bytes())?;
+            self.buf.clear();
+        }
+        self.inner.flush()
+    }
+}
+
+/// A reader that reads records of a fixed length from an underlying reader, and
+/// decrypts them with FF1 in radix 256.
+///
+/// The FF1 instance must have been created with radix 256, for example with
+/// [`FF1::new_byte_level`].
+pub struct FF1Reader<CIPH: BlockCipher + BlockEncrypt, R: Read> {
+    ff: FF1<CIPH>,
+    tweak: Vec<u8>,
+    len: usize,
+    buf: Vec<u8>,
+    pos: usize,
+    inner: R,
+}
+
+impl<CIPH: BlockCipher + BlockEncrypt + Clone, R: Read> FF1Reader<CIPH, R> {
+    /// Creates a reader that decrypts records of `len` bytes with the given tweak.
+    pub fn new(ff: FF1<CIPH>, tweak: &[u8], len: usize, inner: R) -> Self {
+        FF1Reader {
+            ff,
+            tweak: tweak.to_vec(),
+            len,
+            buf: Vec::new(),
+            pos: 0,
+            inner,
+        }
+    }
+
+    /// Returns the underlying reader, discarding any unread plaintext.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    /// Reads and decrypts the next record, returning `false` if the underlying reader
+    /// is at the end of its input.
+    fn fill(&mut self) -> io::Result<bool> {
+        let mut ct = vec![0; self.len];
+        let mut read = 0;
+        while read < self.len {
+            match self.inner.read(&mut ct[read..]) {
+                Ok(0) if read == 0 => return Ok(false),
+                Ok(0) => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::UnexpectedEof,
+                        format!("The record is incomplete ({} of {} bytes)", read, self.len),
+                    ))
+                }
+                Ok(n) => read += n,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => (),
+                Err(e) => return Err(e),
+            }
+        }
+
+        let pt = self
+            .ff
+            .decrypt(&self.tweak, &ByteNumeralString::from(ct))
+            .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?;
+        self.buf = pt.into();
+        self.pos = 0;
+        Ok(true)
+    }
+}
+
+impl<CIPH: BlockCipher + BlockEncrypt + Clone, R: Read> Read for FF1Reader<CIPH, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        if self.pos == self.buf.len() && !self.fill()? {
+            return Ok(0);
+        }
+        let n = buf.len().min(self.buf.len() - self.pos);
+        buf[..n].copy_from_slice(&self.buf[self.pos..self.pos + n]);
+        self.pos += n;
+        Ok(n)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::{ErrorKind, Read, Write};
+
+    use aes::Aes256;
+
+    use super::{FF1Reader, FF1Writer};
+    use crate::ff1::{ByteNumeralString, FF1};
+
+    fn ff() -> FF1<Aes256> {
+        FF1::new_byte_level(&[0; 32]).unwrap()
+    }
+
+    #[test]
+    fn round_trip() {
+        let records: [&[u8]; 2] = [b"alice,42", b"bob,1234"];
+
+        let mut writer = FF1Writer::new(ff(), b"csv", 8, Vec::new());
+        for record in records {
+            // Write each record in two pieces.
+            writer.write_all(&record[..3]).unwrap();
+            writer.write_all(&record[3..]).unwrap();
+            writer.flush().unwrap();
+        }
+        let ct = writer.into_inner();
+        assert_eq!(ct.len(), 16);
+        assert_eq!(
+            &ct[..8],
+            ff().encrypt(b"csv", &ByteNumeralString::from_bytes(records[0]))
+                .unwrap()
+                .as_bytes(),
+        );
+
+        let mut pt = Vec::new();
+        FF1Reader::new(ff(), b"csv", 8, &ct[..])
+            .read_to_end(&mut pt)
+            .unwrap();
+        assert_eq!(pt, records.concat());
+    }
+
+    #[test]
+    fn errors() {
+        let mut writer = FF1Writer::new(ff(), &[], 4, Vec::new());
+        writer.write_all(b"abc").unwrap();
+        assert_eq!(writer.flush().unwrap_err().kind(), ErrorKind::InvalidInput);
+        assert_eq!(writer.write(b"de").unwrap(), 1);
+        assert_eq!(
+            writer.write(b"e").unwrap_err().kind(),
+            ErrorKind::InvalidInput
+        );
+
+        // Radix 256 requires records of at least 3 bytes.
+        let mut writer = FF1Writer::new(ff(), &[], 2, Vec::new());
+        writer.write_all(b"ab").unwrap();
+        assert_eq!(writer.flush().unwrap_err().kind(), ErrorKind::InvalidInput);
+
+        let mut pt = Vec::new();
+        assert_eq!(
+            FF1Reader::new(ff(), &[], 4, &b"abcdefg"[..])
+                .read_to_end(&mut pt)
+                .unwrap_err()
+                .kind(),
+            ErrorKind::UnexpectedEof,
+        );
+    }
+}
@@ -0,0 +1,130 @@
+//! A numeral string for IPv4 addresses.
+
+use std::net::Ipv4Addr;
+
+use super::{FixedHalf, FixedNumeralString, NumeralString, NumeralStringMut};
+
+/// The radix to use when encrypting an [`IPv4NumeralString`].
+const RADIX: u32 = 256;
+
+/// A numeral string representing an IPv4 address as its four octets.
+///
+/// Use a radix of 256 when encrypting, so that every ciphertext is another valid
+/// IPv4 address.
+#[cfg_attr(test, derive(Debug))]
+pub struct IPv4NumeralString(FixedNumeralString<4>);
+
+impl IPv4NumeralString {
+    /// The radix to use when encrypting an `IPv4NumeralString`.
+    pub const RADIX: u32 = RADIX;
+
+    /// Creates a numeral string from the octets of the given address.
+    pub fn from_addr(addr: Ipv4Addr) -> Self {
+        IPv4NumeralString(FixedNumeralString::from(addr.octets().map(u16::from)))
+    }
+
+    /// Returns the address represented by this numeral string.
+    ///
+    /// # Panics
+    ///
+    /// Panics if this numeral string is not valid for radix 256.
+    pub fn to_addr(&self) -> Ipv4Addr {
+        let mut octets = [0; 4];
+        for (octet, n) in octets.iter_mut().zip(self.0.as_slice()) {
+            *octet = u8::try_from(*n).expect("numerals are valid for radix 256");
+        }
+        Ipv4Addr::from(octets)
+    }
+}
+
+impl From<Ipv4Addr> for IPv4NumeralString {
+    fn from(addr: Ipv4Addr) -> Self {
+        IPv4NumeralString::from_addr(addr)
+    }
+}
+
+impl NumeralString for IPv4NumeralString {
+    type Ops = FixedHalf<4>;
+
+    fn is_valid(&self, radix: u32) -> bool {
+        self.0.is_valid(radix)
+    }
+
+    fn numeral_count(&self) -> usize {
+        self.0.numeral_count()
+    }
+
+    fn split(&self) -> (Self::Ops, Self::Ops) {
+        self.0.split()
+    }
+
+    fn concat(a: Self::Ops, b: Self::Ops) -> Self {
+        IPv4NumeralString(FixedNumeralString::concat(a, b))
+    }
+
+    fn reversed(&self) -> Self {
+        IPv4NumeralString(self.0.reversed())
+    }
+}
+
+impl NumeralStringMut for IPv4NumeralString {
+    fn replace_with(&mut self, a: Self::Ops, b: Self::Ops) {
+        self.0.replace_with(a, b);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::Ipv4Addr;
+
+    use aes::Aes256;
+
+    use super::IPv4NumeralString;
+    use crate::ff1::{NumeralStringError, FF1};
+
+    #[test]
+    fn addr_round_trip() {
+        let addr = Ipv4Addr::new(192, 168, 0, 1);
+        let ns = IPv4NumeralString::from_addr(addr);
+        assert_eq!(ns.0.as_slice(), &[192, 168, 0, 1]);
+        assert_eq!(ns.to_addr(), addr);
+    }
+
+    #[test]
+    fn encrypt_round_trip() {
+        let ff = FF1::<Aes256>::new(&[0; 32], IPv4NumeralString::RADIX).unwrap();
+        let tweak = b"network-logs";
+
+        for addr in [
+            Ipv4Addr::UNSPECIFIED,
+            Ipv4Addr::BROADCAST,
+            Ipv4Addr::LOCALHOST,
+            Ipv4Addr::new(0, 0, 0, 255),
+            Ipv4Addr::new(255, 0, 0, 0),
+            Ipv4Addr::new(10, 255, 255, 255),
+            Ipv4Addr::new(172, 16, 0, 1),
+            Ipv4Addr::new(192, 168, 255, 254),
+        ] {
+            let ct = ff
+                .encrypt(tweak, &IPv4NumeralString::from_addr(addr))
+                .unwrap();
+            // Every numeral of the ciphertext is a valid octet.
+            let ct_addr = ct.to_addr();
+
+            let pt = ff
+                .decrypt(tweak, &IPv4NumeralString::from_addr(ct_addr))
+                .unwrap();
+            assert_eq!(pt.to_addr(), addr);
+        }
+    }
+
+    #[test]
+    fn wrong_radix() {
+        let ff = FF1::<Aes256>::new(&[0; 32], 10).unwrap();
+        assert_eq!(
+            ff.encrypt(&[], &IPv4NumeralString::from_addr(Ipv4Addr::BROADCAST))
+                .unwrap_err(),
+            NumeralStringError::InvalidForRadix(10),
+        );
+    }
+}
@@ -0,0 +1,155 @@
+//! Derivation of per-purpose FF1 keys from a master secret.
+
+use core::fmt;
+
+use cipher::{BlockCipher, BlockEncrypt, Key, KeyInit};
+use hkdf::Hkdf;
+use sha2::Sha256;
+
+#[cfg(feature = "zeroize")]
+use cipher::zeroize::Zeroize;
+
+use super::{InvalidRadix, FF1};
+
+/// Derives FF1 keys from a master secret with HKDF-SHA256.
+///
+/// Each key is derived with its purpose (such as `b"ssn"` or `b"pan"`) as the HKDF
+/// `info`, so keys for different purposes are independent, and compromising one does
+/// not reveal the master secret or any other derived key.
+///
+/// ```
+/// use aes::Aes256;
+/// use fpe::ff1::KeyDerivation;
+///
+/// let kdf = KeyDerivation::from_master_key(&[0x42; 32], Some(b"app salt"));
+/// let ssn = kdf.derive_ff1_key::<Aes256>(b"ssn", 10).unwrap();
+/// let pan = kdf.derive_ff1_key::<Aes256>(b"pan", 10).unwrap();
+/// # let _ = (ssn, pan);
+/// ```
+#[derive(Clone)]
+pub struct KeyDerivation {
+    hkdf: Hkdf<Sha256>,
+}
+
+impl fmt::Debug for KeyDerivation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeyDerivation")
+            .field("key", &format_args!("[REDACTED]"))
+            .finish()
+    }
+}
+
+impl KeyDerivation {
+    /// Creates a key derivation from the given master secret and optional salt.
+    ///
+    /// The caller is responsible for zeroizing `ikm` after this call.
+    pub fn from_master_key(ikm: &[u8], salt: Option<&[u8]>) -> Self {
+        KeyDerivation {
+            hkdf: Hkdf::new(salt, ikm),
+        }
+    }
+
+    /// Derives the FF1 key for the given purpose, and creates an FF1 instance with it and
+    /// the given radix.
+    ///
+    /// The key has the key size of `CIPH`. Returns an error if the given radix is not in
+    /// [2..2^16].
+    pub fn derive_ff1_key<CIPH: BlockCipher + BlockEncrypt + Clone + KeyInit>(
+        &self,
+        purpose: &[u8],
+        radix: u32,
+    ) -> Result<FF1<CIPH>, InvalidRadix> {
+        let mut key = Key::<CIPH>::default();
+        self.hkdf
+            .expand(purpose, &mut key)
+            .expect("cipher key is short enough for HKDF-SHA256");
+        let ff = FF1::new_from_key(&key, radix);
+        #[cfg(feature = "zeroize")]
+        key.as_mut_slice().zeroize();
+        ff
+    }
+
+    /// Derives the AES-256 FF1 key for the given purpose, as
+    /// [`KeyDerivation::derive_ff1_key`].
+    #[cfg(feature = "aes")]
+    pub fn derive_ff1_aes256(
+        &self,
+        purpose: &[u8],
+        radix: u32,
+    ) -> Result<FF1<aes::Aes256>, InvalidRadix> {
+        self.derive_ff1_key(purpose, radix)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aes::{Aes128, Aes256};
+    use hkdf::Hkdf;
+    use sha2::Sha256;
+
+    use super::KeyDerivation;
+    use crate::ff1::{FlexibleNumeralString, FF1};
+
+    #[test]
+    fn derive() {
+        let kdf = KeyDerivation::from_master_key(&[0x42; 32], Some(b"salt"));
+        let pt = FlexibleNumeralString::from(vec![1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        let encrypt = |ff: FF1<Aes256>| Vec::from(ff.encrypt(&[], &pt).unwrap());
+
+        // The derived key is the HKDF output for the purpose.
+        let mut key = [0; 32];
+        Hkdf::<Sha256>::new(Some(b"salt"), &[0x42; 32])
+            .expand(b"ssn", &mut key)
+            .unwrap();
+        let ssn = encrypt(kdf.derive_ff1_key(b"ssn", 10).unwrap());
+        assert_eq!(ssn, encrypt(FF1::new(&key, 10).unwrap()));
+
+        // Deriving is deterministic, and depends on the purpose, secret and salt.
+        assert_eq!(ssn, encrypt(kdf.derive_ff1_key(b"ssn", 10).unwrap()));
+        assert_ne!(ssn, encrypt(kdf.derive_ff1_key(b"pan", 10).unwrap()));
+        let other = KeyDerivation::from_master_key(&[0x43; 32], Some(b"salt"));
+        assert_ne!(ssn, encrypt(other.derive_ff1_key(b"ssn", 10).unwrap()));
+        let unsalted = KeyDerivation::from_master_key(&[0x42; 32], None);
+        assert_ne!(ssn, encrypt(unsalted.derive_ff1_key(b"ssn", 10).unwrap()));
+
+        // The key size follows the cipher.
+        let mut key = [0; 16];
+        Hkdf::<Sha256>::new(Some(b"salt"), &[0x42; 32])
+            .expand(b"ssn", &mut key)
+            .unwrap();
+        let ff = kdf.derive_ff1_key::<Aes128>(b"ssn", 10).unwrap();
+        assert_eq!(
+            Vec::from(ff.encrypt(&[], &pt).unwrap()),
+            Vec::from(
+                FF1::<Aes128>::new(&key, 10)
+                    .unwrap()
+                    .encrypt(&[], &pt)
+                    .unwrap()
+            ),
+        );
+
+        assert!(kdf.derive_ff1_key::<Aes256>(b"ssn", 1).is_err());
+        assert_eq!(format!("{:?}", kdf), "KeyDerivation { key: [REDACTED] }");
+    }
+
+    #[cfg(feature = "aes")]
+    #[test]
+    fn aes256() {
+        let kdf = KeyDerivation::from_master_key(&[0x42; 32], None);
+        let pt = FlexibleNumeralString::from(vec![1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        assert_eq!(
+            Vec::from(
+                kdf.derive_ff1_aes256(b"ssn", 10)
+                    .unwrap()
+                    .encrypt(&[], &pt)
+                    .unwrap()
+            ),
+            Vec::from(
+                kdf.derive_ff1_key::<Aes256>(b"ssn", 10)
+                    .unwrap()
+                    .encrypt(&[], &pt)
+                    .unwrap()
+            ),
+        );
+    }
+}
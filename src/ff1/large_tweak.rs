@@ -0,0 +1,181 @@
+//! Tweaks longer than FF1 allows, which are replaced by their hash.
+
+use core::{fmt, marker::PhantomData};
+
+use cipher::{BlockCipher, BlockEncrypt, KeyInit};
+use digest::Digest;
+
+use super::{InvalidRadix, NumeralString, NumeralStringError, FF1, MAX_TWEAK_LEN};
+
+/// The minimum length of a hashed tweak, in bytes.
+const MIN_HASHED_TWEAK_LEN: usize = 16;
+
+/// Indicates that a tweak was longer than NIST SP 800-38G allows, and was replaced by
+/// its hash.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LargeTweakHashedWarning {
+    /// The length of the original tweak, in bytes.
+    pub tweak_len: usize,
+}
+
+impl fmt::Display for LargeTweakHashedWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "The tweak of {} bytes was too long for FF1, and was hashed",
+            self.tweak_len,
+        )
+    }
+}
+
+/// An [`FF1`] instance that accepts tweaks longer than `u32::MAX` bytes, by replacing
+/// them with their hash under `H`.
+///
+/// Tweaks of up to `u32::MAX` bytes are used as-is, so this behaves identically to
+/// [`FF1`] for them. Longer tweaks are replaced by their digest, padded with zeroes to
+/// 16 bytes if it is shorter, and each result carries a [`LargeTweakHashedWarning`].
+///
+/// This is an extension beyond NIST SP 800-38G, for systems that construct tweaks by
+/// concatenating unbounded context fields. A hashed tweak encrypts identically to the
+/// short tweak that is equal to its digest.
+pub struct LargeTweakFF1<CIPH: BlockCipher + BlockEncrypt, H> {
+    ff: FF1<CIPH>,
+    _hash: PhantomData<fn() -> H>,
+}
+
+impl<CIPH: BlockCipher + BlockEncrypt + Clone + KeyInit> FF1<CIPH> {
+    /// Creates a new FF1 object for the given key and radix, which hashes tweaks that
+    /// are longer than `u32::MAX` bytes with `H`.
+    ///
+    /// Returns an error if the given radix is not in [2..2^16].
+    pub fn new_with_large_tweak_support<H: Digest>(
+        key: &[u8],
+        radix: u32,
+    ) -> Result<LargeTweakFF1<CIPH, H>, InvalidRadix> {
+        FF1::new(key, radix).map(LargeTweakFF1::new)
+    }
+}
+
+impl<CIPH: BlockCipher + BlockEncrypt, H> LargeTweakFF1<CIPH, H> {
+    /// Wraps the given FF1 instance.
+    pub fn new(ff: FF1<CIPH>) -> Self {
+        LargeTweakFF1 {
+            ff,
+            _hash: PhantomData,
+        }
+    }
+
+    /// Returns the wrapped FF1 instance.
+    pub fn inner(&self) -> &FF1<CIPH> {
+        &self.ff
+    }
+}
+
+/// The result of encrypting or decrypting with a [`LargeTweakFF1`], and whether the
+/// tweak was hashed.
+pub type LargeTweakResult<NS> = Result<(NS, Option<LargeTweakHashedWarning>), NumeralStringError>;
+
+impl<CIPH: BlockCipher + BlockEncrypt + Clone, H: Digest> LargeTweakFF1<CIPH, H> {
+    /// Encrypts the given numeral string.
+    ///
+    /// Returns an error if the numeral string is not in the required radix.
+    pub fn encrypt<NS: NumeralString>(&self, tweak: &[u8], x: &NS) -> LargeTweakResult<NS> {
+        with_tweak::<H, _>(tweak, MAX_TWEAK_LEN, |tweak| self.ff.encrypt(tweak, x))
+    }
+
+    /// Decrypts the given numeral string.
+    ///
+    /// Returns an error if the numeral string is not in the required radix.
+    pub fn decrypt<NS: NumeralString>(&self, tweak: &[u8], x: &NS) -> LargeTweakResult<NS> {
+        with_tweak::<H, _>(tweak, MAX_TWEAK_LEN, |tweak| self.ff.decrypt(tweak, x))
+    }
+}
+
+/// Calls `f` with `tweak`, or with its hash if it is longer than `max_len` bytes.
+fn with_tweak<H: Digest, NS>(
+    tweak: &[u8],
+    max_len: usize,
+    f: impl FnOnce(&[u8]) -> Result<NS, NumeralStringError>,
+) -> LargeTweakResult<NS> {
+    if tweak.len() <= max_len {
+        return f(tweak).map(|y| (y, None));
+    }
+
+    let digest = H::digest(tweak);
+    let mut padded = [0; MIN_HASHED_TWEAK_LEN];
+    let hashed = if digest.len() < MIN_HASHED_TWEAK_LEN {
+        padded[..digest.len()].copy_from_slice(&digest);
+        &padded[..]
+    } else {
+        &digest[..]
+    };
+    let warning = LargeTweakHashedWarning {
+        tweak_len: tweak.len(),
+    };
+    f(hashed).map(|y| (y, Some(warning)))
+}
+
+#[cfg(test)]
+mod tests {
+    use aes::Aes256;
+    use sha2::{Digest, Sha256};
+
+    use super::{with_tweak, LargeTweakHashedWarning};
+    use crate::ff1::{FlexibleNumeralString, FF1};
+
+    #[test]
+    fn short_tweaks() {
+        let ff = FF1::<Aes256>::new_with_large_tweak_support::<Sha256>(&[0; 32], 10).unwrap();
+        let pt = FlexibleNumeralString::from(vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+
+        let (ct, warning) = ff.encrypt(b"tweak", &pt).unwrap();
+        assert_eq!(warning, None);
+        assert_eq!(
+            ct.to_decimal_str(),
+            ff.inner().encrypt(b"tweak", &pt).unwrap().to_decimal_str()
+        );
+        let (pt, warning) = ff.decrypt(b"tweak", &ct).unwrap();
+        assert_eq!(warning, None);
+        assert_eq!(pt.to_decimal_str(), "0123456789");
+    }
+
+    #[test]
+    fn hashed_tweaks() {
+        // Tweaks longer than u32::MAX bytes are too large to test with, so check the
+        // hashing with a lower limit.
+        let tweak = [7; 20];
+        let (hashed, warning) = with_tweak::<Sha256, _>(&tweak, 19, |t| Ok(t.to_vec())).unwrap();
+        assert_eq!(hashed[..], Sha256::digest(tweak)[..]);
+        assert_eq!(warning, Some(LargeTweakHashedWarning { tweak_len: 20 }));
+        assert_eq!(
+            warning.unwrap().to_string(),
+            "The tweak of 20 bytes was too long for FF1, and was hashed"
+        );
+
+        let (unhashed, warning) = with_tweak::<Sha256, _>(&tweak, 20, |t| Ok(t.to_vec())).unwrap();
+        assert_eq!(unhashed, tweak);
+        assert_eq!(warning, None);
+
+        // Digests shorter than 16 bytes are padded with zeroes.
+        #[derive(Clone, Default)]
+        struct Short(Sha256);
+        impl digest::HashMarker for Short {}
+        impl digest::OutputSizeUser for Short {
+            type OutputSize = digest::consts::U8;
+        }
+        impl digest::FixedOutput for Short {
+            fn finalize_into(self, out: &mut digest::Output<Self>) {
+                out.copy_from_slice(&self.0.finalize()[..8]);
+            }
+        }
+        impl digest::Update for Short {
+            fn update(&mut self, data: &[u8]) {
+                digest::Update::update(&mut self.0, data);
+            }
+        }
+
+        let (hashed, _) = with_tweak::<Short, _>(&tweak, 19, |t| Ok(t.to_vec())).unwrap();
+        assert_eq!(hashed[..8], Sha256::digest(tweak)[..8]);
+        assert_eq!(hashed[8..], [0; 8]);
+    }
+}
@@ -0,0 +1,151 @@
+//! Encryption of a sub-range of a numeral string.
+
+use core::ops::Range;
+
+use cipher::{BlockCipher, BlockEncrypt};
+
+use super::{NumeralStringError, SliceableNumeralString, FF1};
+
+/// A numeral string together with the range of its numerals that should be encrypted.
+///
+/// The numerals outside the range are passed through [`FF1::encrypt_masked`] and
+/// [`FF1::decrypt_masked`] unchanged. This can be used, for example, to keep the first
+/// six and last four digits of a card number in plaintext.
+pub struct MaskedNumeralString<NS> {
+    ns: NS,
+    range: Range<usize>,
+}
+
+impl<NS> MaskedNumeralString<NS> {
+    /// Marks the numerals of `ns` in `range` for encryption.
+    pub fn new(ns: NS, range: Range<usize>) -> Self {
+        MaskedNumeralString { ns, range }
+    }
+}
+
+impl<NS> From<(NS, Range<usize>)> for MaskedNumeralString<NS> {
+    fn from((ns, range): (NS, Range<usize>)) -> Self {
+        MaskedNumeralString::new(ns, range)
+    }
+}
+
+impl<CIPH: BlockCipher + BlockEncrypt + Clone> FF1<CIPH> {
+    /// Encrypts the masked range of the given numeral string, leaving the other
+    /// numerals unchanged.
+    ///
+    /// Returns an error if the range is not within the numeral string, if the numeral
+    /// string is not in the required radix, or if the range is too short for the radix.
+    /// An empty range returns the numeral string unchanged.
+    pub fn encrypt_masked<NS, M>(&self, tweak: &[u8], x: M) -> Result<NS, NumeralStringError>
+    where
+        NS: SliceableNumeralString,
+        M: Into<MaskedNumeralString<NS>>,
+    {
+        self.transform_masked(x.into(), |ns| self.encrypt(tweak, ns))
+    }
+
+    /// Decrypts the masked range of the given numeral string, leaving the other
+    /// numerals unchanged.
+    ///
+    /// Returns an error if the range is not within the numeral string, if the numeral
+    /// string is not in the required radix, or if the range is too short for the radix.
+    /// An empty range returns the numeral string unchanged.
+    pub fn decrypt_masked<NS, M>(&self, tweak: &[u8], x: M) -> Result<NS, NumeralStringError>
+    where
+        NS: SliceableNumeralString,
+        M: Into<MaskedNumeralString<NS>>,
+    {
+        self.transform_masked(x.into(), |ns| self.decrypt(tweak, ns))
+    }
+
+    fn transform_masked<NS: SliceableNumeralString>(
+        &self,
+        x: MaskedNumeralString<NS>,
+        f: impl FnOnce(&NS) -> Result<NS, NumeralStringError>,
+    ) -> Result<NS, NumeralStringError> {
+        let MaskedNumeralString { mut ns, range } = x;
+        let ns_len = ns.numeral_count();
+        if range.start > range.end || range.end > ns_len {
+            return Err(NumeralStringError::InvalidRange {
+                start: range.start,
+                end: range.end,
+                ns_len,
+            });
+        }
+        if range.is_empty() {
+            return Ok(ns);
+        }
+        if !ns.is_valid(self.radix.to_u32()) {
+            return Err(NumeralStringError::InvalidForRadix(self.radix.to_u32()));
+        }
+
+        let segment = f(&ns.slice(range.clone()))?;
+        ns.splice(range, segment);
+        Ok(ns)
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use aes::Aes256;
+
+    use super::MaskedNumeralString;
+    use crate::ff1::{FlexibleNumeralString, NumeralStringError, FF1};
+
+    fn ns(s: &str) -> FlexibleNumeralString {
+        FlexibleNumeralString::from_decimal_str(s).unwrap()
+    }
+
+    #[test]
+    fn masked_round_trip() {
+        let ff = FF1::<Aes256>::new(&[0; 32], 10).unwrap();
+        let pan = "4111111111111111";
+
+        let ct = ff.encrypt_masked(b"tweak", (ns(pan), 6..12)).unwrap();
+        let ct_str = ct.to_decimal_str();
+        assert_eq!(ct_str[..6], pan[..6]);
+        assert_eq!(ct_str[12..], pan[12..]);
+        assert_eq!(
+            ct_str[6..12],
+            ff.encrypt(b"tweak", &ns(&pan[6..12]))
+                .unwrap()
+                .to_decimal_str(),
+        );
+
+        let pt = ff
+            .decrypt_masked(b"tweak", MaskedNumeralString::new(ct, 6..12))
+            .unwrap();
+        assert_eq!(pt.to_decimal_str(), pan);
+    }
+
+    #[test]
+    fn masked_edge_cases() {
+        let ff = FF1::<Aes256>::new(&[0; 32], 10).unwrap();
+        let pan = "4111111111111111";
+
+        assert_eq!(
+            ff.encrypt_masked(&[], (ns(pan), 3..3))
+                .unwrap()
+                .to_decimal_str(),
+            pan,
+        );
+        assert_eq!(
+            ff.encrypt_masked(&[], (ns(pan), 10..17)).unwrap_err(),
+            NumeralStringError::InvalidRange {
+                start: 10,
+                end: 17,
+                ns_len: 16,
+            },
+        );
+        #[allow(clippy::reversed_empty_ranges)]
+        let reversed = 12..6;
+        assert!(ff.encrypt_masked(&[], (ns(pan), reversed)).is_err());
+        assert_eq!(
+            ff.encrypt_masked(&[], (ns(pan), 6..11)).unwrap_err(),
+            NumeralStringError::TooShort {
+                ns_len: 5,
+                min_len: 6,
+            },
+        );
+    }
+}
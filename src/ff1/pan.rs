@@ -0,0 +1,320 @@
+//! Tokenization of payment card numbers (PANs).
+
+use core::{fmt, str::FromStr};
+
+use alloc::vec::Vec;
+use cipher::{BlockCipher, BlockEncrypt};
+
+use super::{
+    prefix::prefixed_tweak, FlexibleNumeralString, NumeralString, NumeralStringError, FF1,
+};
+
+const RADIX: u32 = 10;
+const MIN_PAN_LEN: usize = 13;
+const MAX_PAN_LEN: usize = 19;
+
+/// How [`FF1::encrypt_pan`] and [`FF1::decrypt_pan`] treat the Luhn check digit.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LuhnMode {
+    /// The last digit is excluded from encryption, and is re-computed afterwards so
+    /// that the token passes Luhn validation.
+    ///
+    /// Decryption only recovers the original PAN if it was itself Luhn-valid.
+    Preserve,
+    /// The last digit is encrypted along with the others, so the token is generally
+    /// not Luhn-valid.
+    Ignore,
+}
+
+/// A payment card number of 13 to 19 decimal digits.
+#[cfg_attr(test, derive(Debug))]
+pub struct CreditCardNumeralString(Vec<u16>);
+
+impl CreditCardNumeralString {
+    /// Returns `true` if the last digit of this PAN is a valid Luhn check digit.
+    pub fn is_luhn_valid(&self) -> bool {
+        let (body, check) = self.0.split_at(self.0.len() - 1);
+        luhn_check_digit(body) == check[0]
+    }
+}
+
+impl FromStr for CreditCardNumeralString {
+    type Err = NumeralStringError;
+
+    /// Creates a `CreditCardNumeralString` from a string of decimal digits.
+    ///
+    /// Returns [`NumeralStringError::InvalidNumeral`] for the first character that is
+    /// not a decimal digit, or an error if `s` does not contain between 13 and 19
+    /// digits.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let digits = s
+            .chars()
+            .enumerate()
+            .map(|(position, c)| match c.to_digit(RADIX) {
+                Some(d) => Ok(d as u16),
+                None => Err(NumeralStringError::InvalidNumeral {
+                    position,
+                    value: c.into(),
+                    radix: RADIX,
+                }),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let ns_len = digits.len();
+        if ns_len < MIN_PAN_LEN {
+            Err(NumeralStringError::TooShort {
+                ns_len,
+                min_len: MIN_PAN_LEN,
+            })
+        } else if ns_len > MAX_PAN_LEN {
+            Err(NumeralStringError::TooLong {
+                ns_len,
+                max_len: MAX_PAN_LEN,
+            })
+        } else {
+            Ok(CreditCardNumeralString(digits))
+        }
+    }
+}
+
+impl fmt::Display for CreditCardNumeralString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for d in &self.0 {
+            write!(f, "{}", d)?;
+        }
+        Ok(())
+    }
+}
+
+impl NumeralString for CreditCardNumeralString {
+    type Ops = FlexibleNumeralString;
+
+    fn is_valid(&self, radix: u32) -> bool {
+        self.0.iter().all(|n| u32::from(*n) < radix)
+    }
+
+    fn numeral_count(&self) -> usize {
+        self.0.len()
+    }
+
+    fn split(&self) -> (Self::Ops, Self::Ops) {
+        FlexibleNumeralString::from(self.0.clone()).split()
+    }
+
+    fn concat(a: Self::Ops, b: Self::Ops) -> Self {
+        CreditCardNumeralString(FlexibleNumeralString::concat(a, b).into())
+    }
+
+    fn reversed(&self) -> Self {
+        CreditCardNumeralString(self.0.iter().rev().copied().collect())
+    }
+}
+
+/// Computes the Luhn check digit that should follow `body`.
+pub(super) fn luhn_check_digit(body: &[u16]) -> u16 {
+    let sum: u16 = body
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, &d)| {
+            // Double every second digit, starting with the one next to the check digit.
+            if i % 2 == 0 {
+                let d = d * 2;
+                if d > 9 {
+                    d - 9
+                } else {
+                    d
+                }
+            } else {
+                d
+            }
+        })
+        .sum();
+    (10 - sum % 10) % 10
+}
+
+impl<CIPH: BlockCipher + BlockEncrypt + Clone> FF1<CIPH> {
+    /// Encrypts the given PAN, leaving the first `bin_len` digits (the BIN) unchanged.
+    ///
+    /// The BIN digits are prepended to the tweak, so that two PANs with the same
+    /// account digits but different BINs have unrelated tokens.
+    ///
+    /// The FF1 instance must have been created with radix 10. Returns an error if the
+    /// radix is not 10, or if fewer digits remain to be encrypted than radix 10
+    /// requires.
+    pub fn encrypt_pan(
+        &self,
+        tweak: &[u8],
+        pan: &CreditCardNumeralString,
+        bin_len: usize,
+        luhn: LuhnMode,
+    ) -> Result<CreditCardNumeralString, NumeralStringError> {
+        self.transform_pan(tweak, pan, bin_len, luhn, |t, x| self.encrypt(t, x))
+    }
+
+    /// Decrypts the given PAN token, leaving the first `bin_len` digits (the BIN)
+    /// unchanged.
+    ///
+    /// `bin_len` and `luhn` must match the values used for encryption.
+    pub fn decrypt_pan(
+        &self,
+        tweak: &[u8],
+        token: &CreditCardNumeralString,
+        bin_len: usize,
+        luhn: LuhnMode,
+    ) -> Result<CreditCardNumeralString, NumeralStringError> {
+        self.transform_pan(tweak, token, bin_len, luhn, |t, x| self.decrypt(t, x))
+    }
+
+    fn transform_pan(
+        &self,
+        tweak: &[u8],
+        pan: &CreditCardNumeralString,
+        bin_len: usize,
+        luhn: LuhnMode,
+        f: impl FnOnce(
+            &[u8],
+            &FlexibleNumeralString,
+        ) -> Result<FlexibleNumeralString, NumeralStringError>,
+    ) -> Result<CreditCardNumeralString, NumeralStringError> {
+        if self.radix.to_u32() != RADIX {
+            return Err(NumeralStringError::InvalidForRadix(self.radix.to_u32()));
+        }
+
+        let end = match luhn {
+            LuhnMode::Preserve => pan.0.len() - 1,
+            LuhnMode::Ignore => pan.0.len(),
+        };
+        let start = bin_len.min(end);
+
+        let middle = f(
+            &prefixed_tweak(pan.0[..start].iter().copied(), tweak),
+            &FlexibleNumeralString::from(pan.0[start..end].to_vec()),
+        )?;
+
+        let mut digits = pan.0[..start].to_vec();
+        digits.extend(Vec::from(middle));
+        if luhn == LuhnMode::Preserve {
+            digits.push(luhn_check_digit(&digits));
+        }
+        Ok(CreditCardNumeralString(digits))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::str::FromStr;
+
+    use aes::Aes256;
+
+    use super::{CreditCardNumeralString, LuhnMode};
+    use crate::ff1::{FlexibleNumeralString, NumeralStringError, FF1};
+
+    #[test]
+    fn from_str() {
+        let pan = CreditCardNumeralString::from_str("4111111111111111").unwrap();
+        assert_eq!(pan.to_string(), "4111111111111111");
+        assert!(pan.is_luhn_valid());
+        assert!(!CreditCardNumeralString::from_str("4111111111111112")
+            .unwrap()
+            .is_luhn_valid());
+
+        assert_eq!(
+            CreditCardNumeralString::from_str("411111111111").unwrap_err(),
+            NumeralStringError::TooShort {
+                ns_len: 12,
+                min_len: 13,
+            },
+        );
+        assert_eq!(
+            CreditCardNumeralString::from_str("41111111111111111111").unwrap_err(),
+            NumeralStringError::TooLong {
+                ns_len: 20,
+                max_len: 19,
+            },
+        );
+        assert_eq!(
+            CreditCardNumeralString::from_str("4111-1111-1111-1111").unwrap_err(),
+            NumeralStringError::InvalidNumeral {
+                position: 4,
+                value: '-'.into(),
+                radix: 10,
+            },
+        );
+    }
+
+    #[test]
+    fn pan_round_trip() {
+        let ff = FF1::<Aes256>::new(&[0; 32], 10).unwrap();
+        let tweak = b"merchant";
+
+        for s in ["4111111111111111", "378282246310005", "6011000990139424"] {
+            let pan = CreditCardNumeralString::from_str(s).unwrap();
+            for bin_len in [0, 6] {
+                for luhn in [LuhnMode::Preserve, LuhnMode::Ignore] {
+                    let token = ff.encrypt_pan(tweak, &pan, bin_len, luhn).unwrap();
+                    let token_str = token.to_string();
+                    assert_eq!(token_str.len(), s.len());
+                    assert_ne!(token_str, s);
+                    assert_eq!(token_str[..bin_len], s[..bin_len]);
+                    if luhn == LuhnMode::Preserve {
+                        assert!(token.is_luhn_valid());
+                    }
+
+                    let decrypted = ff.decrypt_pan(tweak, &token, bin_len, luhn).unwrap();
+                    assert_eq!(decrypted.to_string(), s);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn bin_bound_to_tweak() {
+        let ff = FF1::<Aes256>::new(&[0; 32], 10).unwrap();
+        let encrypt = |s| {
+            let pan = CreditCardNumeralString::from_str(s).unwrap();
+            ff.encrypt_pan(b"tweak", &pan, 6, LuhnMode::Ignore)
+                .unwrap()
+                .to_string()
+        };
+
+        // The same account digits under different BINs have unrelated tokens.
+        let a = encrypt("4111111234567890");
+        let b = encrypt("5111111234567890");
+        assert_eq!(&a[..6], "411111");
+        assert_eq!(&b[..6], "511111");
+        assert_ne!(a[6..], b[6..]);
+
+        // The account digits are encrypted with the BIN prepended to the tweak.
+        let middle = ff
+            .encrypt(
+                b"\0\x04\0\x01\0\x01\0\x01\0\x01\0\x01tweak",
+                &FlexibleNumeralString::from_decimal_str("1234567890").unwrap(),
+            )
+            .unwrap();
+        assert_eq!(a[6..], middle.to_decimal_str());
+    }
+
+    #[test]
+    fn pan_errors() {
+        let pan = CreditCardNumeralString::from_str("4111111111111111").unwrap();
+
+        let ff = FF1::<Aes256>::new(&[0; 32], 16).unwrap();
+        assert_eq!(
+            ff.encrypt_pan(&[], &pan, 6, LuhnMode::Preserve)
+                .unwrap_err(),
+            NumeralStringError::InvalidForRadix(16),
+        );
+
+        // Only 5 digits are left to encrypt, but radix 10 requires at least 6.
+        let ff = FF1::<Aes256>::new(&[0; 32], 10).unwrap();
+        assert_eq!(
+            ff.encrypt_pan(&[], &pan, 10, LuhnMode::Preserve)
+                .unwrap_err(),
+            NumeralStringError::TooShort {
+                ns_len: 5,
+                min_len: 6,
+            },
+        );
+    }
+}
@@ -0,0 +1,172 @@
+//! FF1 that leaves a fixed-length prefix of each numeral string in plaintext.
+
+use alloc::vec::Vec;
+use cipher::{BlockCipher, BlockEncrypt};
+
+use super::{InspectableOps, NumeralStringError, SliceableNumeralString, FF1};
+
+/// An [`FF1`] instance that encrypts all but the first `prefix_len` numerals of each
+/// numeral string.
+///
+/// This reveals the prefix, for example the BIN of a card number so that tokens can
+/// still be routed to the issuing bank, while encrypting the rest. The prefix
+/// numerals are prepended to the tweak, so that two numeral strings with the same
+/// suffix but different prefixes have unrelated ciphertext suffixes.
+pub struct PrefixPreservingFF1<CIPH: BlockCipher + BlockEncrypt> {
+    ff: FF1<CIPH>,
+    prefix_len: usize,
+}
+
+impl<CIPH: BlockCipher + BlockEncrypt> PrefixPreservingFF1<CIPH> {
+    /// Wraps the given FF1 instance, preserving the first `prefix_len` numerals.
+    pub fn new(ff: FF1<CIPH>, prefix_len: usize) -> Self {
+        PrefixPreservingFF1 { ff, prefix_len }
+    }
+
+    /// Returns the wrapped FF1 instance.
+    pub fn inner(&self) -> &FF1<CIPH> {
+        &self.ff
+    }
+
+    /// Returns the number of numerals that are left in plaintext.
+    pub fn prefix_len(&self) -> usize {
+        self.prefix_len
+    }
+}
+
+impl<CIPH: BlockCipher + BlockEncrypt + Clone> PrefixPreservingFF1<CIPH> {
+    /// Encrypts all but the prefix of the given numeral string.
+    ///
+    /// Returns [`NumeralStringError::TooShort`] if the numerals after the prefix are
+    /// fewer than the minimum length for the radix, or an error if the numeral string
+    /// is not in the required radix.
+    pub fn encrypt<NS>(&self, tweak: &[u8], x: &NS) -> Result<NS, NumeralStringError>
+    where
+        NS: SliceableNumeralString,
+        NS::Ops: InspectableOps,
+    {
+        self.transform(tweak, x, |tweak, suffix| self.ff.encrypt(tweak, suffix))
+    }
+
+    /// Decrypts all but the prefix of the given numeral string.
+    ///
+    /// Returns [`NumeralStringError::TooShort`] if the numerals after the prefix are
+    /// fewer than the minimum length for the radix, or an error if the numeral string
+    /// is not in the required radix.
+    pub fn decrypt<NS>(&self, tweak: &[u8], x: &NS) -> Result<NS, NumeralStringError>
+    where
+        NS: SliceableNumeralString,
+        NS::Ops: InspectableOps,
+    {
+        self.transform(tweak, x, |tweak, suffix| self.ff.decrypt(tweak, suffix))
+    }
+
+    fn transform<NS>(
+        &self,
+        tweak: &[u8],
+        x: &NS,
+        f: impl FnOnce(&[u8], &NS) -> Result<NS, NumeralStringError>,
+    ) -> Result<NS, NumeralStringError>
+    where
+        NS: SliceableNumeralString,
+        NS::Ops: InspectableOps,
+    {
+        let ns_len = x.numeral_count();
+        let min_len = self.prefix_len + self.ff.radix.min_len();
+        if ns_len < min_len {
+            return Err(NumeralStringError::TooShort { ns_len, min_len });
+        }
+        if !x.is_valid(self.ff.radix.to_u32()) {
+            return Err(NumeralStringError::InvalidForRadix(self.ff.radix.to_u32()));
+        }
+
+        let (a, b) = x.slice(0..self.prefix_len).split();
+        let prefix = a.to_numerals().iter().chain(b.to_numerals());
+        let tweak = prefixed_tweak(prefix.map(|&n| n.into()), tweak);
+
+        let range = self.prefix_len..ns_len;
+        let suffix = f(&tweak, &x.slice(range.clone()))?;
+        let mut y = x.slice(0..ns_len);
+        y.splice(range, suffix);
+        Ok(y)
+    }
+}
+
+/// Returns `tweak` with the given prefix numerals prepended, each as two big-endian
+/// bytes.
+///
+/// Callers must only use this with prefixes of a fixed length, so that prepending
+/// the prefix to the tweak is unambiguous.
+pub(super) fn prefixed_tweak(prefix: impl Iterator<Item = u16>, tweak: &[u8]) -> Vec<u8> {
+    let mut prefixed: Vec<u8> = prefix.flat_map(u16::to_be_bytes).collect();
+    prefixed.extend_from_slice(tweak);
+    prefixed
+}
+
+#[cfg(test)]
+mod tests {
+    use aes::Aes256;
+
+    use super::PrefixPreservingFF1;
+    use crate::ff1::{FlexibleNumeralString, NumeralStringError, FF1};
+
+    fn ns(s: &str) -> FlexibleNumeralString {
+        FlexibleNumeralString::from_decimal_str(s).unwrap()
+    }
+
+    #[test]
+    fn preserves_prefix() {
+        let ff = PrefixPreservingFF1::new(FF1::<Aes256>::new(&[0; 32], 10).unwrap(), 6);
+        let pan = "4111111111111111";
+
+        let ct = ff.encrypt(b"tweak", &ns(pan)).unwrap().to_decimal_str();
+        assert_eq!(ct[..6], pan[..6]);
+        assert_ne!(ct[6..], pan[6..]);
+        // The suffix is encrypted with the prefix prepended to the tweak.
+        assert_eq!(
+            ct[6..],
+            ff.inner()
+                .encrypt(b"\0\x04\0\x01\0\x01\0\x01\0\x01\0\x01tweak", &ns(&pan[6..]))
+                .unwrap()
+                .to_decimal_str(),
+        );
+        assert_eq!(
+            ff.decrypt(b"tweak", &ns(&ct)).unwrap().to_decimal_str(),
+            pan
+        );
+
+        // The same suffix with a different prefix encrypts differently.
+        let other = ff
+            .encrypt(b"tweak", &ns("5111111111111111"))
+            .unwrap()
+            .to_decimal_str();
+        assert_eq!(other[..6], *"511111");
+        assert_ne!(other[6..], ct[6..]);
+    }
+
+    #[test]
+    fn errors() {
+        let ff = PrefixPreservingFF1::new(FF1::<Aes256>::new(&[0; 32], 10).unwrap(), 6);
+        assert_eq!(ff.prefix_len(), 6);
+        assert!(ff.encrypt(&[], &ns("411111123456")).is_ok());
+        assert_eq!(
+            ff.encrypt(&[], &ns("41111112345")).unwrap_err(),
+            NumeralStringError::TooShort {
+                ns_len: 11,
+                min_len: 12,
+            },
+        );
+        assert_eq!(
+            ff.encrypt(&[], &ns("4111")).unwrap_err(),
+            NumeralStringError::TooShort {
+                ns_len: 4,
+                min_len: 12,
+            },
+        );
+        assert_eq!(
+            ff.encrypt(&[], &FlexibleNumeralString::from(vec![10; 12]))
+                .unwrap_err(),
+            NumeralStringError::InvalidForRadix(10),
+        );
+    }
+}
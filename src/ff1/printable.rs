@@ -0,0 +1,271 @@
+//! A numeral string over the printable, non-space ASCII characters.
+
+use core::{convert::TryFrom, fmt, ops::Range, str::FromStr};
+
+use alloc::{string::String, vec, vec::Vec};
+
+use cipher::{BlockCipher, BlockEncrypt};
+
+use super::{
+    digits, InspectableOps, NumeralString, NumeralStringError, NumeralStringMut, Operations,
+    SliceableNumeralString, FF1,
+};
+
+/// The number of printable, non-space ASCII characters, `'!'..='~'`.
+const RADIX: u32 = 94;
+
+/// The character for numeral 0.
+const OFFSET: u8 = b'!';
+
+/// A numeral string with radix 94, whose numerals are the printable, non-space ASCII
+/// characters `'!'` (33) to `'~'` (126).
+///
+/// The ciphertexts of such numeral strings are printable strings of the same length,
+/// for storing tokens in HTTP headers, URL parameters or QR codes. As with
+/// [`DecimalNumeralString`](super::DecimalNumeralString), FF1 arithmetic is performed
+/// directly on the numerals.
+#[cfg_attr(test, derive(Debug))]
+pub struct PrintableAsciiNumeralString(Vec<u8>);
+
+impl PrintableAsciiNumeralString {
+    /// Returns the printable ASCII string for this numeral string.
+    pub fn to_str(&self) -> String {
+        self.0.iter().map(|&n| char::from(n + OFFSET)).collect()
+    }
+}
+
+impl FromStr for PrintableAsciiNumeralString {
+    type Err = NumeralStringError;
+
+    /// Creates a `PrintableAsciiNumeralString` from a string of printable, non-space
+    /// ASCII characters.
+    ///
+    /// Returns an error if any character is not in `'!'..='~'`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.chars()
+            .enumerate()
+            .map(|(position, c)| match c {
+                '!'..='~' => Ok(c as u8 - OFFSET),
+                _ => Err(NumeralStringError::InvalidNumeral {
+                    position,
+                    value: c.into(),
+                    radix: RADIX,
+                }),
+            })
+            .collect::<Result<_, _>>()
+            .map(PrintableAsciiNumeralString)
+    }
+}
+
+impl TryFrom<&str> for PrintableAsciiNumeralString {
+    type Error = NumeralStringError;
+
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
+        s.parse()
+    }
+}
+
+impl fmt::Display for PrintableAsciiNumeralString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_str())
+    }
+}
+
+impl NumeralString for PrintableAsciiNumeralString {
+    type Ops = Self;
+
+    fn is_valid(&self, radix: u32) -> bool {
+        // The numerals are valid for radix 94 by construction.
+        radix == RADIX
+    }
+
+    fn numeral_count(&self) -> usize {
+        self.0.len()
+    }
+
+    fn split(&self) -> (Self, Self) {
+        let mut front = self.0.clone();
+        let back = front.split_off(self.0.len() / 2);
+        (
+            PrintableAsciiNumeralString(front),
+            PrintableAsciiNumeralString(back),
+        )
+    }
+
+    fn concat(mut a: Self, mut b: Self) -> Self {
+        a.0.append(&mut b.0);
+        a
+    }
+
+    fn reversed(&self) -> Self {
+        PrintableAsciiNumeralString(self.0.iter().rev().copied().collect())
+    }
+}
+
+impl NumeralStringMut for PrintableAsciiNumeralString {
+    fn replace_with(&mut self, a: Self, b: Self) {
+        self.0.clear();
+        self.0.extend_from_slice(&a.0);
+        self.0.extend_from_slice(&b.0);
+    }
+}
+
+impl SliceableNumeralString for PrintableAsciiNumeralString {
+    fn slice(&self, range: Range<usize>) -> Self {
+        PrintableAsciiNumeralString(self.0[range].to_vec())
+    }
+
+    fn splice(&mut self, range: Range<usize>, replacement: Self) {
+        self.0[range].copy_from_slice(&replacement.0);
+    }
+}
+
+impl Operations for PrintableAsciiNumeralString {
+    type Bytes = Vec<u8>;
+
+    fn numeral_count(&self) -> usize {
+        self.0.len()
+    }
+
+    fn to_be_bytes(&self, radix: u32, b: usize) -> Self::Bytes {
+        assert_eq!(radix, RADIX);
+        let mut bytes = vec![0; b];
+        digits::to_be_bytes(&self.0, RADIX, &mut bytes);
+        bytes
+    }
+
+    fn add_mod_exp(mut self, other: impl Iterator<Item = u8>, radix: u32, m: usize) -> Self {
+        assert_eq!(radix, RADIX);
+        let mut y = vec![0; m];
+        digits::reduce_be_bytes(other, RADIX, &mut y);
+        digits::add_mod(&mut self.0, &y, RADIX);
+        self
+    }
+
+    fn sub_mod_exp(mut self, other: impl Iterator<Item = u8>, radix: u32, m: usize) -> Self {
+        assert_eq!(radix, RADIX);
+        let mut y = vec![0; m];
+        digits::reduce_be_bytes(other, RADIX, &mut y);
+        digits::sub_mod(&mut self.0, &y, RADIX);
+        self
+    }
+}
+
+impl InspectableOps for PrintableAsciiNumeralString {
+    type Numeral = u8;
+
+    fn to_numerals(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl<CIPH: BlockCipher + BlockEncrypt + Clone> FF1<CIPH> {
+    /// Encrypts the given string of printable, non-space ASCII characters, returning a
+    /// string of the same length over the same characters.
+    ///
+    /// This FF1 instance must have radix 94. Returns an error if the string contains
+    /// any other character, or if the radix is not 94.
+    pub fn encrypt_printable(&self, tweak: &[u8], s: &str) -> Result<String, NumeralStringError> {
+        let x: PrintableAsciiNumeralString = s.parse()?;
+        self.encrypt(tweak, &x).map(|y| y.to_str())
+    }
+
+    /// Decrypts the given string of printable, non-space ASCII characters, as
+    /// [`FF1::encrypt_printable`].
+    pub fn decrypt_printable(&self, tweak: &[u8], s: &str) -> Result<String, NumeralStringError> {
+        let x: PrintableAsciiNumeralString = s.parse()?;
+        self.decrypt(tweak, &x).map(|y| y.to_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aes::Aes256;
+
+    use super::PrintableAsciiNumeralString;
+    use crate::ff1::{FlexibleNumeralString, InspectableOps, NumeralStringError, Operations, FF1};
+
+    #[test]
+    fn from_str() {
+        let ns: PrintableAsciiNumeralString = "!0A~".parse().unwrap();
+        assert_eq!(ns.to_numerals(), [0, 15, 32, 93]);
+        assert_eq!(ns.to_str(), "!0A~");
+        assert_eq!(ns.to_string(), "!0A~");
+
+        for (s, position, value) in [("ab c", 2, 32), ("\x7f", 0, 127), ("ok\u{e9}", 2, 0xe9)] {
+            assert_eq!(
+                s.parse::<PrintableAsciiNumeralString>().unwrap_err(),
+                NumeralStringError::InvalidNumeral {
+                    position,
+                    value,
+                    radix: 94,
+                },
+            );
+        }
+    }
+
+    #[test]
+    fn ops_match_flexible() {
+        let s = "!~Hello,World!{}#";
+        let other = [0xff, 0x01, 0x80, 0x7f, 0x00, 0x33, 0xc4, 0x5a, 0xff, 0x09];
+        let m = s.len();
+
+        let to_flexible = |s: &str| {
+            FlexibleNumeralString::from(s.bytes().map(|c| u16::from(c - b'!')).collect::<Vec<_>>())
+        };
+        let pns = || s.parse::<PrintableAsciiNumeralString>().unwrap();
+        let numerals = |ns: PrintableAsciiNumeralString| {
+            ns.to_numerals()
+                .iter()
+                .map(|&n| u16::from(n))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            Operations::to_be_bytes(&pns(), 94, 14),
+            Operations::to_be_bytes(&to_flexible(s), 94, 14),
+        );
+        assert_eq!(
+            numerals(pns().add_mod_exp(other.iter().copied(), 94, m)),
+            Vec::from(to_flexible(s).add_mod_exp(other.iter().copied(), 94, m)),
+        );
+        assert_eq!(
+            numerals(pns().sub_mod_exp(other.iter().copied(), 94, m)),
+            Vec::from(to_flexible(s).sub_mod_exp(other.iter().copied(), 94, m)),
+        );
+    }
+
+    #[test]
+    fn encrypt_printable() {
+        let ff = FF1::<Aes256>::new(&[0; 32], 94).unwrap();
+        let pt = "Token:{abc}~42";
+
+        let ct = ff.encrypt_printable(b"header", pt).unwrap();
+        assert_eq!(ct.len(), pt.len());
+        assert!(ct.bytes().all(|c| (b'!'..=b'~').contains(&c)));
+        assert_ne!(ct, pt);
+        assert_eq!(
+            ct,
+            ff.encrypt(
+                b"header",
+                &FlexibleNumeralString::from(
+                    pt.bytes().map(|c| u16::from(c - b'!')).collect::<Vec<_>>()
+                )
+            )
+            .unwrap()
+            .iter()
+            .map(|n| char::from(n as u8 + b'!'))
+            .collect::<String>(),
+        );
+        assert_eq!(ff.decrypt_printable(b"header", &ct).unwrap(), pt);
+
+        assert!(ff.encrypt_printable(&[], "with space").is_err());
+        assert_eq!(
+            FF1::<Aes256>::new(&[0; 32], 95)
+                .unwrap()
+                .encrypt_printable(&[], "abcdef")
+                .unwrap_err(),
+            NumeralStringError::InvalidForRadix(95),
+        );
+    }
+}
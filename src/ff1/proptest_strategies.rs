@@ -0,0 +1,129 @@
+//! [`proptest`](mod@proptest) strategies for generating FF1 instances and valid numeral strings.
+//!
+//! These let downstream crates write property tests over their own uses of FF1.
+
+use cipher::{BlockCipher, BlockEncrypt, KeyInit};
+use proptest::prelude::*;
+
+use super::{BinaryNumeralString, FlexibleNumeralString, Radix, FF1, MAX_RADIX, MIN_RADIX};
+
+/// The maximum number of numerals beyond the minimum length that the strategies
+/// generate.
+const MAX_EXTRA_LEN: usize = 64;
+
+/// Returns a strategy for numeral strings that are valid for FF1 with the given radix.
+///
+/// The numeral strings are between the minimum length for the radix and 64 numerals
+/// longer.
+///
+/// # Panics
+///
+/// Panics if the radix is not in [2..2^16].
+pub fn arb_flexible_numeral_string(radix: u32) -> impl Strategy<Value = FlexibleNumeralString> {
+    let min_len = Radix::from_u32(radix).expect("valid radix").min_len();
+    let max_numeral = (radix - 1) as u16;
+    prop::collection::vec(0..=max_numeral, min_len..=min_len + MAX_EXTRA_LEN)
+        .prop_map(FlexibleNumeralString::from)
+}
+
+/// Returns a strategy for binary numeral strings that are valid for FF1 with radix 2.
+pub fn arb_binary_numeral_string() -> impl Strategy<Value = BinaryNumeralString> {
+    // The minimum length for radix 2 is 20 numerals, which requires 3 bytes.
+    prop::collection::vec(any::<u8>(), 3..=3 + MAX_EXTRA_LEN / 8)
+        .prop_map(|bytes| BinaryNumeralString::from_bytes_le(&bytes))
+}
+
+/// Returns a strategy for an FF1 instance with a random key and radix, together with a
+/// numeral string that is valid for that radix.
+pub fn arb_ff1_and_input<CIPH>() -> impl Strategy<Value = (FF1<CIPH>, FlexibleNumeralString)>
+where
+    CIPH: BlockCipher + BlockEncrypt + Clone + KeyInit,
+{
+    (
+        prop::collection::vec(any::<u8>(), CIPH::key_size()),
+        MIN_RADIX..=MAX_RADIX,
+    )
+        .prop_flat_map(|(key, radix)| {
+            let ff = FF1::<CIPH>::new(&key, radix).expect("radix is in range");
+            (Just(ff), arb_flexible_numeral_string(radix))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use aes::Aes256;
+    use proptest::{
+        prelude::*,
+        strategy::ValueTree,
+        test_runner::{Config, TestRunner},
+    };
+
+    use super::{arb_binary_numeral_string, arb_ff1_and_input, arb_flexible_numeral_string};
+    use crate::ff1::{FlexibleNumeralString, FF1};
+
+    proptest! {
+        #![proptest_config(Config::with_cases(64))]
+
+        #[test]
+        fn round_trip(
+            (ff, pt) in arb_ff1_and_input::<Aes256>(),
+            tweak in prop::collection::vec(any::<u8>(), 0..32),
+        ) {
+            let ct = ff.encrypt(&tweak, &pt).unwrap();
+            let decrypted = ff.decrypt(&tweak, &ct).unwrap();
+            prop_assert_eq!(Vec::from(decrypted), Vec::from(pt));
+        }
+
+        #[test]
+        fn binary_round_trip(pt in arb_binary_numeral_string()) {
+            let ff = FF1::<Aes256>::new(&[0; 32], 2).unwrap();
+            let ct = ff.encrypt(&[], &pt).unwrap();
+            prop_assert_eq!(ff.decrypt(&[], &ct).unwrap().to_bytes_le(), pt.to_bytes_le());
+        }
+
+        #[test]
+        fn tweak_changes_ciphertext(
+            pt in arb_flexible_numeral_string(10),
+            t1 in prop::collection::vec(any::<u8>(), 0..32),
+            t2 in prop::collection::vec(any::<u8>(), 0..32),
+        ) {
+            prop_assume!(t1 != t2);
+            let ff = FF1::<Aes256>::new(&[0; 32], 10).unwrap();
+            // Distinct tweaks collide with probability at most 10^-6.
+            let c1 = Vec::from(ff.encrypt(&t1, &pt).unwrap());
+            let c2 = Vec::from(ff.encrypt(&t2, &pt).unwrap());
+            prop_assert_ne!(c1, c2);
+        }
+    }
+
+    #[test]
+    fn avalanche() {
+        let mut runner = TestRunner::deterministic();
+        let strategy = arb_ff1_and_input::<Aes256>();
+
+        let (mut changed, mut total) = (0.0, 0.0);
+        for i in 0..200 {
+            let (ff, pt) = strategy.new_tree(&mut runner).unwrap().current();
+            let radix = ff.radix.to_u32();
+
+            // Change one numeral to a different value.
+            let mut modified = pt.0.clone();
+            let j = i % modified.len();
+            modified[j] = ((u32::from(modified[j]) + 1) % radix) as u16;
+
+            let c1 = Vec::from(ff.encrypt(&[], &pt).unwrap());
+            let c2 = Vec::from(
+                ff.encrypt(&[], &FlexibleNumeralString::from(modified))
+                    .unwrap(),
+            );
+            let differing = c1.iter().zip(&c2).filter(|(a, b)| a != b).count();
+
+            // A random output numeral differs with probability 1 - 1/radix, so weight
+            // each run by that expectation.
+            changed += differing as f64;
+            total += c1.len() as f64 * (1.0 - 1.0 / f64::from(radix));
+        }
+
+        assert!(changed / total > 0.9, "{} / {}", changed, total);
+    }
+}
@@ -1,8 +1,13 @@
+use core::cmp::Ordering;
+
 use aes::Aes256;
 use num_integer::Integer;
 use proptest::prelude::*;
 
-use super::{BinaryNumeralString, FlexibleNumeralString, NumeralStringError, Radix, FF1};
+use super::{
+    BinaryNumeralString, FlexibleNumeralString, NumeralStringError, Radix, SliceableNumeralString,
+    FF1,
+};
 
 prop_compose! {
     fn valid_radix()(radix in 2u32..=(1 << 16)) -> (u32, u16, usize) {
@@ -71,6 +76,27 @@ proptest! {
         assert_eq!(Vec::from(pt), Vec::from(ns));
     }
 
+    #[test]
+    fn ord_consistent_with_eq(
+        (_, a) in flexible_ns(),
+        (_, b) in flexible_ns(),
+    ) {
+        assert_eq!(a == b, a.cmp(&b) == Ordering::Equal);
+        assert_eq!(a.cmp(&a), Ordering::Equal);
+    }
+
+    #[test]
+    fn split_many_concat_many(
+        (_, ns) in flexible_ns(),
+        k in 1usize..10,
+    ) {
+        let parts = ns.split_many(k);
+        assert_eq!(parts.len(), k);
+        let (min, max) = (ns.0.len() / k, (ns.0.len() + k - 1) / k);
+        assert!(parts.iter().all(|p| (min..=max).contains(&p.0.len())));
+        assert_eq!(FlexibleNumeralString::concat_many(parts), ns);
+    }
+
     #[test]
     fn binary_round_trip(
         ns in binary_ns(),
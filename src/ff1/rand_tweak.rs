@@ -0,0 +1,159 @@
+//! Random tweaks, for encryptions that do not reveal equal plaintexts.
+
+use alloc::vec::Vec;
+use core::ops::Deref;
+
+use cipher::{BlockCipher, BlockEncrypt};
+use rand_core::{CryptoRng, RngCore};
+
+use super::{NumeralString, NumeralStringError, FF1, MAX_TWEAK_LEN};
+
+/// A random tweak, generated by [`FF1::encrypt_with_rand_tweak`].
+///
+/// The tweak is not secret, but it must be stored alongside the ciphertext, as the
+/// ciphertext cannot be decrypted without it.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct RandTweak(Vec<u8>);
+
+impl RandTweak {
+    /// Generates a tweak of `len` random bytes.
+    pub fn generate<R: CryptoRng + RngCore>(rng: &mut R, len: usize) -> Self {
+        let mut tweak = alloc::vec![0; len];
+        rng.fill_bytes(&mut tweak);
+        RandTweak(tweak)
+    }
+
+    /// Returns the bytes of this tweak.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl Deref for RandTweak {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl AsRef<[u8]> for RandTweak {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl From<RandTweak> for Vec<u8> {
+    fn from(tweak: RandTweak) -> Self {
+        tweak.0
+    }
+}
+
+impl<CIPH: BlockCipher + BlockEncrypt + Clone> FF1<CIPH> {
+    /// Encrypts the given numeral string with a tweak of `tweak_bytes` random bytes,
+    /// and returns the tweak along with the ciphertext.
+    ///
+    /// FF1 is deterministic, so encrypting the same numeral string twice with the same
+    /// tweak reveals that the plaintexts are equal. With a fresh random tweak for each
+    /// encryption, equal plaintexts only produce equal ciphertexts if their tweaks
+    /// collide, so `tweak_bytes` should be large enough for that to be negligible; 16
+    /// bytes is a reasonable default. The caller must store the tweak to decrypt the
+    /// ciphertext with [`FF1::decrypt_with_rand_tweak`].
+    ///
+    /// Returns an error if `tweak_bytes` is longer than FF1 allows, or if the numeral
+    /// string is not in the required radix.
+    pub fn encrypt_with_rand_tweak<NS: NumeralString, R: CryptoRng + RngCore>(
+        &self,
+        rng: &mut R,
+        x: &NS,
+        tweak_bytes: usize,
+    ) -> Result<(RandTweak, NS), NumeralStringError> {
+        // Check the length before allocating the tweak.
+        if tweak_bytes > MAX_TWEAK_LEN {
+            return Err(NumeralStringError::TweakTooLong {
+                tweak_len: tweak_bytes,
+                max_len: MAX_TWEAK_LEN,
+            });
+        }
+        let tweak = RandTweak::generate(rng, tweak_bytes);
+        let y = self.encrypt(&tweak, x)?;
+        Ok((tweak, y))
+    }
+
+    /// Decrypts a numeral string that was encrypted with
+    /// [`FF1::encrypt_with_rand_tweak`], given the tweak that it returned.
+    ///
+    /// Returns an error if the numeral string is not in the required radix.
+    pub fn decrypt_with_rand_tweak<NS: NumeralString>(
+        &self,
+        tweak: &[u8],
+        x: &NS,
+    ) -> Result<NS, NumeralStringError> {
+        self.decrypt(tweak, x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aes::Aes256;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    use super::RandTweak;
+    use crate::ff1::{FlexibleNumeralString, NumeralStringError, FF1};
+
+    #[test]
+    fn round_trip() {
+        let ff = FF1::<Aes256>::new(&[0; 32], 10).unwrap();
+        let mut rng = StdRng::seed_from_u64(0);
+        let pt = FlexibleNumeralString::from_decimal_str("123456789").unwrap();
+
+        let (t1, ct1) = ff.encrypt_with_rand_tweak(&mut rng, &pt, 16).unwrap();
+        let (t2, ct2) = ff.encrypt_with_rand_tweak(&mut rng, &pt, 16).unwrap();
+        assert_eq!(t1.len(), 16);
+        assert_ne!(t1, t2);
+        assert_ne!(ct1.to_decimal_str(), ct2.to_decimal_str());
+
+        // The ciphertext is the encryption under the returned tweak.
+        assert_eq!(
+            ct1.to_decimal_str(),
+            ff.encrypt(&t1, &pt).unwrap().to_decimal_str()
+        );
+        for (t, ct) in [(t1, ct1), (t2, ct2)] {
+            assert_eq!(
+                ff.decrypt_with_rand_tweak(t.as_bytes(), &ct)
+                    .unwrap()
+                    .to_decimal_str(),
+                "123456789"
+            );
+        }
+
+        // The tweak is generated from the RNG.
+        let (t, _) = ff
+            .encrypt_with_rand_tweak(&mut StdRng::seed_from_u64(1), &pt, 8)
+            .unwrap();
+        assert_eq!(t, RandTweak::generate(&mut StdRng::seed_from_u64(1), 8));
+        assert_eq!(Vec::from(t.clone()), t.as_bytes());
+    }
+
+    #[test]
+    fn errors() {
+        let ff = FF1::<Aes256>::new(&[0; 32], 10).unwrap();
+        let mut rng = StdRng::seed_from_u64(0);
+        let pt = FlexibleNumeralString::from_decimal_str("123456789").unwrap();
+
+        #[cfg(target_pointer_width = "64")]
+        assert_eq!(
+            ff.encrypt_with_rand_tweak(&mut rng, &pt, 1 << 32)
+                .unwrap_err(),
+            NumeralStringError::TweakTooLong {
+                tweak_len: 1 << 32,
+                max_len: u32::MAX as usize,
+            },
+        );
+        assert_eq!(
+            ff.encrypt_with_rand_tweak(&mut rng, &FlexibleNumeralString::from(vec![10; 9]), 16)
+                .unwrap_err(),
+            NumeralStringError::InvalidForRadix(10),
+        );
+    }
+}
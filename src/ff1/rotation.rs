@@ -0,0 +1,229 @@
+//! Management of multiple FF1 keys, for rotating keys without downtime.
+
+use alloc::vec::Vec;
+use cipher::{BlockCipher, BlockEncrypt, KeyInit};
+
+use super::{
+    InvalidKeyError, InvalidRadix, KeyRotationError, NumeralString, NumeralStringError, Radix, FF1,
+};
+
+/// The version of a key in a [`KeyRotation`].
+pub type KeyVersion = u32;
+
+/// A set of FF1 instances with the same radix, each identified by a [`KeyVersion`].
+///
+/// New values are always encrypted with the current key, which is the key with the
+/// highest version. The version is returned alongside the ciphertext, rather than
+/// being encoded in it, so that the ciphertext keeps the same length as the plaintext.
+/// Values encrypted with older keys can be decrypted until those keys are removed with
+/// [`KeyRotation::remove_key`].
+pub struct KeyRotation<CIPH: BlockCipher + BlockEncrypt> {
+    radix: Radix,
+    // Sorted by increasing version.
+    keys: Vec<(KeyVersion, FF1<CIPH>)>,
+}
+
+impl<CIPH: BlockCipher + BlockEncrypt> KeyRotation<CIPH> {
+    /// Creates an empty set of keys for the given radix.
+    ///
+    /// Returns an error if the given radix is not in [2..2^16].
+    pub fn new(radix: u32) -> Result<Self, InvalidRadix> {
+        Ok(KeyRotation {
+            radix: Radix::from_u32(radix)?,
+            keys: Vec::new(),
+        })
+    }
+
+    /// Returns the version of the current key, or `None` if there are no keys.
+    pub fn current_version(&self) -> Option<KeyVersion> {
+        self.keys.last().map(|(version, _)| *version)
+    }
+
+    /// Removes the key with the given version, returning whether it was present.
+    ///
+    /// Values encrypted with the key can no longer be decrypted.
+    pub fn remove_key(&mut self, version: KeyVersion) -> bool {
+        let len = self.keys.len();
+        self.keys.retain(|(v, _)| *v != version);
+        self.keys.len() != len
+    }
+
+    fn find(&self, version: KeyVersion) -> Option<&FF1<CIPH>> {
+        self.keys
+            .binary_search_by_key(&version, |(v, _)| *v)
+            .ok()
+            .map(|i| &self.keys[i].1)
+    }
+}
+
+impl<CIPH: BlockCipher + BlockEncrypt + Clone + KeyInit> KeyRotation<CIPH> {
+    /// Adds a key with the given version.
+    ///
+    /// If `version` is higher than every existing version, the key becomes the current
+    /// key. Returns an error if the key has the wrong length for the cipher, or if a
+    /// key with this version already exists.
+    pub fn add_key(&mut self, version: KeyVersion, key: &[u8]) -> Result<(), InvalidKeyError> {
+        let i = match self.keys.binary_search_by_key(&version, |(v, _)| *v) {
+            Ok(_) => return Err(InvalidKeyError::DuplicateVersion(version)),
+            Err(i) => i,
+        };
+        let ciph =
+            CIPH::new_from_slice(key).map_err(|_| InvalidKeyError::InvalidLength(key.len()))?;
+        self.keys
+            .insert(i, (version, FF1::from_parts(ciph, self.radix.clone(), 10)));
+        Ok(())
+    }
+}
+
+impl<CIPH: BlockCipher + BlockEncrypt + Clone> KeyRotation<CIPH> {
+    /// Encrypts the given numeral string with the current key, returning the ciphertext
+    /// and the version of the key used.
+    ///
+    /// Returns an error if there are no keys, or if the numeral string is not in the
+    /// required radix.
+    pub fn encrypt<NS: NumeralString>(
+        &self,
+        tweak: &[u8],
+        x: &NS,
+    ) -> Result<(NS, KeyVersion), KeyRotationError> {
+        let (version, ff) = self.keys.last().ok_or(KeyRotationError::NoKeys)?;
+        Ok((ff.encrypt(tweak, x)?, *version))
+    }
+
+    /// Encrypts the given numeral string with every key, returning each ciphertext with
+    /// the version of the key used, in order of increasing version.
+    ///
+    /// This allows a value to be written under both the old and new keys while a key
+    /// is being rotated. Returns an empty `Vec` if there are no keys, or an error if
+    /// the numeral string is not in the required radix.
+    pub fn encrypt_all<NS: NumeralString>(
+        &self,
+        tweak: &[u8],
+        x: &NS,
+    ) -> Result<Vec<(KeyVersion, NS)>, NumeralStringError> {
+        self.keys
+            .iter()
+            .map(|(version, ff)| Ok((*version, ff.encrypt(tweak, x)?)))
+            .collect()
+    }
+
+    /// Decrypts the given numeral string with the key that has the given version.
+    ///
+    /// Returns [`KeyRotationError::KeyNotFound`] if there is no key with that version,
+    /// or an error if the numeral string is not in the required radix.
+    pub fn decrypt<NS: NumeralString>(
+        &self,
+        tweak: &[u8],
+        x: &NS,
+        version: KeyVersion,
+    ) -> Result<NS, KeyRotationError> {
+        let ff = self
+            .find(version)
+            .ok_or(KeyRotationError::KeyNotFound(version))?;
+        Ok(ff.decrypt(tweak, x)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aes::Aes256;
+
+    use super::KeyRotation;
+    use crate::ff1::{
+        FlexibleNumeralString, InvalidKeyError, KeyRotationError, NumeralStringError, FF1,
+    };
+
+    fn pt() -> FlexibleNumeralString {
+        FlexibleNumeralString::from(vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9])
+    }
+
+    #[test]
+    fn rotation() {
+        let mut keys = KeyRotation::<Aes256>::new(10).unwrap();
+        assert_eq!(
+            keys.encrypt(&[], &pt()).unwrap_err(),
+            KeyRotationError::NoKeys
+        );
+
+        keys.add_key(1, &[1; 32]).unwrap();
+        let (ct1, v1) = keys.encrypt(&[], &pt()).unwrap();
+        assert_eq!(v1, 1);
+        let ff1 = FF1::<Aes256>::new(&[1; 32], 10).unwrap();
+        assert_eq!(Vec::from(ff1.decrypt(&[], &ct1).unwrap()), Vec::from(pt()));
+
+        keys.add_key(2, &[2; 32]).unwrap();
+        assert_eq!(keys.current_version(), Some(2));
+        let (ct2, v2) = keys.encrypt(&[], &pt()).unwrap();
+        assert_eq!(v2, 2);
+
+        // Both versions can be decrypted.
+        assert_eq!(
+            Vec::from(keys.decrypt(&[], &ct1, 1).unwrap()),
+            Vec::from(pt())
+        );
+        assert_eq!(
+            Vec::from(keys.decrypt(&[], &ct2, 2).unwrap()),
+            Vec::from(pt())
+        );
+
+        assert!(keys.remove_key(1));
+        assert!(!keys.remove_key(1));
+        assert_eq!(
+            keys.decrypt(&[], &ct1, 1).unwrap_err(),
+            KeyRotationError::KeyNotFound(1)
+        );
+        assert_eq!(
+            Vec::from(keys.decrypt(&[], &ct2, 2).unwrap()),
+            Vec::from(pt())
+        );
+    }
+
+    #[test]
+    fn encrypt_all() {
+        let mut keys = KeyRotation::<Aes256>::new(10).unwrap();
+        assert!(keys.encrypt_all(&[], &pt()).unwrap().is_empty());
+
+        keys.add_key(2, &[2; 32]).unwrap();
+        keys.add_key(1, &[1; 32]).unwrap();
+        let cts = keys.encrypt_all(b"tweak", &pt()).unwrap();
+        assert_eq!(cts.iter().map(|(v, _)| *v).collect::<Vec<_>>(), [1, 2]);
+
+        for (version, ct) in &cts {
+            assert_eq!(
+                Vec::from(keys.decrypt(b"tweak", ct, *version).unwrap()),
+                Vec::from(pt())
+            );
+        }
+
+        // Decrypting with the other key gives the wrong plaintext.
+        assert_ne!(
+            Vec::from(keys.decrypt(b"tweak", &cts[0].1, 2).unwrap()),
+            Vec::from(pt())
+        );
+
+        assert_eq!(
+            keys.encrypt_all(&[], &FlexibleNumeralString::from(vec![10; 6]))
+                .unwrap_err(),
+            NumeralStringError::InvalidForRadix(10)
+        );
+    }
+
+    #[test]
+    fn add_key_errors() {
+        let mut keys = KeyRotation::<Aes256>::new(10).unwrap();
+        assert_eq!(
+            keys.add_key(1, &[0; 16]),
+            Err(InvalidKeyError::InvalidLength(16))
+        );
+        keys.add_key(3, &[3; 32]).unwrap();
+        assert_eq!(
+            keys.add_key(3, &[4; 32]),
+            Err(InvalidKeyError::DuplicateVersion(3))
+        );
+
+        // Adding an older version does not change the current key.
+        keys.add_key(2, &[2; 32]).unwrap();
+        assert_eq!(keys.current_version(), Some(3));
+        assert!(KeyRotation::<Aes256>::new(1).is_err());
+    }
+}
@@ -0,0 +1,128 @@
+//! FF1 with fewer than the standard 10 Feistel rounds, for testing and cryptanalysis.
+
+use cipher::{BlockCipher, BlockEncrypt, KeyInit};
+
+use super::{InvalidRadix, NumeralString, NumeralStringError, FF1};
+
+/// The number of Feistel rounds that NIST SP 800-38G mandates for FF1.
+const STANDARD_ROUNDS: u8 = 10;
+
+/// An FF1 instance with fewer than 10 Feistel rounds.
+///
+/// **This is not FF1, and is not secure.** Reducing the number of rounds makes the
+/// cipher vulnerable to known attacks on Feistel networks. This type exists so that
+/// tests and cryptanalysis tools can exercise round-reduced variants, and is only
+/// available with the `test-utils` feature flag, which must never be enabled in
+/// production builds.
+///
+/// `FF1RoundReduced` is a distinct type from [`FF1`], so that it cannot be passed to
+/// code that expects a standard FF1 instance.
+pub struct FF1RoundReduced<CIPH: BlockCipher + BlockEncrypt> {
+    ff: FF1<CIPH>,
+}
+
+impl<CIPH: BlockCipher + BlockEncrypt + Clone + KeyInit> FF1RoundReduced<CIPH> {
+    /// Creates a new round-reduced FF1 object for the given key, radix and number of
+    /// Feistel rounds.
+    ///
+    /// Returns an error if the given radix is not in [2..2^16].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `rounds` is 0 or at least 10. Use [`FF1`] for 10 or more rounds.
+    pub fn new(key: &[u8], radix: u32, rounds: u8) -> Result<Self, InvalidRadix> {
+        assert!(rounds > 0, "FF1RoundReduced needs at least one round");
+        assert_reduced(rounds);
+        Ok(FF1RoundReduced {
+            ff: FF1::new_with_faistel_rounds(key, radix, rounds)?,
+        })
+    }
+}
+
+impl<CIPH: BlockCipher + BlockEncrypt> FF1RoundReduced<CIPH> {
+    /// Returns the number of Feistel rounds.
+    pub fn rounds(&self) -> u8 {
+        self.ff.faistel_rounds
+    }
+}
+
+impl<CIPH: BlockCipher + BlockEncrypt + Clone> FF1RoundReduced<CIPH> {
+    /// Encrypts the given numeral string with the reduced number of rounds.
+    ///
+    /// Returns an error if the numeral string is not in the required radix.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of rounds is at least 10.
+    pub fn encrypt<NS: NumeralString>(
+        &self,
+        tweak: &[u8],
+        x: &NS,
+    ) -> Result<NS, NumeralStringError> {
+        assert_reduced(self.ff.faistel_rounds);
+        self.ff.encrypt(tweak, x)
+    }
+
+    /// Decrypts the given numeral string with the reduced number of rounds.
+    ///
+    /// Returns an error if the numeral string is not in the required radix.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the number of rounds is at least 10.
+    pub fn decrypt<NS: NumeralString>(
+        &self,
+        tweak: &[u8],
+        x: &NS,
+    ) -> Result<NS, NumeralStringError> {
+        assert_reduced(self.ff.faistel_rounds);
+        self.ff.decrypt(tweak, x)
+    }
+}
+
+fn assert_reduced(rounds: u8) {
+    assert!(
+        rounds < STANDARD_ROUNDS,
+        "FF1RoundReduced must have fewer than {} rounds, got {}",
+        STANDARD_ROUNDS,
+        rounds,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use aes::Aes256;
+
+    use super::FF1RoundReduced;
+    use crate::ff1::{FlexibleNumeralString, FF1};
+
+    #[test]
+    fn test_round_reduced_still_roundtrips() {
+        let pt = vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9];
+        for rounds in 1..=9 {
+            let ff = FF1RoundReduced::<Aes256>::new(&[0; 32], 10, rounds).unwrap();
+            assert_eq!(ff.rounds(), rounds);
+            let ct = ff
+                .encrypt(b"tweak", &FlexibleNumeralString::from(pt.clone()))
+                .unwrap();
+            assert_eq!(Vec::from(ff.decrypt(b"tweak", &ct).unwrap()), pt);
+        }
+    }
+
+    #[test]
+    fn matches_ff1() {
+        let pt = FlexibleNumeralString::from(vec![0, 1, 2, 3, 4, 5, 6, 7, 8, 9]);
+        let reduced = FF1RoundReduced::<Aes256>::new(&[0; 32], 10, 8).unwrap();
+        let ff = FF1::<Aes256>::new_with_faistel_rounds(&[0; 32], 10, 8).unwrap();
+        assert_eq!(
+            Vec::from(reduced.encrypt(&[], &pt).unwrap()),
+            Vec::from(ff.encrypt(&[], &pt).unwrap()),
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "fewer than 10 rounds")]
+    fn standard_rounds() {
+        let _ = FF1RoundReduced::<Aes256>::new(&[0; 32], 10, 10);
+    }
+}
@@ -0,0 +1,202 @@
+//! Encryption of integers as fixed-length numeral strings.
+
+use alloc::vec;
+
+use cipher::{BlockCipher, BlockEncrypt};
+
+use super::{FlexibleNumeralString, NumeralStringError, FF1};
+
+impl<CIPH: BlockCipher + BlockEncrypt + Clone> FF1<CIPH> {
+    /// Encrypts `value` as a numeral string of `len` numerals in the given radix,
+    /// padded with leading zeros, and returns the integer that the ciphertext
+    /// represents.
+    ///
+    /// The ciphertext is in `[0, radix^len)`. Returns
+    /// [`NumeralStringError::TooShort`] if `value` needs more than `len` numerals,
+    /// [`NumeralStringError::TooLong`] if `radix^len` is greater than `2^64`,
+    /// and [`NumeralStringError::InvalidForRadix`] if `radix` is not this instance's
+    /// radix.
+    pub fn encrypt_u64(
+        &self,
+        tweak: &[u8],
+        value: u64,
+        radix: u32,
+        len: usize,
+    ) -> Result<u64, NumeralStringError> {
+        self.transform_integer(value, radix, len, u64::MAX, |x| self.encrypt(tweak, x))
+    }
+
+    /// Decrypts `value` as a numeral string of `len` numerals in the given radix, as
+    /// [`FF1::encrypt_u64`].
+    pub fn decrypt_u64(
+        &self,
+        tweak: &[u8],
+        value: u64,
+        radix: u32,
+        len: usize,
+    ) -> Result<u64, NumeralStringError> {
+        self.transform_integer(value, radix, len, u64::MAX, |x| self.decrypt(tweak, x))
+    }
+
+    /// Encrypts `value` as a numeral string of `len` numerals in the given radix, as
+    /// [`FF1::encrypt_u64`].
+    ///
+    /// Returns [`NumeralStringError::TooLong`] if `radix^len` is greater than `2^32`.
+    pub fn encrypt_u32(
+        &self,
+        tweak: &[u8],
+        value: u32,
+        radix: u32,
+        len: usize,
+    ) -> Result<u32, NumeralStringError> {
+        self.transform_integer(value.into(), radix, len, u32::MAX.into(), |x| {
+            self.encrypt(tweak, x)
+        })
+        .map(|y| y as u32)
+    }
+
+    /// Decrypts `value` as a numeral string of `len` numerals in the given radix, as
+    /// [`FF1::encrypt_u32`].
+    pub fn decrypt_u32(
+        &self,
+        tweak: &[u8],
+        value: u32,
+        radix: u32,
+        len: usize,
+    ) -> Result<u32, NumeralStringError> {
+        self.transform_integer(value.into(), radix, len, u32::MAX.into(), |x| {
+            self.decrypt(tweak, x)
+        })
+        .map(|y| y as u32)
+    }
+
+    /// Converts `value` to `len` numerals, applies `f`, and converts the result back.
+    ///
+    /// `max` is the largest value of the integer type, which every numeral string of
+    /// length `len` must fit in.
+    fn transform_integer(
+        &self,
+        value: u64,
+        radix: u32,
+        len: usize,
+        max: u64,
+        f: impl FnOnce(&FlexibleNumeralString) -> Result<FlexibleNumeralString, NumeralStringError>,
+    ) -> Result<u64, NumeralStringError> {
+        if radix != self.radix.to_u32() {
+            return Err(NumeralStringError::InvalidForRadix(self.radix.to_u32()));
+        }
+
+        // The largest length whose values all fit in `max`.
+        let radix = u64::from(radix);
+        let mut max_len = 0;
+        let mut domain = 1u128;
+        while domain * u128::from(radix) <= u128::from(max) + 1 {
+            domain *= u128::from(radix);
+            max_len += 1;
+        }
+        if len > max_len {
+            return Err(NumeralStringError::TooLong {
+                ns_len: len,
+                max_len,
+            });
+        }
+
+        let mut numerals = vec![0; len];
+        let mut rest = value;
+        for n in numerals.iter_mut().rev() {
+            *n = (rest % radix) as u16;
+            rest /= radix;
+        }
+        if rest != 0 {
+            let mut min_len = len;
+            while rest != 0 {
+                rest /= radix;
+                min_len += 1;
+            }
+            return Err(NumeralStringError::TooShort {
+                ns_len: len,
+                min_len,
+            });
+        }
+
+        let y = f(&FlexibleNumeralString::from(numerals))?;
+        Ok(y.iter().fold(0, |acc, n| acc * radix + u64::from(n)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aes::Aes256;
+
+    use crate::ff1::{FlexibleNumeralString, NumeralStringError, FF1};
+
+    #[test]
+    fn round_trip() {
+        let ff = FF1::<Aes256>::new(&[0; 32], 10).unwrap();
+
+        let ct = ff.encrypt_u64(b"id", 123456789, 10, 9).unwrap();
+        assert!(ct < 1_000_000_000);
+        assert_eq!(
+            FlexibleNumeralString::from_decimal_str(&format!("{:09}", ct)).unwrap(),
+            ff.encrypt(
+                b"id",
+                &FlexibleNumeralString::from_decimal_str("123456789").unwrap()
+            )
+            .unwrap(),
+        );
+        assert_eq!(ff.decrypt_u64(b"id", ct, 10, 9).unwrap(), 123456789);
+
+        // Leading zeros are significant.
+        let ct = ff.encrypt_u64(b"id", 42, 10, 9).unwrap();
+        assert_ne!(ct, ff.encrypt_u64(b"id", 42, 10, 10).unwrap());
+        assert_eq!(ff.decrypt_u64(b"id", ct, 10, 9).unwrap(), 42);
+
+        let ct = ff.encrypt_u32(b"zip", 94107, 10, 6).unwrap();
+        assert!(ct < 1_000_000);
+        assert_eq!(u64::from(ct), ff.encrypt_u64(b"zip", 94107, 10, 6).unwrap());
+        assert_eq!(ff.decrypt_u32(b"zip", ct, 10, 6).unwrap(), 94107);
+
+        // The whole domain of the integer type can be used.
+        let ff = FF1::<Aes256>::new(&[0; 32], 2).unwrap();
+        let ct = ff.encrypt_u64(&[], u64::MAX, 2, 64).unwrap();
+        assert_eq!(ff.decrypt_u64(&[], ct, 2, 64).unwrap(), u64::MAX);
+    }
+
+    #[test]
+    fn errors() {
+        let ff = FF1::<Aes256>::new(&[0; 32], 10).unwrap();
+        assert_eq!(
+            ff.encrypt_u64(&[], 1234567, 10, 6).unwrap_err(),
+            NumeralStringError::TooShort {
+                ns_len: 6,
+                min_len: 7,
+            },
+        );
+        assert_eq!(
+            ff.encrypt_u64(&[], 0, 10, 20).unwrap_err(),
+            NumeralStringError::TooLong {
+                ns_len: 20,
+                max_len: 19,
+            },
+        );
+        assert_eq!(
+            ff.encrypt_u32(&[], 0, 10, 10).unwrap_err(),
+            NumeralStringError::TooLong {
+                ns_len: 10,
+                max_len: 9,
+            },
+        );
+        assert_eq!(
+            ff.encrypt_u64(&[], 0, 16, 6).unwrap_err(),
+            NumeralStringError::InvalidForRadix(10),
+        );
+        // The FF1 minimum length still applies.
+        assert_eq!(
+            ff.encrypt_u64(&[], 0, 10, 5).unwrap_err(),
+            NumeralStringError::TooShort {
+                ns_len: 5,
+                min_len: 6,
+            },
+        );
+    }
+}
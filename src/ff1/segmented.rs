@@ -0,0 +1,249 @@
+//! Numeral strings for structured identifiers made up of several segments.
+
+use core::fmt;
+
+use alloc::{string::String, vec::Vec};
+use cipher::{BlockCipher, BlockEncrypt};
+
+use super::{FlexibleNumeralString, FormatError, NumeralString, NumeralStringError, FF1};
+
+/// The template character that stands for an encrypted decimal digit.
+const DIGIT_PLACEHOLDER: char = '#';
+
+/// One segment of a [`SegmentedNumeralString`].
+#[cfg_attr(test, derive(Debug))]
+pub enum Segment {
+    /// Characters that are passed through unencrypted, such as separators.
+    Literal(String),
+    /// Numerals that are encrypted, together with their radix.
+    Digits(FlexibleNumeralString, u32),
+}
+
+/// A structured identifier, such as a phone number or formatted account number, made
+/// up of encrypted and literal segments.
+///
+/// [`FF1::encrypt_segmented`] encrypts the numerals of all [`Segment::Digits`] segments
+/// together as a single numeral string, and then splits the result back into segments
+/// of the original lengths. This means that individual segments may be shorter than the
+/// minimum length for the radix, as long as the segments together are long enough.
+#[cfg_attr(test, derive(Debug))]
+pub struct SegmentedNumeralString(Vec<Segment>);
+
+impl SegmentedNumeralString {
+    /// Creates a numeral string from the given segments.
+    pub fn new(segments: Vec<Segment>) -> Self {
+        SegmentedNumeralString(segments)
+    }
+
+    /// Parses `value` according to `template`.
+    ///
+    /// Each `#` in the template stands for a decimal digit in `value` that will be
+    /// encrypted, and every other character must appear verbatim in `value`. For
+    /// example, the template `"###-##-####"` matches US Social Security numbers.
+    pub fn from_format(template: &str, value: &str) -> Result<Self, FormatError> {
+        let (template_len, value_len) = (template.chars().count(), value.chars().count());
+        if template_len != value_len {
+            return Err(FormatError::LengthMismatch {
+                template_len,
+                value_len,
+            });
+        }
+
+        let mut segments = Vec::new();
+        for (position, (t, c)) in template.chars().zip(value.chars()).enumerate() {
+            if t == DIGIT_PLACEHOLDER {
+                let d = c
+                    .to_digit(10)
+                    .ok_or(FormatError::InvalidDigit { position, found: c })?;
+                match segments.last_mut() {
+                    Some(Segment::Digits(ns, _)) => ns.0.push(d as u16),
+                    _ => segments.push(Segment::Digits(
+                        FlexibleNumeralString::from(alloc::vec![d as u16]),
+                        10,
+                    )),
+                }
+            } else if t == c {
+                match segments.last_mut() {
+                    Some(Segment::Literal(s)) => s.push(c),
+                    _ => segments.push(Segment::Literal(c.into())),
+                }
+            } else {
+                return Err(FormatError::LiteralMismatch {
+                    position,
+                    expected: t,
+                    found: c,
+                });
+            }
+        }
+
+        Ok(SegmentedNumeralString(segments))
+    }
+
+    /// Returns the segments of this numeral string.
+    pub fn segments(&self) -> &[Segment] {
+        &self.0
+    }
+}
+
+impl From<Vec<Segment>> for SegmentedNumeralString {
+    fn from(segments: Vec<Segment>) -> Self {
+        SegmentedNumeralString::new(segments)
+    }
+}
+
+/// Formats the numeral string with digits in radixes up to 36 written as `0-9a-z`.
+///
+/// Returns an error if a segment has a radix greater than 36.
+impl fmt::Display for SegmentedNumeralString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for segment in &self.0 {
+            match segment {
+                Segment::Literal(s) => f.write_str(s)?,
+                Segment::Digits(ns, radix) => {
+                    for &n in &ns.0 {
+                        let c = char::from_digit(u32::from(n), *radix).ok_or(fmt::Error)?;
+                        write!(f, "{}", c)?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<CIPH: BlockCipher + BlockEncrypt + Clone> FF1<CIPH> {
+    /// Encrypts the digit segments of the given numeral string, copying the literal
+    /// segments verbatim.
+    ///
+    /// Returns an error if any digit segment has a different radix than this FF1
+    /// instance, or if the digit segments together are not a valid numeral string.
+    pub fn encrypt_segmented(
+        &self,
+        tweak: &[u8],
+        x: &SegmentedNumeralString,
+    ) -> Result<SegmentedNumeralString, NumeralStringError> {
+        self.transform_segmented(x, |ns| self.encrypt(tweak, ns))
+    }
+
+    /// Decrypts the digit segments of the given numeral string, copying the literal
+    /// segments verbatim.
+    ///
+    /// Returns an error if any digit segment has a different radix than this FF1
+    /// instance, or if the digit segments together are not a valid numeral string.
+    pub fn decrypt_segmented(
+        &self,
+        tweak: &[u8],
+        x: &SegmentedNumeralString,
+    ) -> Result<SegmentedNumeralString, NumeralStringError> {
+        self.transform_segmented(x, |ns| self.decrypt(tweak, ns))
+    }
+
+    fn transform_segmented(
+        &self,
+        x: &SegmentedNumeralString,
+        f: impl FnOnce(&FlexibleNumeralString) -> Result<FlexibleNumeralString, NumeralStringError>,
+    ) -> Result<SegmentedNumeralString, NumeralStringError> {
+        let radix = self.radix.to_u32();
+        let mut numerals = Vec::new();
+        for segment in &x.0 {
+            if let Segment::Digits(ns, segment_radix) = segment {
+                if *segment_radix != radix {
+                    return Err(NumeralStringError::InvalidForRadix(radix));
+                }
+                numerals.extend_from_slice(&ns.0);
+            }
+        }
+
+        let transformed = f(&FlexibleNumeralString::from(numerals))?;
+
+        let mut rest = &transformed.0[..];
+        Ok(SegmentedNumeralString(
+            x.0.iter()
+                .map(|segment| match segment {
+                    Segment::Literal(s) => Segment::Literal(s.clone()),
+                    Segment::Digits(ns, segment_radix) => {
+                        let (digits, tail) = rest.split_at(ns.numeral_count());
+                        rest = tail;
+                        Segment::Digits(
+                            FlexibleNumeralString::from(digits.to_vec()),
+                            *segment_radix,
+                        )
+                    }
+                })
+                .collect(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aes::Aes256;
+
+    use super::{Segment, SegmentedNumeralString};
+    use crate::ff1::{FlexibleNumeralString, FormatError, NumeralStringError, FF1};
+
+    #[test]
+    fn from_format() {
+        let ns = SegmentedNumeralString::from_format("###-##-####", "123-45-6789").unwrap();
+        assert_eq!(ns.segments().len(), 5);
+        assert_eq!(ns.to_string(), "123-45-6789");
+
+        assert_eq!(
+            SegmentedNumeralString::from_format("###-##", "123-4").unwrap_err(),
+            FormatError::LengthMismatch {
+                template_len: 6,
+                value_len: 5,
+            },
+        );
+        assert_eq!(
+            SegmentedNumeralString::from_format("###-##", "123/45").unwrap_err(),
+            FormatError::LiteralMismatch {
+                position: 3,
+                expected: '-',
+                found: '/',
+            },
+        );
+        assert_eq!(
+            SegmentedNumeralString::from_format("###-##", "12a-45").unwrap_err(),
+            FormatError::InvalidDigit {
+                position: 2,
+                found: 'a',
+            },
+        );
+    }
+
+    #[test]
+    fn segmented_round_trip() {
+        let ff = FF1::<Aes256>::new(&[0; 32], 10).unwrap();
+        for (template, value) in [
+            ("###-##-####", "123-45-6789"),
+            ("+#-###-###-####", "+1-800-555-1234"),
+            ("(###) ###-####", "(555) 010-9999"),
+            ("DE####################", "DE89370400440532013000"),
+        ] {
+            let pt = SegmentedNumeralString::from_format(template, value).unwrap();
+            let ct = ff.encrypt_segmented(b"tweak", &pt).unwrap();
+            let ct_str = ct.to_string();
+            assert_ne!(ct_str, value);
+            // The ciphertext still matches the template.
+            assert!(SegmentedNumeralString::from_format(template, &ct_str).is_ok());
+
+            let decrypted = ff.decrypt_segmented(b"tweak", &ct).unwrap();
+            assert_eq!(decrypted.to_string(), value);
+        }
+    }
+
+    #[test]
+    fn radix_mismatch() {
+        let ff = FF1::<Aes256>::new(&[0; 32], 10).unwrap();
+        let ns = SegmentedNumeralString::new(vec![
+            Segment::Digits(FlexibleNumeralString::from(vec![1, 2, 3]), 10),
+            Segment::Literal("-".into()),
+            Segment::Digits(FlexibleNumeralString::from(vec![0xa, 0xb, 0xc]), 16),
+        ]);
+        assert_eq!(
+            ff.encrypt_segmented(&[], &ns).unwrap_err(),
+            NumeralStringError::InvalidForRadix(10),
+        );
+    }
+}
@@ -0,0 +1,251 @@
+//! `serde` support for numeral strings.
+//!
+//! - [`FlexibleNumeralString`] is serialized as a sequence of its numerals.
+//! - [`BinaryNumeralString`] is serialized as a hex string, as
+//!   [`BinaryNumeralString::to_hex`].
+//! - [`WithRadix`] additionally records the radix, and checks on deserialization that
+//!   every numeral is valid for it.
+//! - [`EncryptedValue`] is serialized as its ciphertext.
+//! - [`OrderedTokenSet`] is serialized as a sequence of its tokens in insertion order.
+
+use core::{convert::TryFrom, fmt};
+
+use alloc::vec::Vec;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+
+use super::{
+    BinaryNumeralString, EncryptedValue, FlexibleNumeralString, NumeralString, NumeralStringError,
+    OrderedTokenSet,
+};
+
+impl Serialize for FlexibleNumeralString {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for FlexibleNumeralString {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Vec::deserialize(deserializer).map(FlexibleNumeralString)
+    }
+}
+
+impl Serialize for BinaryNumeralString {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_hex())
+    }
+}
+
+impl<'de> Deserialize<'de> for BinaryNumeralString {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct HexVisitor;
+
+        impl<'de> de::Visitor<'de> for HexVisitor {
+            type Value = BinaryNumeralString;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a hex string with an even number of digits")
+            }
+
+            fn visit_str<E: de::Error>(self, s: &str) -> Result<Self::Value, E> {
+                BinaryNumeralString::from_hex(s)
+                    .map_err(|_| E::invalid_value(de::Unexpected::Str(s), &self))
+            }
+        }
+
+        deserializer.deserialize_str(HexVisitor)
+    }
+}
+
+impl<T, NS: Serialize> Serialize for EncryptedValue<T, NS> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        self.ciphertext().serialize(serializer)
+    }
+}
+
+impl<'de, T, NS: Deserialize<'de>> Deserialize<'de> for EncryptedValue<T, NS> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        NS::deserialize(deserializer).map(EncryptedValue::from_ciphertext)
+    }
+}
+
+impl<NS: NumeralString + Ord + Serialize> Serialize for OrderedTokenSet<NS> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(self.iter())
+    }
+}
+
+impl<'de, NS: NumeralString + Ord + Deserialize<'de>> Deserialize<'de> for OrderedTokenSet<NS> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let tokens = Vec::<NS>::deserialize(deserializer)?;
+        let mut set = OrderedTokenSet::new();
+        for token in tokens {
+            // Duplicates would shift the indices of every later token.
+            if set.insert(token) != set.len() as u64 - 1 {
+                return Err(de::Error::custom("duplicate token"));
+            }
+        }
+        Ok(set)
+    }
+}
+
+/// A numeral string together with its radix.
+///
+/// This is serialized as a struct with `radix` and `numerals` fields. Deserialization
+/// fails if the numerals are not valid for the radix.
+#[derive(Serialize, Deserialize)]
+#[serde(try_from = "RawWithRadix<NS>")]
+#[serde(bound(deserialize = "NS: NumeralString + Deserialize<'de>"))]
+pub struct WithRadix<NS> {
+    radix: u32,
+    numerals: NS,
+}
+
+#[derive(Deserialize)]
+struct RawWithRadix<NS> {
+    radix: u32,
+    numerals: NS,
+}
+
+impl<NS: NumeralString> WithRadix<NS> {
+    /// Pairs the given numeral string with its radix.
+    ///
+    /// Returns an error if the numeral string is not valid for the radix.
+    pub fn new(radix: u32, numerals: NS) -> Result<Self, NumeralStringError> {
+        if numerals.is_valid(radix) {
+            Ok(WithRadix { radix, numerals })
+        } else {
+            Err(NumeralStringError::InvalidForRadix(radix))
+        }
+    }
+
+    /// Returns the radix of the numeral string.
+    pub fn radix(&self) -> u32 {
+        self.radix
+    }
+
+    /// Returns the numeral string.
+    pub fn numerals(&self) -> &NS {
+        &self.numerals
+    }
+
+    /// Returns the numeral string, discarding the radix.
+    pub fn into_numerals(self) -> NS {
+        self.numerals
+    }
+}
+
+impl<NS: NumeralString> TryFrom<RawWithRadix<NS>> for WithRadix<NS> {
+    type Error = NumeralStringError;
+
+    fn try_from(raw: RawWithRadix<NS>) -> Result<Self, Self::Error> {
+        WithRadix::new(raw.radix, raw.numerals)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aes::Aes256;
+
+    use super::WithRadix;
+    use crate::ff1::{
+        BinaryNumeralString, EncryptedValue, FlexibleNumeralString, OrderedTokenSet, FF1,
+    };
+
+    #[test]
+    fn flexible() {
+        let ns = FlexibleNumeralString::from_decimal_str("123456789").unwrap();
+        let json = serde_json::to_string(&ns).unwrap();
+        assert_eq!(json, "[1,2,3,4,5,6,7,8,9]");
+        let ns: FlexibleNumeralString = serde_json::from_str(&json).unwrap();
+        assert_eq!(ns.to_decimal_str(), "123456789");
+    }
+
+    #[test]
+    fn binary() {
+        let ns = BinaryNumeralString::from_hex("deadbeef").unwrap();
+        let json = serde_json::to_string(&ns).unwrap();
+        assert_eq!(json, format!("\"{}\"", ns.to_hex()));
+        assert_eq!(json, "\"deadbeef\"");
+        let ns: BinaryNumeralString = serde_json::from_str(&json).unwrap();
+        assert_eq!(ns.to_bytes_be(), [0xde, 0xad, 0xbe, 0xef]);
+
+        assert!(serde_json::from_str::<BinaryNumeralString>("\"abc\"").is_err());
+        assert!(serde_json::from_str::<BinaryNumeralString>("\"abcg\"").is_err());
+    }
+
+    #[test]
+    fn with_radix() {
+        let ns = FlexibleNumeralString::from(vec![1, 2, 3]);
+        let json = serde_json::to_string(&WithRadix::new(10, ns).unwrap()).unwrap();
+        assert_eq!(json, r#"{"radix":10,"numerals":[1,2,3]}"#);
+
+        let ns: WithRadix<FlexibleNumeralString> = serde_json::from_str(&json).unwrap();
+        assert_eq!(ns.radix(), 10);
+        assert_eq!(Vec::from(ns.into_numerals()), vec![1, 2, 3]);
+
+        let err = serde_json::from_str::<WithRadix<FlexibleNumeralString>>(
+            r#"{"radix":10,"numerals":[1,10,3]}"#,
+        )
+        .err()
+        .unwrap();
+        assert!(err.to_string().contains("invalid for radix 10"));
+
+        assert!(WithRadix::new(2, FlexibleNumeralString::from(vec![0, 2])).is_err());
+    }
+
+    #[test]
+    fn encrypted_value() {
+        let ff = FF1::<Aes256>::new(&[0; 32], 10).unwrap();
+        let pt = FlexibleNumeralString::from_decimal_str("123456789").unwrap();
+        let value = EncryptedValue::<Vec<u16>, _>::encrypt(&pt, &ff, b"ssn").unwrap();
+
+        let json = serde_json::to_string(&value).unwrap();
+        assert_eq!(json, serde_json::to_string(value.ciphertext()).unwrap());
+        let value: EncryptedValue<Vec<u16>, FlexibleNumeralString> =
+            serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            value.decrypt(&ff, b"ssn").unwrap(),
+            [1, 2, 3, 4, 5, 6, 7, 8, 9]
+        );
+
+        assert!(
+            serde_json::from_str::<EncryptedValue<Vec<u16>, FlexibleNumeralString>>("\"12a\"")
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn encrypted_value_radix_16() {
+        // Ciphertext numerals of 10 and above must survive a round trip.
+        let ff = FF1::<Aes256>::new(&[0; 32], 16).unwrap();
+        let pt = FlexibleNumeralString::from(vec![0xd, 0xe, 0xa, 0xd, 0xb, 0xe, 0xe, 0xf]);
+        let value = EncryptedValue::<Vec<u16>, _>::encrypt(&pt, &ff, b"id").unwrap();
+        assert!(value.ciphertext().0.iter().any(|&n| n >= 10));
+
+        let json = serde_json::to_string(&value).unwrap();
+        let value: EncryptedValue<Vec<u16>, FlexibleNumeralString> =
+            serde_json::from_str(&json).unwrap();
+        assert_eq!(value.decrypt(&ff, b"id").unwrap(), Vec::from(pt));
+    }
+
+    #[test]
+    fn ordered_token_set() {
+        let mut set = OrderedTokenSet::new();
+        set.insert(FlexibleNumeralString::from(vec![3, 2, 1]));
+        set.insert(FlexibleNumeralString::from(vec![1, 2, 3]));
+
+        let json = serde_json::to_string(&set).unwrap();
+        assert_eq!(json, "[[3,2,1],[1,2,3]]");
+        let set: OrderedTokenSet<FlexibleNumeralString> = serde_json::from_str(&json).unwrap();
+        assert_eq!(
+            set.get_index(&FlexibleNumeralString::from(vec![1, 2, 3])),
+            Some(1)
+        );
+
+        let err = serde_json::from_str::<OrderedTokenSet<FlexibleNumeralString>>("[[1],[2],[1]]")
+            .err()
+            .unwrap();
+        assert!(err.to_string().contains("duplicate token"));
+    }
+}
@@ -0,0 +1,102 @@
+//! Encryption of many numeral strings with the same tweak.
+
+use core::cell::RefCell;
+
+use cipher::{BlockCipher, BlockEncrypt};
+
+use super::{check_tweak, NumeralString, NumeralStringError, Prf, FF1};
+
+/// An [`FF1`] instance and tweak, for encrypting many numeral strings with the same
+/// tweak.
+///
+/// The PRF input `P || T` includes the numeral string length `n` before the tweak, so
+/// the PRF state after the tweak cannot be shared between lengths. The context caches
+/// the state for the length of the most recent numeral string, and reuses it while
+/// subsequent numeral strings have the same length.
+///
+/// Created by [`FF1::session`].
+pub struct EncryptionContext<'a, CIPH: BlockCipher + BlockEncrypt> {
+    ff: &'a FF1<CIPH>,
+    tweak: &'a [u8],
+    prf: RefCell<Option<(usize, Prf<CIPH>)>>,
+}
+
+impl<CIPH: BlockCipher + BlockEncrypt + Clone> FF1<CIPH> {
+    /// Returns a context for encrypting and decrypting with the given tweak.
+    pub fn session<'a>(&'a self, tweak: &'a [u8]) -> EncryptionContext<'a, CIPH> {
+        EncryptionContext {
+            ff: self,
+            tweak,
+            prf: RefCell::new(None),
+        }
+    }
+}
+
+impl<'a, CIPH: BlockCipher + BlockEncrypt + Clone> EncryptionContext<'a, CIPH> {
+    /// Returns the tweak that this context encrypts with.
+    pub fn tweak(&self) -> &'a [u8] {
+        self.tweak
+    }
+
+    /// Encrypts the given numeral string with this context's tweak.
+    ///
+    /// Returns an error if the tweak is too long, or if the numeral string is not in the
+    /// required radix.
+    pub fn encrypt<NS: NumeralString>(&self, x: &NS) -> Result<NS, NumeralStringError> {
+        check_tweak(self.tweak)?;
+        self.ff.check_ns(x)?;
+        let mut cache = self.prf.borrow_mut();
+        let prf = self
+            .ff
+            .cached_prf_prefix(&mut cache, x.numeral_count(), self.tweak);
+        let (x_a, x_b) = self.ff.encrypt_with_prefix(prf, x);
+        Ok(NS::concat(x_a, x_b))
+    }
+
+    /// Decrypts the given numeral string with this context's tweak.
+    ///
+    /// Returns an error if the tweak is too long, or if the numeral string is not in the
+    /// required radix.
+    pub fn decrypt<NS: NumeralString>(&self, x: &NS) -> Result<NS, NumeralStringError> {
+        check_tweak(self.tweak)?;
+        self.ff.check_ns(x)?;
+        let mut cache = self.prf.borrow_mut();
+        let prf = self
+            .ff
+            .cached_prf_prefix(&mut cache, x.numeral_count(), self.tweak);
+        let (x_a, x_b) = self.ff.decrypt_with_prefix(prf, x);
+        Ok(NS::concat(x_a, x_b))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aes::Aes256;
+
+    use crate::ff1::{FlexibleNumeralString, NumeralStringError, FF1};
+
+    #[test]
+    fn matches_ff1() {
+        let ff = FF1::<Aes256>::new(&[0; 32], 10).unwrap();
+        let session = ff.session(b"tweak");
+        assert_eq!(session.tweak(), b"tweak");
+
+        // Alternate lengths, so that the cached state is both reused and replaced.
+        for s in ["012345", "987654", "0123456789", "9876543210", "012345"] {
+            let pt = FlexibleNumeralString::from_decimal_str(s).unwrap();
+            let ct = session.encrypt(&pt).unwrap();
+            assert_eq!(
+                ct.to_decimal_str(),
+                ff.encrypt(b"tweak", &pt).unwrap().to_decimal_str()
+            );
+            assert_eq!(session.decrypt(&ct).unwrap().to_decimal_str(), s);
+        }
+
+        assert_eq!(
+            session
+                .encrypt(&FlexibleNumeralString::from(vec![10; 6]))
+                .unwrap_err(),
+            NumeralStringError::InvalidForRadix(10),
+        );
+    }
+}
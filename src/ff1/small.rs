@@ -0,0 +1,169 @@
+//! A numeral string that stores short inputs inline, backed by `smallvec`.
+
+use core::ops::Range;
+
+use smallvec::SmallVec;
+
+use super::{
+    digits, InspectableOps, NumeralString, NumeralStringMut, Operations, SliceableNumeralString,
+};
+
+/// The number of numerals that a [`SmallNumeralString`] stores inline.
+const INLINE_NUMERALS: usize = 24;
+
+/// The number of bytes that the byte representation of a [`SmallNumeralString`] half
+/// stores inline, which covers any half of an inline numeral string.
+const INLINE_BYTES: usize = 2 * INLINE_NUMERALS + 12;
+
+type Numerals = SmallVec<[u16; INLINE_NUMERALS]>;
+
+/// A numeral string that stores up to 24 numerals inline, and spills longer numeral
+/// strings to the heap.
+///
+/// Supports radixes in [2..2^16], like
+/// [`FlexibleNumeralString`](super::FlexibleNumeralString). Payment card numbers, SSNs
+/// and phone numbers are short enough that encrypting and decrypting them never invokes
+/// the global allocator.
+#[cfg_attr(test, derive(Debug))]
+pub struct SmallNumeralString(Numerals);
+
+impl SmallNumeralString {
+    /// Creates a `SmallNumeralString` from the given numerals.
+    pub fn from_slice(numerals: &[u16]) -> Self {
+        SmallNumeralString(SmallVec::from_slice(numerals))
+    }
+
+    /// Returns the numerals of this numeral string.
+    pub fn as_slice(&self) -> &[u16] {
+        &self.0
+    }
+
+    /// Returns `true` if the numerals are stored on the heap.
+    pub fn spilled(&self) -> bool {
+        self.0.spilled()
+    }
+}
+
+impl From<SmallNumeralString> for alloc::vec::Vec<u16> {
+    fn from(ns: SmallNumeralString) -> Self {
+        ns.0.into_vec()
+    }
+}
+
+impl NumeralString for SmallNumeralString {
+    type Ops = Self;
+
+    fn is_valid(&self, radix: u32) -> bool {
+        self.0.iter().all(|n| (u32::from(*n) < radix))
+    }
+
+    fn numeral_count(&self) -> usize {
+        self.0.len()
+    }
+
+    fn split(&self) -> (Self, Self) {
+        let (front, back) = self.0.split_at(self.0.len() / 2);
+        (Self::from_slice(front), Self::from_slice(back))
+    }
+
+    fn concat(mut a: Self, b: Self) -> Self {
+        a.0.extend_from_slice(&b.0);
+        a
+    }
+
+    fn reversed(&self) -> Self {
+        SmallNumeralString(self.0.iter().rev().copied().collect())
+    }
+}
+
+impl NumeralStringMut for SmallNumeralString {
+    fn replace_with(&mut self, a: Self, b: Self) {
+        self.0.clear();
+        self.0.extend_from_slice(&a.0);
+        self.0.extend_from_slice(&b.0);
+    }
+}
+
+impl SliceableNumeralString for SmallNumeralString {
+    fn slice(&self, range: Range<usize>) -> Self {
+        Self::from_slice(&self.0[range])
+    }
+
+    fn splice(&mut self, range: Range<usize>, replacement: Self) {
+        self.0[range].copy_from_slice(&replacement.0);
+    }
+}
+
+impl Operations for SmallNumeralString {
+    type Bytes = SmallVec<[u8; INLINE_BYTES]>;
+
+    fn numeral_count(&self) -> usize {
+        self.0.len()
+    }
+
+    fn to_be_bytes(&self, radix: u32, b: usize) -> Self::Bytes {
+        let mut bytes = SmallVec::from_elem(0, b);
+        digits::to_be_bytes(&self.0, radix, &mut bytes);
+        bytes
+    }
+
+    fn add_mod_exp(mut self, other: impl Iterator<Item = u8>, radix: u32, m: usize) -> Self {
+        assert_eq!(self.0.len(), m);
+        let mut y: Numerals = SmallVec::from_elem(0, m);
+        digits::reduce_be_bytes(other, radix, &mut y);
+        digits::add_mod(&mut self.0, &y, radix);
+        self
+    }
+
+    fn sub_mod_exp(mut self, other: impl Iterator<Item = u8>, radix: u32, m: usize) -> Self {
+        assert_eq!(self.0.len(), m);
+        let mut y: Numerals = SmallVec::from_elem(0, m);
+        digits::reduce_be_bytes(other, radix, &mut y);
+        digits::sub_mod(&mut self.0, &y, radix);
+        self
+    }
+}
+
+impl InspectableOps for SmallNumeralString {
+    type Numeral = u16;
+
+    fn to_numerals(&self) -> &[u16] {
+        &self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aes::{Aes128, Aes192, Aes256};
+
+    use super::SmallNumeralString;
+    use crate::ff1::{
+        test_vectors::{self, AesType},
+        FF1,
+    };
+
+    #[test]
+    fn small() {
+        for tv in test_vectors::get() {
+            let pt = SmallNumeralString::from_slice(&tv.pt);
+            let ct = SmallNumeralString::from_slice(&tv.ct);
+            assert_eq!(pt.spilled(), tv.pt.len() > 24);
+            let (ct, pt) = match tv.aes {
+                AesType::AES128 => {
+                    let ff = FF1::<Aes128>::new(&tv.key, tv.radix).unwrap();
+                    (ff.encrypt(&tv.tweak, &pt), ff.decrypt(&tv.tweak, &ct))
+                }
+                AesType::AES192 => {
+                    let ff = FF1::<Aes192>::new(&tv.key, tv.radix).unwrap();
+                    (ff.encrypt(&tv.tweak, &pt), ff.decrypt(&tv.tweak, &ct))
+                }
+                AesType::AES256 => {
+                    let ff = FF1::<Aes256>::new(&tv.key, tv.radix).unwrap();
+                    (ff.encrypt(&tv.tweak, &pt), ff.decrypt(&tv.tweak, &ct))
+                }
+            };
+            assert_eq!(ct.unwrap().as_slice(), &tv.ct[..]);
+            assert_eq!(pt.unwrap().as_slice(), &tv.pt[..]);
+        }
+    }
+}
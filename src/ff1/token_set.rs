@@ -0,0 +1,117 @@
+//! A set of issued FF1 tokens, for checking whether a value has already been tokenized.
+
+use alloc::{collections::BTreeMap, vec::Vec};
+
+use super::NumeralString;
+
+/// A set of tokens (FF1 ciphertexts) that records the order in which they were
+/// inserted.
+///
+/// FF1 is deterministic for a given key and tweak, so encrypting a value and checking
+/// whether the token is in the set answers "has this value been tokenized before?"
+/// without storing the plaintext. Membership tests take O(log n) time.
+///
+/// Each token is assigned the index at which it was first inserted, starting from 0.
+/// With the `serde` feature flag, the set is serialized as a sequence of its tokens in
+/// insertion order.
+pub struct OrderedTokenSet<NS> {
+    tokens: BTreeMap<NS, u64>,
+}
+
+impl<NS: NumeralString + Ord> Default for OrderedTokenSet<NS> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<NS: NumeralString + Ord> OrderedTokenSet<NS> {
+    /// Creates an empty set.
+    pub fn new() -> Self {
+        OrderedTokenSet {
+            tokens: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the number of tokens in the set.
+    pub fn len(&self) -> usize {
+        self.tokens.len()
+    }
+
+    /// Returns `true` if the set contains no tokens.
+    pub fn is_empty(&self) -> bool {
+        self.tokens.is_empty()
+    }
+
+    /// Inserts a token, returning its insertion index.
+    ///
+    /// If the token is already in the set, it keeps the index at which it was first
+    /// inserted, and that index is returned.
+    pub fn insert(&mut self, token: NS) -> u64 {
+        let next = self.tokens.len() as u64;
+        *self.tokens.entry(token).or_insert(next)
+    }
+
+    /// Returns `true` if the set contains the given token.
+    pub fn contains(&self, token: &NS) -> bool {
+        self.tokens.contains_key(token)
+    }
+
+    /// Returns the insertion index of the given token, or `None` if it is not in the
+    /// set.
+    pub fn get_index(&self, token: &NS) -> Option<u64> {
+        self.tokens.get(token).copied()
+    }
+
+    /// Returns an iterator over the tokens in insertion order.
+    ///
+    /// This sorts the tokens by index, so takes O(n log n) time.
+    pub fn iter(&self) -> impl Iterator<Item = &NS> {
+        let mut tokens: Vec<_> = self.tokens.iter().collect();
+        tokens.sort_unstable_by_key(|(_, i)| **i);
+        tokens.into_iter().map(|(token, _)| token)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aes::Aes256;
+
+    use super::OrderedTokenSet;
+    use crate::ff1::{FlexibleNumeralString, FF1};
+
+    #[test]
+    fn insertion_order() {
+        let ff = FF1::<Aes256>::new(&[0; 32], 10).unwrap();
+        let tokenize =
+            |x: &str| ff.encrypt(&[], &FlexibleNumeralString::from_decimal_str(x).unwrap());
+
+        let mut set = OrderedTokenSet::new();
+        assert!(set.is_empty());
+        for (i, pan) in ["4111111111111111", "5500005555555559", "4012888888881881"]
+            .iter()
+            .enumerate()
+        {
+            assert_eq!(set.insert(tokenize(pan).unwrap()), i as u64);
+        }
+
+        // Re-inserting a token keeps its original index.
+        assert_eq!(set.insert(tokenize("5500005555555559").unwrap()), 1);
+        assert_eq!(set.len(), 3);
+
+        let token = tokenize("4012888888881881").unwrap();
+        assert!(set.contains(&token));
+        assert_eq!(set.get_index(&token), Some(2));
+        let other = tokenize("378282246310005").unwrap();
+        assert!(!set.contains(&other));
+        assert_eq!(set.get_index(&other), None);
+
+        let tokens: Vec<_> = set.iter().map(|t| t.to_decimal_str()).collect();
+        assert_eq!(
+            tokens,
+            ["4111111111111111", "5500005555555559", "4012888888881881"]
+                .iter()
+                .map(|pan| tokenize(pan).unwrap().to_decimal_str())
+                .collect::<Vec<_>>(),
+        );
+    }
+}
@@ -0,0 +1,135 @@
+//! Non-blocking FF1 operations for the `tokio` runtime.
+
+use core::{future::Future, ops::Deref};
+use std::{panic, sync::Arc, vec::Vec};
+
+use cipher::{BlockCipher, BlockEncrypt};
+use tokio::task::{self, JoinHandle};
+
+use super::{NumeralString, NumeralStringError, FF1};
+
+/// An [`FF1`] instance that can be shared between tasks, with an async interface.
+///
+/// Each operation runs [`FF1::encrypt`] or [`FF1::decrypt`] on tokio's blocking
+/// thread pool via [`tokio::task::spawn_blocking`], so that the calling task's executor
+/// thread is never blocked. The operation is started immediately, and must be started
+/// from within a tokio runtime.
+///
+/// `SharedFF1` dereferences to `FF1`, so the blocking API is available as well.
+pub struct SharedFF1<CIPH: BlockCipher + BlockEncrypt>(Arc<FF1<CIPH>>);
+
+impl<CIPH: BlockCipher + BlockEncrypt> SharedFF1<CIPH> {
+    /// Wraps the given FF1 instance so it can be shared between tasks.
+    pub fn new(ff: FF1<CIPH>) -> Self {
+        SharedFF1(Arc::new(ff))
+    }
+}
+
+impl<CIPH: BlockCipher + BlockEncrypt> Clone for SharedFF1<CIPH> {
+    fn clone(&self) -> Self {
+        SharedFF1(self.0.clone())
+    }
+}
+
+impl<CIPH: BlockCipher + BlockEncrypt> From<FF1<CIPH>> for SharedFF1<CIPH> {
+    fn from(ff: FF1<CIPH>) -> Self {
+        SharedFF1::new(ff)
+    }
+}
+
+impl<CIPH: BlockCipher + BlockEncrypt> Deref for SharedFF1<CIPH> {
+    type Target = FF1<CIPH>;
+
+    fn deref(&self) -> &FF1<CIPH> {
+        &self.0
+    }
+}
+
+impl<CIPH> SharedFF1<CIPH>
+where
+    CIPH: BlockCipher + BlockEncrypt + Clone + Send + Sync + 'static,
+{
+    /// Encrypts the given numeral string without blocking the calling task.
+    ///
+    /// Returns an error if the numeral string is not in the required radix.
+    pub fn encrypt_async<NS: NumeralString + Send + 'static>(
+        &self,
+        tweak: &[u8],
+        x: NS,
+    ) -> impl Future<Output = Result<NS, NumeralStringError>> + Send + 'static {
+        join(self.spawn(tweak, x, |ff, tweak, x| ff.encrypt(tweak, x)))
+    }
+
+    /// Decrypts the given numeral string without blocking the calling task.
+    ///
+    /// Returns an error if the numeral string is not in the required radix.
+    pub fn decrypt_async<NS: NumeralString + Send + 'static>(
+        &self,
+        tweak: &[u8],
+        x: NS,
+    ) -> impl Future<Output = Result<NS, NumeralStringError>> + Send + 'static {
+        join(self.spawn(tweak, x, |ff, tweak, x| ff.decrypt(tweak, x)))
+    }
+
+    /// Encrypts each of the given numeral strings with the same tweak, as separate
+    /// blocking tasks.
+    ///
+    /// Returns an error if any numeral string is not in the required radix.
+    pub fn encrypt_batch_async<NS: NumeralString + Send + 'static>(
+        &self,
+        tweak: &[u8],
+        inputs: Vec<NS>,
+    ) -> impl Future<Output = Result<Vec<NS>, NumeralStringError>> + Send + 'static {
+        join_all(
+            inputs
+                .into_iter()
+                .map(|x| self.spawn(tweak, x, |ff, tweak, x| ff.encrypt(tweak, x)))
+                .collect(),
+        )
+    }
+
+    /// Decrypts each of the given numeral strings with the same tweak, as separate
+    /// blocking tasks.
+    ///
+    /// Returns an error if any numeral string is not in the required radix.
+    pub fn decrypt_batch_async<NS: NumeralString + Send + 'static>(
+        &self,
+        tweak: &[u8],
+        inputs: Vec<NS>,
+    ) -> impl Future<Output = Result<Vec<NS>, NumeralStringError>> + Send + 'static {
+        join_all(
+            inputs
+                .into_iter()
+                .map(|x| self.spawn(tweak, x, |ff, tweak, x| ff.decrypt(tweak, x)))
+                .collect(),
+        )
+    }
+
+    fn spawn<NS, F>(&self, tweak: &[u8], x: NS, f: F) -> JoinHandle<Result<NS, NumeralStringError>>
+    where
+        NS: NumeralString + Send + 'static,
+        F: FnOnce(&FF1<CIPH>, &[u8], &NS) -> Result<NS, NumeralStringError> + Send + 'static,
+    {
+        let ff = self.0.clone();
+        let tweak = tweak.to_vec();
+        task::spawn_blocking(move || f(&ff, &tweak, &x))
+    }
+}
+
+/// Waits for a blocking task, propagating any panic that occurred within it.
+async fn join<T>(handle: JoinHandle<T>) -> T {
+    match handle.await {
+        Ok(res) => res,
+        Err(e) => panic::resume_unwind(e.into_panic()),
+    }
+}
+
+async fn join_all<NS>(
+    handles: Vec<JoinHandle<Result<NS, NumeralStringError>>>,
+) -> Result<Vec<NS>, NumeralStringError> {
+    let mut outputs = Vec::with_capacity(handles.len());
+    for handle in handles {
+        outputs.push(join(handle).await?);
+    }
+    Ok(outputs)
+}
@@ -0,0 +1,126 @@
+//! The last few numerals of a token, for display.
+
+use alloc::{string::String, vec::Vec};
+
+use super::{FlexibleNumeralString, InspectableOps, NumeralString};
+
+/// The last `k` numerals of a ciphertext, such as the last four digits of a PAN
+/// token, along with the length of the full ciphertext.
+///
+/// A truncated token cannot be decrypted, so a store of truncated tokens reveals
+/// nothing about the plaintexts even to a holder of the key.
+///
+/// The suffix of a token is not the suffix of its plaintext, as FF1 changes every
+/// numeral. To display the real last four digits of a PAN, leave them in plaintext
+/// with [`FF1::encrypt_masked`](super::FF1::encrypt_masked) instead.
+#[derive(PartialEq, Eq, Hash)]
+#[cfg_attr(any(test, feature = "proptest"), derive(Debug))]
+pub struct TruncatedToken {
+    suffix: FlexibleNumeralString,
+    full_len: usize,
+    suffix_start: usize,
+}
+
+impl TruncatedToken {
+    /// Keeps the last `k` numerals of `ns`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ns` has fewer than `k` numerals.
+    pub fn from_ns<NS>(ns: NS, k: usize) -> Self
+    where
+        NS: NumeralString,
+        NS::Ops: InspectableOps,
+    {
+        let full_len = ns.numeral_count();
+        assert!(
+            k <= full_len,
+            "cannot keep {} numerals of a numeral string of length {}",
+            k,
+            full_len
+        );
+        let suffix_start = full_len - k;
+
+        let (a, b) = ns.split();
+        let suffix = a
+            .to_numerals()
+            .iter()
+            .chain(b.to_numerals())
+            .skip(suffix_start)
+            .map(|&n| n.into())
+            .collect::<Vec<u16>>();
+        TruncatedToken {
+            suffix: suffix.into(),
+            full_len,
+            suffix_start,
+        }
+    }
+
+    /// Returns the numerals that were kept.
+    pub fn suffix(&self) -> &FlexibleNumeralString {
+        &self.suffix
+    }
+
+    /// Returns the number of numerals in the full token.
+    pub fn full_len(&self) -> usize {
+        self.full_len
+    }
+
+    /// Returns the position in the full token of the first numeral that was kept.
+    pub fn suffix_start(&self) -> usize {
+        self.suffix_start
+    }
+
+    /// Returns the numerals that were kept, using `'0'..='9'` followed by `'a'..='z'`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `radix` is not in the range 2 to 36, or if the numerals are not valid
+    /// for `radix`.
+    pub fn to_display_string(&self, radix: u32) -> String {
+        self.suffix.to_string_radix(radix)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aes::Aes256;
+
+    use super::TruncatedToken;
+    use crate::ff1::{DecimalNumeralString, FlexibleNumeralString, FF1};
+
+    #[test]
+    fn last_four() {
+        let ff = FF1::<Aes256>::new(&[0; 32], 10).unwrap();
+        let pan = FlexibleNumeralString::from_decimal_str("4111111111111111").unwrap();
+        let token = ff.encrypt(b"pan", &pan).unwrap();
+        let digits = token.to_decimal_str();
+
+        let truncated = TruncatedToken::from_ns(token, 4);
+        assert_eq!(truncated.to_display_string(10), digits[12..]);
+        assert_eq!(truncated.suffix().to_decimal_str(), digits[12..]);
+        assert_eq!(truncated.full_len(), 16);
+        assert_eq!(truncated.suffix_start(), 12);
+    }
+
+    #[test]
+    fn bounds() {
+        let ns = || DecimalNumeralString::try_from("1234567").unwrap();
+        assert_eq!(
+            TruncatedToken::from_ns(ns(), 7).to_display_string(10),
+            "1234567"
+        );
+        let empty = TruncatedToken::from_ns(ns(), 0);
+        assert_eq!(empty.to_display_string(10), "");
+        assert_eq!(empty.suffix_start(), 7);
+
+        let hex = TruncatedToken::from_ns(FlexibleNumeralString::from(vec![1, 10, 15]), 2);
+        assert_eq!(hex.to_display_string(16), "af");
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot keep 8 numerals of a numeral string of length 7")]
+    fn too_long() {
+        TruncatedToken::from_ns(FlexibleNumeralString::from(vec![0; 7]), 8);
+    }
+}
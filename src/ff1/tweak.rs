@@ -0,0 +1,350 @@
+//! Derivation of per-record tweaks from record metadata.
+
+use alloc::vec::Vec;
+use cipher::{BlockCipher, BlockEncrypt};
+
+use super::{NumeralString, NumeralStringError, FF1};
+
+/// The metadata identifying where a value is stored.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TweakContext<'a> {
+    /// The kind of record, such as a table name.
+    pub entity_type: &'a str,
+    /// The field of the record, such as a column name.
+    pub field_name: &'a str,
+    /// The identifier of the record.
+    pub record_id: &'a [u8],
+}
+
+/// A method for deriving an FF1 tweak from a [`TweakContext`].
+pub trait TweakDeriver {
+    /// Returns the tweak for values stored at `context`.
+    ///
+    /// Implementations should return distinct tweaks for distinct contexts.
+    fn derive(&self, context: &TweakContext<'_>) -> Vec<u8>;
+}
+
+/// A [`TweakDeriver`] that derives 32-byte tweaks using HKDF-SHA256.
+///
+/// Each field of the context is length-prefixed before being used as the HKDF input
+/// keying material, so that contexts with the same concatenated fields (such as
+/// `"ab" || "c"` and `"a" || "bc"`) still produce different tweaks.
+#[cfg(feature = "hkdf-sha256")]
+#[derive(Clone, Debug)]
+pub struct HkdfTweakDeriver {
+    salt: Vec<u8>,
+}
+
+#[cfg(feature = "hkdf-sha256")]
+impl HkdfTweakDeriver {
+    /// The length in bytes of the derived tweaks.
+    pub const TWEAK_LEN: usize = 32;
+
+    /// Creates a deriver using the given application-specific salt.
+    pub fn new(salt: &[u8]) -> Self {
+        HkdfTweakDeriver {
+            salt: salt.to_vec(),
+        }
+    }
+}
+
+#[cfg(feature = "hkdf-sha256")]
+impl TweakDeriver for HkdfTweakDeriver {
+    fn derive(&self, context: &TweakContext<'_>) -> Vec<u8> {
+        let mut ikm = Vec::new();
+        for field in [
+            context.entity_type.as_bytes(),
+            context.field_name.as_bytes(),
+            context.record_id,
+        ] {
+            ikm.extend_from_slice(&(field.len() as u64).to_be_bytes());
+            ikm.extend_from_slice(field);
+        }
+
+        let mut tweak = [0; Self::TWEAK_LEN];
+        hkdf::Hkdf::<sha2::Sha256>::new(Some(&self.salt), &ikm)
+            .expand(b"fpe ff1 tweak", &mut tweak)
+            .expect("TWEAK_LEN is a valid length for HKDF-SHA256");
+        tweak.to_vec()
+    }
+}
+
+/// A builder for tweaks made of typed components, such as a table name, a column name
+/// and a tenant ID.
+///
+/// Each component is encoded as a one-byte type tag, its length in bytes as a
+/// big-endian `u64`, and then its bytes, with integers in big-endian order. The
+/// encoding is canonical, and distinct sequences of components always produce
+/// distinct tweaks: `add_str("ab").add_str("c")` differs from
+/// `add_str("a").add_str("bc")`, and `add_u32(1)` differs from
+/// `add_bytes(&[0, 0, 0, 1])`.
+///
+/// ```
+/// use fpe::ff1::TweakBuilder;
+///
+/// let tweak = TweakBuilder::new()
+///     .add_str("users")
+///     .add_str("ssn")
+///     .add_u64(42)
+///     .build();
+/// # let _ = tweak;
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct TweakBuilder {
+    tweak: Vec<u8>,
+}
+
+impl TweakBuilder {
+    const TAG_BYTES: u8 = 0;
+    const TAG_STR: u8 = 1;
+    const TAG_U32: u8 = 2;
+    const TAG_U64: u8 = 3;
+    const TAG_U128: u8 = 4;
+    #[cfg(feature = "uuid")]
+    const TAG_UUID: u8 = 5;
+
+    /// Creates a builder with no components.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn add(mut self, tag: u8, bytes: &[u8]) -> Self {
+        self.tweak.push(tag);
+        self.tweak
+            .extend_from_slice(&(bytes.len() as u64).to_be_bytes());
+        self.tweak.extend_from_slice(bytes);
+        self
+    }
+
+    /// Appends a byte string component.
+    pub fn add_bytes(self, bytes: &[u8]) -> Self {
+        self.add(Self::TAG_BYTES, bytes)
+    }
+
+    /// Appends a UTF-8 string component.
+    pub fn add_str(self, s: &str) -> Self {
+        self.add(Self::TAG_STR, s.as_bytes())
+    }
+
+    /// Appends a `u32` component.
+    pub fn add_u32(self, n: u32) -> Self {
+        self.add(Self::TAG_U32, &n.to_be_bytes())
+    }
+
+    /// Appends a `u64` component.
+    pub fn add_u64(self, n: u64) -> Self {
+        self.add(Self::TAG_U64, &n.to_be_bytes())
+    }
+
+    /// Appends a `u128` component.
+    pub fn add_u128(self, n: u128) -> Self {
+        self.add(Self::TAG_U128, &n.to_be_bytes())
+    }
+
+    /// Appends a UUID component.
+    #[cfg(feature = "uuid")]
+    pub fn add_uuid(self, uuid: uuid::Uuid) -> Self {
+        self.add(Self::TAG_UUID, uuid.as_bytes())
+    }
+
+    /// Returns the encoded tweak.
+    pub fn build(self) -> Vec<u8> {
+        self.tweak
+    }
+}
+
+/// An [`FF1`] instance that derives the tweak for each operation from a
+/// [`TweakContext`].
+pub struct TweakedFF1<CIPH: BlockCipher + BlockEncrypt, D: TweakDeriver> {
+    ff: FF1<CIPH>,
+    deriver: D,
+}
+
+impl<CIPH: BlockCipher + BlockEncrypt, D: TweakDeriver> TweakedFF1<CIPH, D> {
+    /// Wraps the given FF1 instance, deriving tweaks with `deriver`.
+    pub fn new(ff: FF1<CIPH>, deriver: D) -> Self {
+        TweakedFF1 { ff, deriver }
+    }
+
+    /// Returns the wrapped FF1 instance.
+    pub fn inner(&self) -> &FF1<CIPH> {
+        &self.ff
+    }
+}
+
+impl<CIPH: BlockCipher + BlockEncrypt + Clone, D: TweakDeriver> TweakedFF1<CIPH, D> {
+    /// Encrypts the given numeral string, using the tweak derived from `context`.
+    ///
+    /// Returns an error if the numeral string is not in the required radix.
+    pub fn encrypt_with_context<NS: NumeralString>(
+        &self,
+        context: &TweakContext<'_>,
+        x: &NS,
+    ) -> Result<NS, NumeralStringError> {
+        self.ff.encrypt(&self.deriver.derive(context), x)
+    }
+
+    /// Decrypts the given numeral string, using the tweak derived from `context`.
+    ///
+    /// Returns an error if the numeral string is not in the required radix.
+    pub fn decrypt_with_context<NS: NumeralString>(
+        &self,
+        context: &TweakContext<'_>,
+        x: &NS,
+    ) -> Result<NS, NumeralStringError> {
+        self.ff.decrypt(&self.deriver.derive(context), x)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aes::Aes256;
+
+    use super::{TweakBuilder, TweakContext, TweakDeriver, TweakedFF1};
+    use crate::ff1::{FlexibleNumeralString, FF1};
+
+    /// Derives the tweak by concatenating the field name and record ID.
+    struct Concat;
+
+    impl TweakDeriver for Concat {
+        fn derive(&self, context: &TweakContext<'_>) -> Vec<u8> {
+            [context.field_name.as_bytes(), context.record_id].concat()
+        }
+    }
+
+    const CONTEXT: TweakContext<'static> = TweakContext {
+        entity_type: "users",
+        field_name: "ssn",
+        record_id: b"42",
+    };
+
+    #[test]
+    fn uses_derived_tweak() {
+        let ff = TweakedFF1::new(FF1::<Aes256>::new(&[0; 32], 10).unwrap(), Concat);
+        let pt = FlexibleNumeralString::from_decimal_str("123456789").unwrap();
+
+        let ct = ff.encrypt_with_context(&CONTEXT, &pt).unwrap();
+        assert_eq!(
+            ct.to_decimal_str(),
+            ff.inner().encrypt(b"ssn42", &pt).unwrap().to_decimal_str(),
+        );
+        assert_eq!(
+            ff.decrypt_with_context(&CONTEXT, &ct)
+                .unwrap()
+                .to_decimal_str(),
+            "123456789",
+        );
+    }
+
+    #[test]
+    fn builder() {
+        let tweak = TweakBuilder::new()
+            .add_str("users")
+            .add_str("ssn")
+            .add_u64(42)
+            .build();
+        assert_eq!(
+            tweak,
+            TweakBuilder::new()
+                .add_str("users")
+                .add_str("ssn")
+                .add_u64(42)
+                .build(),
+        );
+        assert_eq!(
+            &tweak[..14],
+            &[1, 0, 0, 0, 0, 0, 0, 0, 5, b'u', b's', b'e', b'r', b's'],
+        );
+        assert!(TweakBuilder::new().build().is_empty());
+
+        let others = [
+            // Different order.
+            TweakBuilder::new()
+                .add_str("ssn")
+                .add_str("users")
+                .add_u64(42),
+            // Different value.
+            TweakBuilder::new()
+                .add_str("users")
+                .add_str("ssn")
+                .add_u64(43),
+            // Same concatenation of strings.
+            TweakBuilder::new()
+                .add_str("user")
+                .add_str("sssn")
+                .add_u64(42),
+            // Same bytes with different types.
+            TweakBuilder::new()
+                .add_bytes(b"users")
+                .add_str("ssn")
+                .add_u64(42),
+            TweakBuilder::new()
+                .add_str("users")
+                .add_str("ssn")
+                .add_bytes(&42u64.to_be_bytes()),
+            TweakBuilder::new()
+                .add_str("users")
+                .add_str("ssn")
+                .add_u128(42),
+            TweakBuilder::new()
+                .add_str("users")
+                .add_str("ssn")
+                .add_u32(0)
+                .add_u32(42),
+        ];
+        for (i, other) in others.iter().enumerate() {
+            let other = other.clone().build();
+            assert_ne!(other, tweak);
+            for later in &others[i + 1..] {
+                assert_ne!(later.clone().build(), other);
+            }
+        }
+    }
+
+    #[cfg(feature = "uuid")]
+    #[test]
+    fn builder_uuid() {
+        let uuid = uuid::Uuid::from_bytes([7; 16]);
+        assert_ne!(
+            TweakBuilder::new().add_uuid(uuid).build(),
+            TweakBuilder::new().add_bytes(&[7; 16]).build(),
+        );
+        assert_ne!(
+            TweakBuilder::new().add_uuid(uuid).build(),
+            TweakBuilder::new()
+                .add_u128(u128::from_be_bytes([7; 16]))
+                .build(),
+        );
+    }
+
+    #[cfg(feature = "hkdf-sha256")]
+    #[test]
+    fn hkdf() {
+        use super::HkdfTweakDeriver;
+
+        let deriver = HkdfTweakDeriver::new(b"app salt");
+        let tweak = deriver.derive(&CONTEXT);
+        assert_eq!(tweak.len(), HkdfTweakDeriver::TWEAK_LEN);
+        assert_eq!(tweak, deriver.derive(&CONTEXT));
+
+        for other in [
+            TweakContext {
+                field_name: "phone",
+                ..CONTEXT
+            },
+            TweakContext {
+                record_id: b"43",
+                ..CONTEXT
+            },
+            // Same concatenation of fields as `CONTEXT`.
+            TweakContext {
+                entity_type: "user",
+                field_name: "sssn",
+                ..CONTEXT
+            },
+        ] {
+            assert_ne!(deriver.derive(&other), tweak);
+        }
+        assert_ne!(HkdfTweakDeriver::new(b"other salt").derive(&CONTEXT), tweak);
+    }
+}
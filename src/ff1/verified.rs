@@ -0,0 +1,282 @@
+//! Decryption that checks the plaintext is in the expected domain.
+
+use alloc::vec::Vec;
+use cipher::{BlockCipher, BlockEncrypt};
+
+use super::{
+    pan::luhn_check_digit, InspectableOps, NumeralString, NumeralStringError, VerificationError,
+    FF1,
+};
+
+/// A check that a decrypted numeral string is a plausible plaintext.
+///
+/// Any `Fn(&NS) -> bool` closure is a validator.
+pub trait Validator<NS> {
+    /// Returns `true` if `decrypted` is in the expected domain.
+    fn validate(&self, decrypted: &NS) -> bool;
+}
+
+impl<NS, F: Fn(&NS) -> bool> Validator<NS> for F {
+    fn validate(&self, decrypted: &NS) -> bool {
+        self(decrypted)
+    }
+}
+
+/// Returns the numerals of `x`, in big-endian order.
+fn numerals<NS>(x: &NS) -> Vec<u16>
+where
+    NS: NumeralString,
+    NS::Ops: InspectableOps,
+{
+    let (a, b) = x.split();
+    a.to_numerals()
+        .iter()
+        .chain(b.to_numerals())
+        .map(|&n| n.into())
+        .collect()
+}
+
+/// Accepts decimal numeral strings whose last digit is a valid Luhn check digit.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct LuhnValidator;
+
+impl<NS> Validator<NS> for LuhnValidator
+where
+    NS: NumeralString,
+    NS::Ops: InspectableOps,
+{
+    fn validate(&self, decrypted: &NS) -> bool {
+        let digits = numerals(decrypted);
+        match digits.split_last() {
+            Some((&check, body)) if digits.iter().all(|&d| d < 10) => {
+                luhn_check_digit(body) == check
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Accepts numeral strings whose value, read as a big-endian integer in `radix`, is
+/// in `min..=max`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RangeValidator {
+    /// The smallest accepted value.
+    pub min: u64,
+    /// The largest accepted value.
+    pub max: u64,
+    /// The radix of the numeral strings.
+    pub radix: u32,
+}
+
+impl<NS> Validator<NS> for RangeValidator
+where
+    NS: NumeralString,
+    NS::Ops: InspectableOps,
+{
+    fn validate(&self, decrypted: &NS) -> bool {
+        let value = numerals(decrypted).into_iter().try_fold(0u64, |acc, n| {
+            acc.checked_mul(u64::from(self.radix))?
+                .checked_add(u64::from(n))
+        });
+        // A value that does not fit in a `u64` is larger than `max`.
+        value.map_or(false, |v| (self.min..=self.max).contains(&v))
+    }
+}
+
+/// Accepts numeral strings of exactly `expected` numerals.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LengthValidator {
+    /// The accepted length.
+    pub expected: usize,
+}
+
+impl<NS: NumeralString> Validator<NS> for LengthValidator {
+    fn validate(&self, decrypted: &NS) -> bool {
+        decrypted.numeral_count() == self.expected
+    }
+}
+
+/// Accepts numeral strings that start with `prefix`, such as the BIN of a card number.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PrefixValidator {
+    /// The numerals that must begin the numeral string.
+    pub prefix: Vec<u16>,
+}
+
+impl<NS> Validator<NS> for PrefixValidator
+where
+    NS: NumeralString,
+    NS::Ops: InspectableOps,
+{
+    fn validate(&self, decrypted: &NS) -> bool {
+        numerals(decrypted).starts_with(&self.prefix)
+    }
+}
+
+/// An [`FF1`] instance that checks each decrypted numeral string with a
+/// [`Validator`].
+///
+/// FF1 decrypts any ciphertext in the right radix, so a ciphertext that was
+/// encrypted under a different key, radix or tweak decrypts without error to an
+/// unrelated plaintext. `VerifiedDecrypt` rejects plaintexts that are outside the
+/// expected domain, which catches most such mistakes when the domain is a small part
+/// of all numeral strings of its length. It does not provide integrity; see
+/// `AuthenticatedFF1`, with the `authenticated` feature, for that.
+///
+/// ```
+/// use aes::Aes256;
+/// use fpe::ff1::{CreditCardNumeralString, LuhnValidator, VerifiedDecrypt, FF1};
+///
+/// let ff = VerifiedDecrypt::new(FF1::<Aes256>::new(&[0; 32], 10).unwrap(), LuhnValidator);
+/// let pan: CreditCardNumeralString = "4111111111111111".parse().unwrap();
+/// let ct = ff.encrypt(b"pan", &pan).unwrap();
+/// assert_eq!(ff.decrypt(b"pan", &ct).unwrap().to_string(), "4111111111111111");
+/// ```
+pub struct VerifiedDecrypt<CIPH: BlockCipher + BlockEncrypt, V> {
+    ff: FF1<CIPH>,
+    validator: V,
+}
+
+impl<CIPH: BlockCipher + BlockEncrypt, V> VerifiedDecrypt<CIPH, V> {
+    /// Wraps the given FF1 instance, checking each decryption with `validator`.
+    pub fn new(ff: FF1<CIPH>, validator: V) -> Self {
+        VerifiedDecrypt { ff, validator }
+    }
+
+    /// Returns the wrapped FF1 instance.
+    pub fn inner(&self) -> &FF1<CIPH> {
+        &self.ff
+    }
+
+    /// Returns the validator.
+    pub fn validator(&self) -> &V {
+        &self.validator
+    }
+}
+
+impl<CIPH: BlockCipher + BlockEncrypt + Clone, V> VerifiedDecrypt<CIPH, V> {
+    /// Encrypts the given numeral string.
+    ///
+    /// The plaintext is not validated. Returns an error if the numeral string is not in
+    /// the required radix.
+    pub fn encrypt<NS: NumeralString>(
+        &self,
+        tweak: &[u8],
+        x: &NS,
+    ) -> Result<NS, NumeralStringError> {
+        self.ff.encrypt(tweak, x)
+    }
+
+    /// Decrypts the given numeral string, and checks the plaintext with the validator.
+    ///
+    /// Returns [`VerificationError::ValidationFailed`] if the validator rejects the
+    /// plaintext, or an error if the numeral string is not in the required radix.
+    pub fn decrypt<NS>(&self, tweak: &[u8], x: &NS) -> Result<NS, VerificationError>
+    where
+        NS: NumeralString,
+        V: Validator<NS>,
+    {
+        let y = self.ff.decrypt(tweak, x)?;
+        if self.validator.validate(&y) {
+            Ok(y)
+        } else {
+            Err(VerificationError::ValidationFailed)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use aes::Aes256;
+
+    use super::{
+        LengthValidator, LuhnValidator, PrefixValidator, RangeValidator, Validator, VerifiedDecrypt,
+    };
+    use crate::ff1::{FlexibleNumeralString, NumeralStringError, VerificationError, FF1};
+
+    fn ns(s: &str) -> FlexibleNumeralString {
+        FlexibleNumeralString::from_decimal_str(s).unwrap()
+    }
+
+    #[test]
+    fn validators() {
+        assert!(LuhnValidator.validate(&ns("4111111111111111")));
+        assert!(!LuhnValidator.validate(&ns("4111111111111112")));
+        assert!(!LuhnValidator.validate(&FlexibleNumeralString::from(vec![])));
+        assert!(!LuhnValidator.validate(&FlexibleNumeralString::from(vec![10, 8])));
+
+        let range = RangeValidator {
+            min: 100,
+            max: 999_999,
+            radix: 10,
+        };
+        assert!(range.validate(&ns("000100")));
+        assert!(range.validate(&ns("999999")));
+        assert!(!range.validate(&ns("000099")));
+        assert!(!range.validate(&ns("1000000")));
+        assert!(!range.validate(&ns("99999999999999999999999")));
+        let hex = RangeValidator {
+            min: 0,
+            max: 0xff,
+            radix: 16,
+        };
+        assert!(hex.validate(&FlexibleNumeralString::from(vec![0, 15, 15])));
+        assert!(!hex.validate(&FlexibleNumeralString::from(vec![1, 0, 0])));
+
+        let length = LengthValidator { expected: 9 };
+        assert!(length.validate(&ns("123456789")));
+        assert!(!length.validate(&ns("12345678")));
+
+        let prefix = PrefixValidator {
+            prefix: vec![4, 1, 1, 1],
+        };
+        assert!(prefix.validate(&ns("4111222233334444")));
+        assert!(!prefix.validate(&ns("5111222233334444")));
+        assert!(!prefix.validate(&ns("411")));
+
+        let even = |x: &FlexibleNumeralString| x.to_decimal_str().ends_with('0');
+        assert!(even.validate(&ns("1230")));
+        assert!(!even.validate(&ns("1231")));
+    }
+
+    #[test]
+    fn decrypt() {
+        let ff = VerifiedDecrypt::new(
+            FF1::<Aes256>::new(&[0; 32], 10).unwrap(),
+            RangeValidator {
+                min: 0,
+                max: 99_999,
+                radix: 10,
+            },
+        );
+        let pt = ns("0012345678");
+        let ct = ff.encrypt(b"tweak", &pt).unwrap();
+        assert_eq!(
+            ct.to_decimal_str(),
+            ff.inner().encrypt(b"tweak", &pt).unwrap().to_decimal_str()
+        );
+
+        // The plaintext is out of range, as is almost every decryption under the
+        // wrong tweak.
+        assert_eq!(
+            ff.decrypt(b"tweak", &ct).unwrap_err(),
+            VerificationError::ValidationFailed
+        );
+        let pt = ns("0000012345");
+        let ct = ff.encrypt(b"tweak", &pt).unwrap();
+        assert_eq!(
+            ff.decrypt(b"tweak", &ct).unwrap().to_decimal_str(),
+            "0000012345"
+        );
+        assert_eq!(
+            ff.decrypt(b"other", &ct).unwrap_err(),
+            VerificationError::ValidationFailed
+        );
+
+        assert_eq!(
+            ff.decrypt(&[], &FlexibleNumeralString::from(vec![10; 10]))
+                .unwrap_err(),
+            VerificationError::NumeralString(NumeralStringError::InvalidForRadix(10)),
+        );
+    }
+}
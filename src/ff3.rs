@@ -0,0 +1,397 @@
+//! A Rust implementation of the FF3-1 algorithm, specified in
+//! [NIST Special Publication 800-38G Revision 1](https://nvlpubs.nist.gov/nistpubs/SpecialPublications/NIST.SP.800-38Gr1-draft.pdf).
+//!
+//! FF3-1 operates on the same [`NumeralString`] types as [`FF1`](crate::ff1::FF1), so
+//! any numeral string that can be encrypted with FF1 can also be encrypted with
+//! [`FF3_1`].
+
+use cipher::{Block, BlockCipher, BlockEncrypt, Key, KeyInit};
+
+#[cfg(feature = "zeroize")]
+use cipher::zeroize::Zeroize;
+
+use crate::ff1::{InvalidRadix, NumeralString, NumeralStringError, Operations, Radix};
+
+#[cfg(test)]
+mod test_vectors;
+
+/// The length (in bytes) of an FF3-1 tweak.
+pub const TWEAK_LEN: usize = 7;
+
+/// The number of Feistel rounds performed by FF3-1.
+const FEISTEL_ROUNDS: u8 = 8;
+
+/// The length (in bytes) of the numeral encoding within each round's input block.
+const NUM_BYTES: usize = 12;
+
+/// Returns `maxlen = 2 * floor(log_radix(2^96))`.
+fn max_ns_len(radix: u32) -> usize {
+    let radix = u128::from(radix);
+    let mut half_len = 0;
+    let mut domain = 1u128;
+    while domain * radix <= 1 << (8 * NUM_BYTES) {
+        domain *= radix;
+        half_len += 1;
+    }
+    2 * half_len
+}
+
+/// Splits a 56-bit tweak into the 32-bit tweaks `T_L` and `T_R`.
+fn split_tweak(tweak: &[u8; TWEAK_LEN]) -> ([u8; 4], [u8; 4]) {
+    // T_L = T[0..27] || 0^4
+    // T_R = T[32..55] || T[28..31] || 0^4
+    (
+        [tweak[0], tweak[1], tweak[2], tweak[3] & 0xf0],
+        [tweak[4], tweak[5], tweak[6], (tweak[3] & 0x0f) << 4],
+    )
+}
+
+/// A struct for performing FF3-1 encryption and decryption operations.
+///
+/// FF3-1 differs from the original FF3 only in its tweak, which is shortened from 64
+/// to 56 bits to prevent the known-tweak attack on FF3; the key length, the 8 Feistel
+/// rounds and the round function, which encrypts byte-reversed blocks with the
+/// byte-reversed key, are unchanged.
+///
+/// NIST has not published sample vectors for FF3-1, so this implementation is tested
+/// against the FF3 samples, through the shared 64-bit tweak core, and against
+/// third-party FF3-1 vectors.
+#[allow(non_camel_case_types)]
+pub struct FF3_1<CIPH: BlockCipher> {
+    ciph: CIPH,
+    radix: Radix,
+    max_len: usize,
+}
+
+impl<CIPH: BlockCipher + KeyInit> FF3_1<CIPH> {
+    /// Creates a new FF3-1 object for the given key and radix.
+    ///
+    /// Returns an error if the given radix is not in [2..2^16].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the key has the wrong length for the cipher, as `FF1::new` does.
+    pub fn new(key: &[u8], radix: u32) -> Result<Self, InvalidRadix> {
+        let radix = Radix::from_u32(radix)?;
+
+        // FF3-1 uses the block cipher keyed with REVB(K).
+        let mut rev_key = Key::<CIPH>::clone_from_slice(key);
+        rev_key.reverse();
+        let ciph = CIPH::new(&rev_key);
+        #[cfg(feature = "zeroize")]
+        rev_key.as_mut_slice().zeroize();
+
+        Ok(FF3_1 {
+            ciph,
+            max_len: max_ns_len(radix.to_u32()),
+            radix,
+        })
+    }
+}
+
+impl<CIPH: BlockCipher + BlockEncrypt> FF3_1<CIPH> {
+    fn check_ns<NS: NumeralString>(&self, x: &NS) -> Result<(), NumeralStringError> {
+        if !x.is_valid(self.radix.to_u32()) {
+            return Err(NumeralStringError::InvalidForRadix(self.radix.to_u32()));
+        }
+        let ns_len = x.numeral_count();
+        self.radix.check_ns_length(ns_len)?;
+        if ns_len > self.max_len {
+            return Err(NumeralStringError::TooLong {
+                ns_len,
+                max_len: self.max_len,
+            });
+        }
+        Ok(())
+    }
+
+    /// Computes `S = REVB(CIPH_REVB(K)(REVB(P)))` where `P = W ⊕ [i]^4 || [num]^12`.
+    fn round_function(&self, w: &[u8; 4], i: u8, num: &[u8]) -> Block<CIPH> {
+        let mut block = Block::<CIPH>::default();
+        block[..4].copy_from_slice(w);
+        block[3] ^= i;
+        block[4..].copy_from_slice(num);
+
+        block.reverse();
+        self.ciph.encrypt_block(&mut block);
+        block.reverse();
+        block
+    }
+
+    /// Encrypts the given numeral string.
+    ///
+    /// Returns an error if the numeral string is not in the required radix, or is not
+    /// of a length supported by FF3-1 for that radix.
+    pub fn encrypt<NS: NumeralString>(
+        &self,
+        tweak: &[u8; TWEAK_LEN],
+        x: &NS,
+    ) -> Result<NS, NumeralStringError> {
+        let (t_l, t_r) = split_tweak(tweak);
+        self.encrypt_with_split_tweak(&t_l, &t_r, x)
+    }
+
+    fn encrypt_with_split_tweak<NS: NumeralString>(
+        &self,
+        t_l: &[u8; 4],
+        t_r: &[u8; 4],
+        x: &NS,
+    ) -> Result<NS, NumeralStringError> {
+        self.check_ns(x)?;
+        let radix = self.radix.to_u32();
+
+        // FF3-1 interprets both halves of X in little-endian numeral order, with
+        // A = X[1..u] and B = X[u + 1..n] where u = ceil(n / 2). Splitting REV(X) with
+        // `NumeralString::split` gives us REV(B) and REV(A) directly, which we can then
+        // operate on in big-endian order.
+        //
+        // 1. Let u = ceil(n / 2); v = n - u.
+        // 2. Let A = X[1..u]; B = X[u + 1..n].
+        let (mut rev_b, mut rev_a) = x.reversed().split();
+        let u = rev_a.numeral_count();
+        let v = rev_b.numeral_count();
+
+        // 3. Let T_L = T[0..27] || 0^4 and T_R = T[32..55] || T[28..31] || 0^4.
+        for i in 0..FEISTEL_ROUNDS {
+            // 4i. If i is even, let m = u and W = T_R, else let m = v and W = T_L.
+            let (m, w) = if i % 2 == 0 { (u, t_r) } else { (v, t_l) };
+
+            //  4ii. Let P = W ⊕ [i]^4 || [NUM_radix(REV(B))]^12.
+            // 4iii. Let S = REVB(CIPH_REVB(K)(REVB(P))).
+            let s = self.round_function(w, i, rev_b.to_be_bytes(radix, NUM_BYTES).as_ref());
+
+            // 4iv. Let y = NUM(S).
+            //  4v. Let c = (NUM_radix(REV(A)) + y) mod radix^m.
+            // 4vi. Let C = REV(STR^m_radix(c)).
+            let rev_c = rev_a.add_mod_exp(s.into_iter(), radix, m);
+
+            // 4vii. Let A = B.
+            rev_a = rev_b;
+
+            // 4viii. Let B = C.
+            rev_b = rev_c;
+        }
+
+        // 5. Return A || B.
+        Ok(NS::concat(rev_b, rev_a).reversed())
+    }
+
+    /// Decrypts the given numeral string.
+    ///
+    /// Returns an error if the numeral string is not in the required radix, or is not
+    /// of a length supported by FF3-1 for that radix.
+    pub fn decrypt<NS: NumeralString>(
+        &self,
+        tweak: &[u8; TWEAK_LEN],
+        x: &NS,
+    ) -> Result<NS, NumeralStringError> {
+        let (t_l, t_r) = split_tweak(tweak);
+        self.decrypt_with_split_tweak(&t_l, &t_r, x)
+    }
+
+    fn decrypt_with_split_tweak<NS: NumeralString>(
+        &self,
+        t_l: &[u8; 4],
+        t_r: &[u8; 4],
+        x: &NS,
+    ) -> Result<NS, NumeralStringError> {
+        self.check_ns(x)?;
+        let radix = self.radix.to_u32();
+
+        // See `Self::encrypt_with_split_tweak` for why we split REV(X).
+        //
+        // 1. Let u = ceil(n / 2); v = n - u.
+        // 2. Let A = X[1..u]; B = X[u + 1..n].
+        let (mut rev_b, mut rev_a) = x.reversed().split();
+        let u = rev_a.numeral_count();
+        let v = rev_b.numeral_count();
+
+        // 3. Let T_L = T[0..27] || 0^4 and T_R = T[32..55] || T[28..31] || 0^4.
+        for i in (0..FEISTEL_ROUNDS).rev() {
+            // 4i. If i is even, let m = u and W = T_R, else let m = v and W = T_L.
+            let (m, w) = if i % 2 == 0 { (u, t_r) } else { (v, t_l) };
+
+            //  4ii. Let P = W ⊕ [i]^4 || [NUM_radix(REV(A))]^12.
+            // 4iii. Let S = REVB(CIPH_REVB(K)(REVB(P))).
+            let s = self.round_function(w, i, rev_a.to_be_bytes(radix, NUM_BYTES).as_ref());
+
+            // 4iv. Let y = NUM(S).
+            //  4v. Let c = (NUM_radix(REV(B)) - y) mod radix^m.
+            // 4vi. Let C = REV(STR^m_radix(c)).
+            let rev_c = rev_b.sub_mod_exp(s.into_iter(), radix, m);
+
+            // 4vii. Let B = A.
+            rev_b = rev_a;
+
+            // 4viii. Let A = C.
+            rev_a = rev_c;
+        }
+
+        // 5. Return A || B.
+        Ok(NS::concat(rev_b, rev_a).reversed())
+    }
+}
+
+impl<CIPH: BlockCipher + BlockEncrypt> crate::FpeAlgorithm for FF3_1<CIPH> {
+    type Tweak = [u8; TWEAK_LEN];
+
+    fn encrypt<NS: NumeralString>(
+        &self,
+        tweak: &[u8; TWEAK_LEN],
+        x: &NS,
+    ) -> Result<NS, NumeralStringError> {
+        FF3_1::encrypt(self, tweak, x)
+    }
+
+    fn decrypt<NS: NumeralString>(
+        &self,
+        tweak: &[u8; TWEAK_LEN],
+        x: &NS,
+    ) -> Result<NS, NumeralStringError> {
+        FF3_1::decrypt(self, tweak, x)
+    }
+}
+
+#[cfg(all(test, feature = "alloc"))]
+mod tests {
+    use aes::{Aes128, Aes192, Aes256};
+
+    use super::{max_ns_len, split_tweak, test_vectors, FF3_1};
+    use crate::ff1::{BinaryNumeralString, FlexibleNumeralString, NumeralStringError};
+
+    #[test]
+    #[should_panic(expected = "Slice must be the same length as the array")]
+    fn wrong_key_length() {
+        let _ = FF3_1::<Aes256>::new(&[0; 16], 10);
+    }
+
+    #[test]
+    fn max_len() {
+        assert_eq!(max_ns_len(2), 192);
+        assert_eq!(max_ns_len(10), 56);
+        assert_eq!(max_ns_len(26), 40);
+        assert_eq!(max_ns_len(36), 36);
+        assert_eq!(max_ns_len(1 << 16), 12);
+    }
+
+    #[test]
+    fn length_limits() {
+        let ff = FF3_1::<Aes128>::new(&[0; 16], 10).unwrap();
+
+        assert_eq!(
+            ff.encrypt(&[0; 7], &FlexibleNumeralString::from(vec![0; 5]))
+                .unwrap_err(),
+            NumeralStringError::TooShort {
+                ns_len: 5,
+                min_len: 6,
+            },
+        );
+        assert!(ff
+            .encrypt(&[0; 7], &FlexibleNumeralString::from(vec![0; 56]))
+            .is_ok());
+        assert_eq!(
+            ff.encrypt(&[0; 7], &FlexibleNumeralString::from(vec![0; 57]))
+                .unwrap_err(),
+            NumeralStringError::TooLong {
+                ns_len: 57,
+                max_len: 56,
+            },
+        );
+    }
+
+    #[test]
+    fn tweak_split() {
+        let (t_l, t_r) = split_tweak(&[0x12, 0x34, 0x56, 0x78, 0x9a, 0xbc, 0xde]);
+        assert_eq!(t_l, [0x12, 0x34, 0x56, 0x70]);
+        assert_eq!(t_r, [0x9a, 0xbc, 0xde, 0x80]);
+    }
+
+    #[test]
+    fn ff3_test_vectors() {
+        // FF3-1 only changes how the 64-bit FF3 tweak is derived, so we can check the
+        // FF3 sample vectors against the shared core.
+        for tv in test_vectors::ff3() {
+            let t_l = tv.tweak[..4].try_into().unwrap();
+            let t_r = tv.tweak[4..].try_into().unwrap();
+            let (ct, pt) = match tv.key.len() {
+                16 => {
+                    let ff = FF3_1::<Aes128>::new(&tv.key, tv.radix).unwrap();
+                    (
+                        ff.encrypt_with_split_tweak(
+                            &t_l,
+                            &t_r,
+                            &FlexibleNumeralString::from(tv.pt.clone()),
+                        ),
+                        ff.decrypt_with_split_tweak(
+                            &t_l,
+                            &t_r,
+                            &FlexibleNumeralString::from(tv.ct.clone()),
+                        ),
+                    )
+                }
+                24 => {
+                    let ff = FF3_1::<Aes192>::new(&tv.key, tv.radix).unwrap();
+                    (
+                        ff.encrypt_with_split_tweak(
+                            &t_l,
+                            &t_r,
+                            &FlexibleNumeralString::from(tv.pt.clone()),
+                        ),
+                        ff.decrypt_with_split_tweak(
+                            &t_l,
+                            &t_r,
+                            &FlexibleNumeralString::from(tv.ct.clone()),
+                        ),
+                    )
+                }
+                32 => {
+                    let ff = FF3_1::<Aes256>::new(&tv.key, tv.radix).unwrap();
+                    (
+                        ff.encrypt_with_split_tweak(
+                            &t_l,
+                            &t_r,
+                            &FlexibleNumeralString::from(tv.pt.clone()),
+                        ),
+                        ff.decrypt_with_split_tweak(
+                            &t_l,
+                            &t_r,
+                            &FlexibleNumeralString::from(tv.ct.clone()),
+                        ),
+                    )
+                }
+                _ => unreachable!(),
+            };
+            assert_eq!(Vec::from(ct.unwrap()), tv.ct);
+            assert_eq!(Vec::from(pt.unwrap()), tv.pt);
+        }
+    }
+
+    #[test]
+    fn ff3_1_test_vectors() {
+        for tv in test_vectors::ff3_1() {
+            let tweak = tv.tweak[..].try_into().unwrap();
+            let ff = FF3_1::<Aes128>::new(&tv.key, tv.radix).unwrap();
+            let ct = ff.encrypt(&tweak, &FlexibleNumeralString::from(tv.pt.clone()));
+            let pt = ff.decrypt(&tweak, &FlexibleNumeralString::from(tv.ct.clone()));
+            assert_eq!(Vec::from(ct.unwrap()), tv.ct);
+            assert_eq!(Vec::from(pt.unwrap()), tv.pt);
+        }
+    }
+
+    #[test]
+    fn binary_round_trip() {
+        let ff = FF3_1::<Aes256>::new(&[7; 32], 2).unwrap();
+        let tweak = [1, 2, 3, 4, 5, 6, 7];
+
+        // Lengths up to the FF3-1 maximum of 192 bits (24 bytes).
+        for len in 3..=24 {
+            let pt = (0..len as u8).collect::<Vec<_>>();
+            let ct = ff
+                .encrypt(&tweak, &BinaryNumeralString::from_bytes_le(&pt))
+                .unwrap();
+            assert_ne!(ct.to_bytes_le(), pt);
+            let pt2 = ff.decrypt(&tweak, &ct).unwrap();
+            assert_eq!(pt2.to_bytes_le(), pt);
+        }
+    }
+}
@@ -0,0 +1,254 @@
+use core::array;
+
+pub(crate) struct TestVector {
+    pub(crate) key: Vec<u8>,
+    pub(crate) radix: u32,
+    pub(crate) tweak: Vec<u8>,
+    pub(crate) pt: Vec<u16>,
+    pub(crate) ct: Vec<u16>,
+}
+
+/// Sample vectors for the original FF3 algorithm, which uses a 64-bit tweak.
+pub(crate) fn ff3() -> impl Iterator<Item = TestVector> {
+    #[allow(deprecated)]
+    array::IntoIter::new([
+        // From https://csrc.nist.gov/CSRC/media/Projects/Cryptographic-Standards-and-Guidelines/documents/examples/FF3samples.pdf
+        TestVector {
+            // Sample #1
+            key: vec![
+                0xEF, 0x43, 0x59, 0xD8, 0xD5, 0x80, 0xAA, 0x4F, 0x7F, 0x03, 0x6D, 0x6F, 0x04, 0xFC,
+                0x6A, 0x94,
+            ],
+            radix: 10,
+            tweak: vec![0xD8, 0xE7, 0x92, 0x0A, 0xFA, 0x33, 0x0A, 0x73],
+            pt: vec![8, 9, 0, 1, 2, 1, 2, 3, 4, 5, 6, 7, 8, 9, 0, 0, 0, 0],
+            ct: vec![7, 5, 0, 9, 1, 8, 8, 1, 4, 0, 5, 8, 6, 5, 4, 6, 0, 7],
+        },
+        TestVector {
+            // Sample #2
+            key: vec![
+                0xEF, 0x43, 0x59, 0xD8, 0xD5, 0x80, 0xAA, 0x4F, 0x7F, 0x03, 0x6D, 0x6F, 0x04, 0xFC,
+                0x6A, 0x94,
+            ],
+            radix: 10,
+            tweak: vec![0x9A, 0x76, 0x8A, 0x92, 0xF6, 0x0E, 0x12, 0xD8],
+            pt: vec![8, 9, 0, 1, 2, 1, 2, 3, 4, 5, 6, 7, 8, 9, 0, 0, 0, 0],
+            ct: vec![0, 1, 8, 9, 8, 9, 8, 3, 9, 1, 8, 9, 3, 9, 5, 3, 8, 4],
+        },
+        TestVector {
+            // Sample #3
+            key: vec![
+                0xEF, 0x43, 0x59, 0xD8, 0xD5, 0x80, 0xAA, 0x4F, 0x7F, 0x03, 0x6D, 0x6F, 0x04, 0xFC,
+                0x6A, 0x94,
+            ],
+            radix: 10,
+            tweak: vec![0xD8, 0xE7, 0x92, 0x0A, 0xFA, 0x33, 0x0A, 0x73],
+            pt: vec![
+                8, 9, 0, 1, 2, 1, 2, 3, 4, 5, 6, 7, 8, 9, 0, 0, 0, 0, 0, 0, 7, 8, 9, 0, 0, 0, 0, 0,
+                0,
+            ],
+            ct: vec![
+                4, 8, 5, 9, 8, 3, 6, 7, 1, 6, 2, 2, 5, 2, 5, 6, 9, 6, 2, 9, 3, 9, 7, 4, 1, 6, 2, 2,
+                6,
+            ],
+        },
+        TestVector {
+            // Sample #4
+            key: vec![
+                0xEF, 0x43, 0x59, 0xD8, 0xD5, 0x80, 0xAA, 0x4F, 0x7F, 0x03, 0x6D, 0x6F, 0x04, 0xFC,
+                0x6A, 0x94,
+            ],
+            radix: 10,
+            tweak: vec![0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+            pt: vec![
+                8, 9, 0, 1, 2, 1, 2, 3, 4, 5, 6, 7, 8, 9, 0, 0, 0, 0, 0, 0, 7, 8, 9, 0, 0, 0, 0, 0,
+                0,
+            ],
+            ct: vec![
+                3, 4, 6, 9, 5, 2, 2, 4, 8, 2, 1, 7, 3, 4, 5, 3, 5, 1, 2, 2, 6, 1, 3, 7, 0, 1, 4, 3,
+                4,
+            ],
+        },
+        TestVector {
+            // Sample #5
+            key: vec![
+                0xEF, 0x43, 0x59, 0xD8, 0xD5, 0x80, 0xAA, 0x4F, 0x7F, 0x03, 0x6D, 0x6F, 0x04, 0xFC,
+                0x6A, 0x94,
+            ],
+            radix: 26,
+            tweak: vec![0x9A, 0x76, 0x8A, 0x92, 0xF6, 0x0E, 0x12, 0xD8],
+            pt: vec![
+                0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18,
+            ],
+            ct: vec![
+                16, 2, 25, 20, 4, 0, 18, 9, 9, 2, 15, 23, 2, 0, 12, 19, 10, 20, 11,
+            ],
+        },
+        TestVector {
+            // Sample #6
+            key: vec![
+                0xEF, 0x43, 0x59, 0xD8, 0xD5, 0x80, 0xAA, 0x4F, 0x7F, 0x03, 0x6D, 0x6F, 0x04, 0xFC,
+                0x6A, 0x94, 0x2B, 0x7E, 0x15, 0x16, 0x28, 0xAE, 0xD2, 0xA6,
+            ],
+            radix: 10,
+            tweak: vec![0xD8, 0xE7, 0x92, 0x0A, 0xFA, 0x33, 0x0A, 0x73],
+            pt: vec![8, 9, 0, 1, 2, 1, 2, 3, 4, 5, 6, 7, 8, 9, 0, 0, 0, 0],
+            ct: vec![6, 4, 6, 9, 6, 5, 3, 9, 3, 8, 7, 5, 0, 2, 8, 7, 5, 5],
+        },
+        TestVector {
+            // Sample #7
+            key: vec![
+                0xEF, 0x43, 0x59, 0xD8, 0xD5, 0x80, 0xAA, 0x4F, 0x7F, 0x03, 0x6D, 0x6F, 0x04, 0xFC,
+                0x6A, 0x94, 0x2B, 0x7E, 0x15, 0x16, 0x28, 0xAE, 0xD2, 0xA6,
+            ],
+            radix: 10,
+            tweak: vec![0x9A, 0x76, 0x8A, 0x92, 0xF6, 0x0E, 0x12, 0xD8],
+            pt: vec![8, 9, 0, 1, 2, 1, 2, 3, 4, 5, 6, 7, 8, 9, 0, 0, 0, 0],
+            ct: vec![9, 6, 1, 6, 1, 0, 5, 1, 4, 4, 9, 1, 4, 2, 4, 4, 4, 6],
+        },
+        TestVector {
+            // Sample #8
+            key: vec![
+                0xEF, 0x43, 0x59, 0xD8, 0xD5, 0x80, 0xAA, 0x4F, 0x7F, 0x03, 0x6D, 0x6F, 0x04, 0xFC,
+                0x6A, 0x94, 0x2B, 0x7E, 0x15, 0x16, 0x28, 0xAE, 0xD2, 0xA6,
+            ],
+            radix: 10,
+            tweak: vec![0xD8, 0xE7, 0x92, 0x0A, 0xFA, 0x33, 0x0A, 0x73],
+            pt: vec![
+                8, 9, 0, 1, 2, 1, 2, 3, 4, 5, 6, 7, 8, 9, 0, 0, 0, 0, 0, 0, 7, 8, 9, 0, 0, 0, 0, 0,
+                0,
+            ],
+            ct: vec![
+                5, 3, 0, 4, 8, 8, 8, 4, 0, 6, 5, 3, 5, 0, 2, 0, 4, 5, 4, 1, 7, 8, 6, 3, 8, 0, 8, 0,
+                7,
+            ],
+        },
+        TestVector {
+            // Sample #9
+            key: vec![
+                0xEF, 0x43, 0x59, 0xD8, 0xD5, 0x80, 0xAA, 0x4F, 0x7F, 0x03, 0x6D, 0x6F, 0x04, 0xFC,
+                0x6A, 0x94, 0x2B, 0x7E, 0x15, 0x16, 0x28, 0xAE, 0xD2, 0xA6,
+            ],
+            radix: 10,
+            tweak: vec![0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+            pt: vec![
+                8, 9, 0, 1, 2, 1, 2, 3, 4, 5, 6, 7, 8, 9, 0, 0, 0, 0, 0, 0, 7, 8, 9, 0, 0, 0, 0, 0,
+                0,
+            ],
+            ct: vec![
+                9, 8, 0, 8, 3, 8, 0, 2, 6, 7, 8, 8, 2, 0, 3, 8, 9, 2, 9, 5, 0, 4, 1, 4, 8, 3, 5, 1,
+                2,
+            ],
+        },
+        TestVector {
+            // Sample #10
+            key: vec![
+                0xEF, 0x43, 0x59, 0xD8, 0xD5, 0x80, 0xAA, 0x4F, 0x7F, 0x03, 0x6D, 0x6F, 0x04, 0xFC,
+                0x6A, 0x94, 0x2B, 0x7E, 0x15, 0x16, 0x28, 0xAE, 0xD2, 0xA6,
+            ],
+            radix: 26,
+            tweak: vec![0x9A, 0x76, 0x8A, 0x92, 0xF6, 0x0E, 0x12, 0xD8],
+            pt: vec![
+                0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18,
+            ],
+            ct: vec![
+                18, 0, 18, 17, 14, 2, 19, 15, 19, 7, 10, 9, 24, 25, 15, 9, 25, 8, 8,
+            ],
+        },
+        TestVector {
+            // Sample #11
+            key: vec![
+                0xEF, 0x43, 0x59, 0xD8, 0xD5, 0x80, 0xAA, 0x4F, 0x7F, 0x03, 0x6D, 0x6F, 0x04, 0xFC,
+                0x6A, 0x94, 0x2B, 0x7E, 0x15, 0x16, 0x28, 0xAE, 0xD2, 0xA6, 0xAB, 0xF7, 0x15, 0x88,
+                0x09, 0xCF, 0x4F, 0x3C,
+            ],
+            radix: 10,
+            tweak: vec![0xD8, 0xE7, 0x92, 0x0A, 0xFA, 0x33, 0x0A, 0x73],
+            pt: vec![8, 9, 0, 1, 2, 1, 2, 3, 4, 5, 6, 7, 8, 9, 0, 0, 0, 0],
+            ct: vec![9, 2, 2, 0, 1, 1, 2, 0, 5, 5, 6, 2, 7, 7, 7, 4, 9, 5],
+        },
+        TestVector {
+            // Sample #12
+            key: vec![
+                0xEF, 0x43, 0x59, 0xD8, 0xD5, 0x80, 0xAA, 0x4F, 0x7F, 0x03, 0x6D, 0x6F, 0x04, 0xFC,
+                0x6A, 0x94, 0x2B, 0x7E, 0x15, 0x16, 0x28, 0xAE, 0xD2, 0xA6, 0xAB, 0xF7, 0x15, 0x88,
+                0x09, 0xCF, 0x4F, 0x3C,
+            ],
+            radix: 10,
+            tweak: vec![0x9A, 0x76, 0x8A, 0x92, 0xF6, 0x0E, 0x12, 0xD8],
+            pt: vec![8, 9, 0, 1, 2, 1, 2, 3, 4, 5, 6, 7, 8, 9, 0, 0, 0, 0],
+            ct: vec![5, 0, 4, 1, 4, 9, 8, 6, 5, 5, 7, 8, 0, 5, 6, 1, 4, 0],
+        },
+        TestVector {
+            // Sample #13
+            key: vec![
+                0xEF, 0x43, 0x59, 0xD8, 0xD5, 0x80, 0xAA, 0x4F, 0x7F, 0x03, 0x6D, 0x6F, 0x04, 0xFC,
+                0x6A, 0x94, 0x2B, 0x7E, 0x15, 0x16, 0x28, 0xAE, 0xD2, 0xA6, 0xAB, 0xF7, 0x15, 0x88,
+                0x09, 0xCF, 0x4F, 0x3C,
+            ],
+            radix: 10,
+            tweak: vec![0xD8, 0xE7, 0x92, 0x0A, 0xFA, 0x33, 0x0A, 0x73],
+            pt: vec![
+                8, 9, 0, 1, 2, 1, 2, 3, 4, 5, 6, 7, 8, 9, 0, 0, 0, 0, 0, 0, 7, 8, 9, 0, 0, 0, 0, 0,
+                0,
+            ],
+            ct: vec![
+                0, 4, 3, 4, 4, 3, 4, 3, 2, 3, 5, 7, 9, 2, 5, 9, 9, 1, 6, 5, 7, 3, 4, 6, 2, 2, 6, 9,
+                9,
+            ],
+        },
+        TestVector {
+            // Sample #14
+            key: vec![
+                0xEF, 0x43, 0x59, 0xD8, 0xD5, 0x80, 0xAA, 0x4F, 0x7F, 0x03, 0x6D, 0x6F, 0x04, 0xFC,
+                0x6A, 0x94, 0x2B, 0x7E, 0x15, 0x16, 0x28, 0xAE, 0xD2, 0xA6, 0xAB, 0xF7, 0x15, 0x88,
+                0x09, 0xCF, 0x4F, 0x3C,
+            ],
+            radix: 10,
+            tweak: vec![0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00],
+            pt: vec![
+                8, 9, 0, 1, 2, 1, 2, 3, 4, 5, 6, 7, 8, 9, 0, 0, 0, 0, 0, 0, 7, 8, 9, 0, 0, 0, 0, 0,
+                0,
+            ],
+            ct: vec![
+                3, 0, 8, 5, 9, 2, 3, 9, 9, 9, 9, 3, 7, 4, 0, 5, 3, 8, 7, 2, 3, 6, 5, 5, 5, 5, 8, 2,
+                2,
+            ],
+        },
+        TestVector {
+            // Sample #15
+            key: vec![
+                0xEF, 0x43, 0x59, 0xD8, 0xD5, 0x80, 0xAA, 0x4F, 0x7F, 0x03, 0x6D, 0x6F, 0x04, 0xFC,
+                0x6A, 0x94, 0x2B, 0x7E, 0x15, 0x16, 0x28, 0xAE, 0xD2, 0xA6, 0xAB, 0xF7, 0x15, 0x88,
+                0x09, 0xCF, 0x4F, 0x3C,
+            ],
+            radix: 26,
+            tweak: vec![0x9A, 0x76, 0x8A, 0x92, 0xF6, 0x0E, 0x12, 0xD8],
+            pt: vec![
+                0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18,
+            ],
+            ct: vec![
+                25, 0, 11, 2, 16, 24, 13, 15, 19, 10, 9, 11, 17, 11, 7, 11, 20, 3, 8,
+            ],
+        },
+    ])
+}
+
+/// Vectors for FF3-1, which uses a 56-bit tweak.
+pub(crate) fn ff3_1() -> impl Iterator<Item = TestVector> {
+    #[allow(deprecated)]
+    array::IntoIter::new([
+        // From https://github.com/mysto/python-fpe
+        TestVector {
+            // README example
+            key: vec![
+                0x2D, 0xE7, 0x9D, 0x23, 0x2D, 0xF5, 0x58, 0x5D, 0x68, 0xCE, 0x47, 0x88, 0x2A, 0xE2,
+                0x56, 0xD6,
+            ],
+            radix: 10,
+            tweak: vec![0xCB, 0xD0, 0x92, 0x80, 0x97, 0x95, 0x64],
+            pt: vec![3, 9, 9, 2, 5, 2, 0, 2, 4, 0],
+            ct: vec![8, 9, 0, 1, 8, 0, 1, 1, 0, 6],
+        },
+    ])
+}
@@ -0,0 +1,282 @@
+//! A C-compatible interface to FF1 with AES-256.
+//!
+//! Numeral strings are passed as arrays of `uint16_t` numerals, and every fallible
+//! function returns one of the `FPE_*` error codes. A C header for these functions can
+//! be generated with [cbindgen](https://github.com/mozilla/cbindgen), using the
+//! `cbindgen.toml` file in the root of this crate.
+
+use core::slice;
+
+use aes::Aes256;
+use alloc::{boxed::Box, vec::Vec};
+
+use crate::ff1::{FlexibleNumeralString, NumeralStringError, FF1};
+
+/// The operation succeeded.
+pub const FPE_OK: i32 = 0;
+/// The radix was not in the range supported by FF1.
+pub const FPE_ERR_INVALID_RADIX: i32 = 1;
+/// The numeral string was shorter than the minimum length for the radix.
+pub const FPE_ERR_NS_TOO_SHORT: i32 = 2;
+/// The numeral string was longer than the maximum length for FF1.
+pub const FPE_ERR_NS_TOO_LONG: i32 = 3;
+/// The numeral string contained a numeral that is not valid for the radix.
+pub const FPE_ERR_INVALID_NUMERAL: i32 = 4;
+/// The tweak was longer than the maximum length for FF1.
+pub const FPE_ERR_TWEAK_TOO_LONG: i32 = 5;
+/// A required pointer argument was null.
+pub const FPE_ERR_NULL_POINTER: i32 = 6;
+/// An error occurred that has no more specific error code.
+pub const FPE_ERR_OTHER: i32 = 255;
+
+/// An opaque handle to an FF1 instance using AES-256.
+pub struct FF1Handle(FF1<Aes256>);
+
+fn error_code(e: NumeralStringError) -> i32 {
+    match e {
+        NumeralStringError::InvalidForRadix(_) | NumeralStringError::InvalidNumeral { .. } => {
+            FPE_ERR_INVALID_NUMERAL
+        }
+        NumeralStringError::TooLong { .. } => FPE_ERR_NS_TOO_LONG,
+        NumeralStringError::TooShort { .. } => FPE_ERR_NS_TOO_SHORT,
+        NumeralStringError::TweakTooLong { .. } => FPE_ERR_TWEAK_TOO_LONG,
+        NumeralStringError::InvalidRange { .. } => FPE_ERR_OTHER,
+    }
+}
+
+/// Returns the slice at `ptr`, allowing `ptr` to be null if `len` is zero.
+///
+/// # Safety
+///
+/// If `len` is non-zero, `ptr` must be valid for reads of `len` elements.
+unsafe fn slice_or_empty<'a, T>(ptr: *const T, len: usize) -> Option<&'a [T]> {
+    if len == 0 {
+        Some(&[])
+    } else if ptr.is_null() {
+        None
+    } else {
+        Some(slice::from_raw_parts(ptr, len))
+    }
+}
+
+/// Creates an FF1 instance for the given 32-byte AES-256 key and radix.
+///
+/// Returns null if `key` is null, `key_len` is not 32, or `radix` is not in
+/// `[2..2^16]`. The returned handle must be freed with [`fpe_ff1_free`].
+///
+/// # Safety
+///
+/// `key` must be valid for reads of `key_len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn fpe_ff1_new(key: *const u8, key_len: usize, radix: u32) -> *mut FF1Handle {
+    let key = match slice_or_empty(key, key_len) {
+        Some(key) => key,
+        None => return core::ptr::null_mut(),
+    };
+    match FF1::<Aes256>::builder().key(key).radix(radix).build() {
+        Ok(ff) => Box::into_raw(Box::new(FF1Handle(ff))),
+        Err(_) => core::ptr::null_mut(),
+    }
+}
+
+/// Encrypts the `input_len` numerals at `input`, writing the `input_len` numerals of
+/// the ciphertext to `output`.
+///
+/// Returns [`FPE_OK`] on success, or an error code on failure, in which case `output`
+/// is left unchanged.
+///
+/// # Safety
+///
+/// - `handle` must have been returned by [`fpe_ff1_new`] and not yet freed.
+/// - `tweak` must be valid for reads of `tweak_len` bytes.
+/// - `input` must be valid for reads, and `output` for writes, of `input_len`
+///   numerals.
+#[no_mangle]
+pub unsafe extern "C" fn fpe_ff1_encrypt(
+    handle: *mut FF1Handle,
+    tweak: *const u8,
+    tweak_len: usize,
+    input: *const u16,
+    input_len: usize,
+    output: *mut u16,
+) -> i32 {
+    transform(
+        handle,
+        tweak,
+        tweak_len,
+        input,
+        input_len,
+        output,
+        |ff, tweak, x| ff.encrypt(tweak, x),
+    )
+}
+
+/// Decrypts the `input_len` numerals at `input`, writing the `input_len` numerals of
+/// the plaintext to `output`.
+///
+/// Returns [`FPE_OK`] on success, or an error code on failure, in which case `output`
+/// is left unchanged.
+///
+/// # Safety
+///
+/// The same requirements as for [`fpe_ff1_encrypt`] apply.
+#[no_mangle]
+pub unsafe extern "C" fn fpe_ff1_decrypt(
+    handle: *mut FF1Handle,
+    tweak: *const u8,
+    tweak_len: usize,
+    input: *const u16,
+    input_len: usize,
+    output: *mut u16,
+) -> i32 {
+    transform(
+        handle,
+        tweak,
+        tweak_len,
+        input,
+        input_len,
+        output,
+        |ff, tweak, x| ff.decrypt(tweak, x),
+    )
+}
+
+/// Frees an FF1 instance created by [`fpe_ff1_new`].
+///
+/// Does nothing if `handle` is null.
+///
+/// # Safety
+///
+/// `handle` must be null, or have been returned by [`fpe_ff1_new`] and not yet freed.
+#[no_mangle]
+pub unsafe extern "C" fn fpe_ff1_free(handle: *mut FF1Handle) {
+    if !handle.is_null() {
+        drop(Box::from_raw(handle));
+    }
+}
+
+unsafe fn transform(
+    handle: *mut FF1Handle,
+    tweak: *const u8,
+    tweak_len: usize,
+    input: *const u16,
+    input_len: usize,
+    output: *mut u16,
+    f: impl FnOnce(
+        &FF1<Aes256>,
+        &[u8],
+        &FlexibleNumeralString,
+    ) -> Result<FlexibleNumeralString, NumeralStringError>,
+) -> i32 {
+    let (ff, tweak, input) = match (
+        handle.as_ref(),
+        slice_or_empty(tweak, tweak_len),
+        slice_or_empty(input, input_len),
+    ) {
+        (Some(FF1Handle(ff)), Some(tweak), Some(input)) => (ff, tweak, input),
+        _ => return FPE_ERR_NULL_POINTER,
+    };
+    if output.is_null() {
+        return FPE_ERR_NULL_POINTER;
+    }
+
+    match f(ff, tweak, &FlexibleNumeralString::from(input.to_vec())) {
+        Ok(result) => {
+            let result = Vec::from(result);
+            slice::from_raw_parts_mut(output, input_len).copy_from_slice(&result);
+            FPE_OK
+        }
+        Err(e) => error_code(e),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::ptr;
+
+    use super::{
+        fpe_ff1_decrypt, fpe_ff1_encrypt, fpe_ff1_free, fpe_ff1_new, FPE_ERR_INVALID_NUMERAL,
+        FPE_ERR_NS_TOO_SHORT, FPE_ERR_NULL_POINTER, FPE_OK,
+    };
+
+    #[test]
+    fn round_trip() {
+        let key = [0x2b; 32];
+        let tweak = b"tweak";
+        let pt = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9];
+        let mut ct = [0; 10];
+        let mut decrypted = [0; 10];
+
+        unsafe {
+            let handle = fpe_ff1_new(key.as_ptr(), key.len(), 10);
+            assert!(!handle.is_null());
+
+            assert_eq!(
+                fpe_ff1_encrypt(
+                    handle,
+                    tweak.as_ptr(),
+                    tweak.len(),
+                    pt.as_ptr(),
+                    pt.len(),
+                    ct.as_mut_ptr(),
+                ),
+                FPE_OK,
+            );
+            assert_ne!(ct, pt);
+            assert_eq!(
+                fpe_ff1_decrypt(
+                    handle,
+                    tweak.as_ptr(),
+                    tweak.len(),
+                    ct.as_ptr(),
+                    ct.len(),
+                    decrypted.as_mut_ptr(),
+                ),
+                FPE_OK,
+            );
+            assert_eq!(decrypted, pt);
+
+            fpe_ff1_free(handle);
+        }
+    }
+
+    #[test]
+    fn errors() {
+        let key = [0; 32];
+        let mut out = [0; 10];
+
+        unsafe {
+            assert!(fpe_ff1_new(key.as_ptr(), 16, 10).is_null());
+            assert!(fpe_ff1_new(key.as_ptr(), key.len(), 1).is_null());
+            assert!(fpe_ff1_new(ptr::null(), key.len(), 10).is_null());
+
+            let handle = fpe_ff1_new(key.as_ptr(), key.len(), 10);
+            assert_eq!(
+                fpe_ff1_encrypt(handle, ptr::null(), 0, [1; 5].as_ptr(), 5, out.as_mut_ptr()),
+                FPE_ERR_NS_TOO_SHORT,
+            );
+            assert_eq!(
+                fpe_ff1_encrypt(
+                    handle,
+                    ptr::null(),
+                    0,
+                    [10; 6].as_ptr(),
+                    6,
+                    out.as_mut_ptr()
+                ),
+                FPE_ERR_INVALID_NUMERAL,
+            );
+            assert_eq!(
+                fpe_ff1_encrypt(handle, ptr::null(), 1, [1; 6].as_ptr(), 6, out.as_mut_ptr()),
+                FPE_ERR_NULL_POINTER,
+            );
+            assert_eq!(
+                fpe_ff1_encrypt(handle, ptr::null(), 0, [1; 6].as_ptr(), 6, ptr::null_mut()),
+                FPE_ERR_NULL_POINTER,
+            );
+            assert_eq!(out, [0; 10]);
+
+            fpe_ff1_free(handle);
+            fpe_ff1_free(ptr::null_mut());
+        }
+    }
+}
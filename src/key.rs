@@ -0,0 +1,74 @@
+//! Key wrappers that erase key material when dropped.
+
+use cipher::zeroize::{Zeroize, ZeroizeOnDrop};
+#[cfg(feature = "rand")]
+use rand_core::{CryptoRng, RngCore};
+
+/// An `N`-byte secret key that is zeroized when dropped.
+///
+/// The key bytes are not accessible once wrapped; the only way to use a `SecretKey` is
+/// to construct an algorithm instance from it, for example with
+/// [`FF1::new_with_secret_key`](crate::ff1::FF1::new_with_secret_key).
+pub struct SecretKey<const N: usize>([u8; N]);
+
+impl<const N: usize> SecretKey<N> {
+    /// Asserts at compile time that `N` is a valid AES key size.
+    const AES_KEY_SIZE: () = assert!(
+        N == 16 || N == 24 || N == 32,
+        "AES keys are 16, 24 or 32 bytes"
+    );
+
+    /// Wraps the given key bytes.
+    ///
+    /// The caller remains responsible for erasing any other copies of `key`.
+    pub fn new(key: [u8; N]) -> Self {
+        SecretKey(key)
+    }
+
+    /// Copies the given key bytes into a new `SecretKey`.
+    ///
+    /// The caller remains responsible for erasing `bytes`.
+    pub fn from_bytes(bytes: &[u8; N]) -> Self {
+        SecretKey(*bytes)
+    }
+
+    /// Copies the given AES key bytes into a new `SecretKey`.
+    ///
+    /// This is the same as [`SecretKey::from_bytes`], but fails to compile if `N` is
+    /// not 16, 24 or 32:
+    ///
+    /// ```compile_fail
+    /// let key = fpe::SecretKey::from_aes_bytes(&[0; 20]);
+    /// ```
+    pub fn from_aes_bytes(bytes: &[u8; N]) -> Self {
+        #[allow(clippy::let_unit_value)]
+        let () = Self::AES_KEY_SIZE;
+        Self::from_bytes(bytes)
+    }
+
+    /// Generates a random key using the given cryptographically secure RNG.
+    #[cfg(feature = "rand")]
+    pub fn generate<R: CryptoRng + RngCore>(rng: &mut R) -> Self {
+        let mut key = SecretKey([0; N]);
+        rng.fill_bytes(&mut key.0);
+        key
+    }
+
+    pub(crate) fn as_bytes(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl<const N: usize> From<[u8; N]> for SecretKey<N> {
+    fn from(key: [u8; N]) -> Self {
+        SecretKey::new(key)
+    }
+}
+
+impl<const N: usize> Drop for SecretKey<N> {
+    fn drop(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl<const N: usize> ZeroizeOnDrop for SecretKey<N> {}
@@ -1,5 +1,9 @@
 //! Format-preserving encryption algorithms.
 //!
+//! The following algorithms are implemented:
+//! - FF1, in the [`ff1`] module.
+//! - FF3-1, in the [`ff3`] module.
+//!
 //! # Example
 //!
 //! ```
@@ -31,3 +35,82 @@
 extern crate alloc;
 
 pub mod ff1;
+pub mod ff3;
+
+#[cfg(feature = "ffi")]
+pub mod ffi;
+
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+/// Builds a token from a format string, encrypting each field with FF1.
+///
+/// `fpe_token!(ff, tweak, "format")` encrypts each `{name:length:kind}` field of the
+/// format string with the [`ff1::FF1`] instance `ff` and the tweak `tweak` (anything
+/// that implements `AsRef<[u8]>`), and copies the text between fields unchanged. Each
+/// field reads the string variable `name`, which must have exactly `length`
+/// characters of the given kind:
+///
+/// - `decimal`: decimal digits, for an FF1 instance with radix 10.
+/// - `hex`: hexadecimal digits, for an FF1 instance with radix 16. The encrypted field
+///   is lowercase.
+///
+/// Use `{{` and `}}` for literal braces. The format string is parsed at compile time,
+/// and malformed format strings are compile errors. The macro evaluates to a
+/// `Result<String, NumeralStringError>`, which is an error if a field has the wrong
+/// length or characters, or is not in the radix of `ff`.
+///
+/// ```
+/// use aes::Aes256;
+/// use fpe::{ff1::FF1, fpe_token};
+///
+/// let ff = FF1::<Aes256>::new(&[0; 32], 10).unwrap();
+/// let digits = "0123456789";
+/// let token = fpe_token!(ff, b"account", "prefix-{digits:10:decimal}-suffix").unwrap();
+/// assert!(token.starts_with("prefix-") && token.ends_with("-suffix"));
+/// assert_ne!(&token[7..17], digits);
+/// ```
+///
+/// ```compile_fail
+/// # use aes::Aes256;
+/// # use fpe::{ff1::FF1, fpe_token};
+/// # let ff = FF1::<Aes256>::new(&[0; 32], 10).unwrap();
+/// # let digits = "0123456789";
+/// // The field is missing its kind.
+/// let token = fpe_token!(ff, b"", "prefix-{digits:10}");
+/// ```
+#[cfg(feature = "macros")]
+pub use fpe_macros::fpe_token;
+
+#[cfg(feature = "macros")]
+#[doc(hidden)]
+pub mod macro_support;
+
+#[cfg(feature = "zeroize")]
+mod key;
+#[cfg(feature = "zeroize")]
+pub use key::SecretKey;
+
+use ff1::{NumeralString, NumeralStringError};
+
+/// A format-preserving encryption algorithm.
+///
+/// This allows code to be written generically over [`ff1::FF1`] and [`ff3::FF3_1`].
+pub trait FpeAlgorithm {
+    /// The type of tweak used by this algorithm.
+    type Tweak: ?Sized;
+
+    /// Encrypts the given numeral string.
+    fn encrypt<NS: NumeralString>(
+        &self,
+        tweak: &Self::Tweak,
+        x: &NS,
+    ) -> Result<NS, NumeralStringError>;
+
+    /// Decrypts the given numeral string.
+    fn decrypt<NS: NumeralString>(
+        &self,
+        tweak: &Self::Tweak,
+        x: &NS,
+    ) -> Result<NS, NumeralStringError>;
+}
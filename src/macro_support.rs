@@ -0,0 +1,90 @@
+//! Support code for the `fpe_token!` macro. Not part of the public API.
+
+use alloc::string::String;
+
+use cipher::{BlockCipher, BlockEncrypt};
+
+use crate::ff1::{FlexibleNumeralString, HexNumeralString, NumeralStringError, FF1};
+
+/// The kind of a `fpe_token!` field.
+pub enum FieldKind {
+    /// Decimal digits, encrypted with radix 10.
+    Decimal,
+    /// Hexadecimal digits, encrypted with radix 16 and output in lowercase.
+    Hex,
+}
+
+/// Builds the token for a `fpe_token!` invocation, stopping at the first error.
+pub struct TokenBuilder<'a, CIPH: BlockCipher + BlockEncrypt> {
+    ff: &'a FF1<CIPH>,
+    tweak: &'a [u8],
+    token: Result<String, NumeralStringError>,
+}
+
+impl<'a, CIPH: BlockCipher + BlockEncrypt + Clone> TokenBuilder<'a, CIPH> {
+    /// Creates a builder for a token encrypted with the given FF1 instance and tweak.
+    pub fn new(ff: &'a FF1<CIPH>, tweak: &'a [u8]) -> Self {
+        TokenBuilder {
+            ff,
+            tweak,
+            token: Ok(String::new()),
+        }
+    }
+
+    /// Appends literal text.
+    pub fn literal(mut self, text: &str) -> Self {
+        if let Ok(token) = &mut self.token {
+            token.push_str(text);
+        }
+        self
+    }
+
+    /// Appends the encryption of `value`, which must have exactly `len` characters.
+    pub fn field(mut self, value: &str, len: usize, kind: FieldKind) -> Self {
+        if self.token.is_ok() {
+            self.token = self.encrypt(value, len, kind).and_then(|ct| {
+                let mut token = self.token?;
+                token.push_str(&ct);
+                Ok(token)
+            });
+        }
+        self
+    }
+
+    fn encrypt(
+        &self,
+        value: &str,
+        len: usize,
+        kind: FieldKind,
+    ) -> Result<String, NumeralStringError> {
+        let ns_len = value.chars().count();
+        if ns_len < len {
+            return Err(NumeralStringError::TooShort {
+                ns_len,
+                min_len: len,
+            });
+        }
+        if ns_len > len {
+            return Err(NumeralStringError::TooLong {
+                ns_len,
+                max_len: len,
+            });
+        }
+
+        match kind {
+            FieldKind::Decimal => {
+                let pt = FlexibleNumeralString::from_decimal_str(value)?;
+                Ok(self.ff.encrypt(self.tweak, &pt)?.to_decimal_str())
+            }
+            FieldKind::Hex => {
+                let pt = HexNumeralString::from_hex_str(value)?;
+                Ok(self.ff.encrypt(self.tweak, &pt)?.to_hex_str_lower())
+            }
+        }
+    }
+
+    /// Returns the token, or the first error.
+    pub fn finish(self) -> Result<String, NumeralStringError> {
+        self.token
+    }
+}
@@ -0,0 +1,98 @@
+//! JavaScript bindings for FF1 with AES, generated with `wasm-bindgen`.
+//!
+//! Build the bindings with `wasm-pack build --features wasm`; see `examples/wasm` for
+//! an example of using them in a browser.
+
+use aes::{Aes128, Aes192, Aes256};
+use alloc::{string::ToString, vec::Vec};
+use wasm_bindgen::prelude::*;
+
+use crate::ff1::{FlexibleNumeralString, NumeralStringError, FF1};
+
+// The variants only differ by the size of the AES key schedule.
+#[allow(clippy::large_enum_variant)]
+enum Inner {
+    Aes128(FF1<Aes128>),
+    Aes192(FF1<Aes192>),
+    Aes256(FF1<Aes256>),
+}
+
+/// An FF1 instance using AES, exposed to JavaScript as the `FF1Wasm` class.
+#[wasm_bindgen]
+pub struct FF1Wasm(Inner);
+
+#[wasm_bindgen]
+impl FF1Wasm {
+    /// Creates an FF1 instance for the given key and radix.
+    ///
+    /// The key length selects AES-128, AES-192 or AES-256. Throws an error if the key is
+    /// not 16, 24 or 32 bytes long, or if the radix is not in [2..2^16].
+    #[wasm_bindgen(constructor)]
+    pub fn new(key: &[u8], radix: u32) -> Result<FF1Wasm, JsError> {
+        let inner = match key.len() {
+            16 => FF1::<Aes128>::new(key, radix).map(Inner::Aes128),
+            24 => FF1::<Aes192>::new(key, radix).map(Inner::Aes192),
+            32 => FF1::<Aes256>::new(key, radix).map(Inner::Aes256),
+            len => {
+                return Err(JsError::new(&alloc::format!(
+                    "Invalid key length {} for AES",
+                    len,
+                )))
+            }
+        };
+        inner.map(FF1Wasm).map_err(|e| JsError::new(&e.to_string()))
+    }
+
+    /// Encrypts the given numerals.
+    ///
+    /// Throws an error if the numerals are not a valid numeral string for the radix.
+    pub fn encrypt(&self, tweak: &[u8], input: &[u16]) -> Result<Vec<u16>, JsError> {
+        let x = FlexibleNumeralString::from(input.to_vec());
+        to_js(match &self.0 {
+            Inner::Aes128(ff) => ff.encrypt(tweak, &x),
+            Inner::Aes192(ff) => ff.encrypt(tweak, &x),
+            Inner::Aes256(ff) => ff.encrypt(tweak, &x),
+        })
+    }
+
+    /// Decrypts the given numerals.
+    ///
+    /// Throws an error if the numerals are not a valid numeral string for the radix.
+    pub fn decrypt(&self, tweak: &[u8], input: &[u16]) -> Result<Vec<u16>, JsError> {
+        let x = FlexibleNumeralString::from(input.to_vec());
+        to_js(match &self.0 {
+            Inner::Aes128(ff) => ff.decrypt(tweak, &x),
+            Inner::Aes192(ff) => ff.decrypt(tweak, &x),
+            Inner::Aes256(ff) => ff.decrypt(tweak, &x),
+        })
+    }
+}
+
+fn to_js(result: Result<FlexibleNumeralString, NumeralStringError>) -> Result<Vec<u16>, JsError> {
+    result
+        .map(Vec::from)
+        .map_err(|e| JsError::new(&e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(target_arch = "wasm32")]
+    use wasm_bindgen_test::wasm_bindgen_test as test;
+
+    #[cfg(target_arch = "wasm32")]
+    wasm_bindgen_test::wasm_bindgen_test_configure!(run_in_browser);
+
+    use super::FF1Wasm;
+    use crate::ff1::test_vectors;
+
+    // Errors can only be constructed on a wasm32 target, so native test runs only
+    // exercise the success paths.
+    #[test]
+    fn test_vectors() {
+        for tv in test_vectors::get() {
+            let ff = FF1Wasm::new(&tv.key, tv.radix).unwrap();
+            assert_eq!(ff.encrypt(&tv.tweak, &tv.pt).unwrap(), tv.ct);
+            assert_eq!(ff.decrypt(&tv.tweak, &tv.ct).unwrap(), tv.pt);
+        }
+    }
+}
@@ -0,0 +1,117 @@
+//! Runs the ACVP-AES-FF1 test vector files in `tests/acvp`.
+//!
+//! The files use the JSON format of the NIST Automated Cryptographic Validation
+//! Protocol, as published in the `gen-val/json-files/ACVP-AES-FF1-1.0` directory of
+//! <https://github.com/usnistgov/ACVP-Server>. Any `internalProjection.json` file
+//! from there can be copied into `tests/acvp` to be checked by this test.
+#![cfg(feature = "alloc")]
+
+use std::{fs, path::Path};
+
+use aes::{Aes128, Aes192, Aes256};
+use cipher::{BlockCipher, BlockEncrypt, KeyInit};
+use fpe::ff1::{FlexibleNumeralString, FF1};
+use serde_json::Value;
+
+fn parse_hex(s: &str) -> Vec<u8> {
+    (0..s.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+        .collect()
+}
+
+/// Parses a numeral string, given either as an array of numerals or as a string of
+/// characters from the group's alphabet.
+fn parse_numerals(value: &Value, alphabet: Option<&str>) -> Vec<u16> {
+    match value {
+        Value::Array(numerals) => numerals
+            .iter()
+            .map(|n| n.as_u64().unwrap() as u16)
+            .collect(),
+        Value::String(s) => {
+            let alphabet: Vec<char> = alphabet.expect("missing alphabet").chars().collect();
+            s.chars()
+                .map(|c| alphabet.iter().position(|&a| a == c).unwrap() as u16)
+                .collect()
+        }
+        _ => panic!("invalid numeral string {}", value),
+    }
+}
+
+struct TestCase {
+    id: String,
+    radix: u32,
+    key: Vec<u8>,
+    tweak: Vec<u8>,
+    pt: Vec<u16>,
+    ct: Vec<u16>,
+    encrypt: bool,
+    decrypt: bool,
+}
+
+fn check<CIPH: BlockCipher + BlockEncrypt + Clone + KeyInit>(tc: &TestCase) {
+    let ff = FF1::<CIPH>::new(&tc.key, tc.radix).unwrap();
+    if tc.encrypt {
+        let ct = ff
+            .encrypt(&tc.tweak, &FlexibleNumeralString::from(tc.pt.clone()))
+            .unwrap_or_else(|e| panic!("{}: {}", tc.id, e));
+        assert_eq!(Vec::from(ct), tc.ct, "{}: encrypt", tc.id);
+    }
+    if tc.decrypt {
+        let pt = ff
+            .decrypt(&tc.tweak, &FlexibleNumeralString::from(tc.ct.clone()))
+            .unwrap_or_else(|e| panic!("{}: {}", tc.id, e));
+        assert_eq!(Vec::from(pt), tc.pt, "{}: decrypt", tc.id);
+    }
+}
+
+fn run_file(path: &Path) -> usize {
+    let json: Value = serde_json::from_str(&fs::read_to_string(path).unwrap()).unwrap();
+    let mut count = 0;
+
+    for group in json["testGroups"].as_array().unwrap() {
+        let tg_id = &group["tgId"];
+        let radix = group["radix"].as_u64().unwrap() as u32;
+        let alphabet = group["alphabet"].as_str();
+        let (encrypt, decrypt) = match group["direction"].as_str() {
+            Some("encrypt") => (true, false),
+            Some("decrypt") => (false, true),
+            _ => (true, true),
+        };
+
+        for test in group["tests"].as_array().unwrap() {
+            let tc = TestCase {
+                id: format!("{} group {} case {}", path.display(), tg_id, test["tcId"]),
+                radix,
+                key: parse_hex(test["key"].as_str().unwrap()),
+                tweak: parse_hex(test["tweak"].as_str().unwrap_or("")),
+                pt: parse_numerals(&test["pt"], alphabet),
+                ct: parse_numerals(&test["ct"], alphabet),
+                encrypt,
+                decrypt,
+            };
+            match tc.key.len() {
+                16 => check::<Aes128>(&tc),
+                24 => check::<Aes192>(&tc),
+                32 => check::<Aes256>(&tc),
+                len => panic!("{}: invalid key length {}", tc.id, len),
+            }
+            count += 1;
+        }
+    }
+
+    count
+}
+
+#[test]
+fn acvp_vectors() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/acvp");
+    let mut count = 0;
+    for entry in fs::read_dir(dir).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().map_or(false, |ext| ext == "json") {
+            count += run_file(&path);
+        }
+    }
+    assert!(count > 0);
+}
@@ -0,0 +1,26 @@
+//! Checks which numeral strings can be encrypted without invoking the global allocator.
+
+#![cfg(feature = "smallvec")]
+
+mod common;
+
+use aes::Aes256;
+use fpe::ff1::{FlexibleNumeralString, SmallNumeralString, FF1};
+
+use common::count_allocations;
+
+#[test]
+fn small_numeral_string_does_not_allocate() {
+    let ff = FF1::<Aes256>::new(&[0; 32], 10).unwrap();
+    let numerals = [0, 1, 2, 3, 4, 5, 6, 7, 8, 9];
+
+    let small = SmallNumeralString::from_slice(&numerals);
+    let (ct, allocations) = count_allocations(|| ff.encrypt(&[], &small).unwrap());
+    assert_eq!(allocations, 0);
+    let (_, allocations) = count_allocations(|| ff.decrypt(&[], &ct).unwrap());
+    assert_eq!(allocations, 0);
+
+    let flexible = FlexibleNumeralString::from(numerals.to_vec());
+    let (_, allocations) = count_allocations(|| ff.encrypt(&[], &flexible).unwrap());
+    assert!(allocations > 0);
+}
@@ -0,0 +1,102 @@
+//! Checks the `fpe` command-line tool.
+#![cfg(feature = "cli")]
+
+use std::process::{Command, Output};
+
+/// The key from NIST SP 800-38G samples #1 to #3.
+const KEY: &str = "2b7e151628aed2a6abf7158809cf4f3c";
+
+fn fpe(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_fpe"))
+        .args(args)
+        .env_remove("FPE_KEY")
+        .output()
+        .unwrap()
+}
+
+fn stdout(output: Output) -> String {
+    assert!(output.status.success(), "{:?}", output);
+    String::from_utf8(output.stdout)
+        .unwrap()
+        .trim_end()
+        .to_owned()
+}
+
+#[test]
+fn nist_samples() {
+    assert_eq!(
+        stdout(fpe(&[
+            "--key",
+            KEY,
+            "--radix",
+            "10",
+            "--encrypt",
+            "0123456789"
+        ])),
+        "2433477484",
+    );
+    assert_eq!(
+        stdout(fpe(&[
+            "--key",
+            KEY,
+            "--radix",
+            "10",
+            "--tweak",
+            "39383736353433323130",
+            "--decrypt",
+            "6124200773",
+        ])),
+        "0123456789",
+    );
+}
+
+#[test]
+fn key_from_env() {
+    let output = Command::new(env!("CARGO_BIN_EXE_fpe"))
+        .args(["--radix", "10", "--encrypt", "0123456789"])
+        .env("FPE_KEY", KEY)
+        .output()
+        .unwrap();
+    assert_eq!(stdout(output), "2433477484");
+}
+
+#[test]
+fn formats() {
+    for (format, value) in [
+        ("ssn", "123-45-6789"),
+        ("cc", "4111111111111111"),
+        ("uuid", "67e55044-10b1-426f-9247-bb680e5fe0c8"),
+    ] {
+        let ct = stdout(fpe(&["--key", KEY, "--format", format, "--encrypt", value]));
+        assert_eq!(ct.len(), value.len());
+        assert_ne!(ct, value);
+        assert_eq!(
+            stdout(fpe(&["--key", KEY, "--format", format, "--decrypt", &ct])),
+            value,
+        );
+    }
+}
+
+#[test]
+fn errors() {
+    let output = fpe(&["--radix", "10", "--encrypt", "0123456789"]);
+    assert_eq!(output.status.code(), Some(2));
+
+    let output = fpe(&["--key", KEY, "--radix", "10", "--encrypt", "12345"]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("too short"));
+
+    let output = fpe(&[
+        "--key",
+        KEY,
+        "--format",
+        "uuid",
+        "--radix",
+        "10",
+        "--encrypt",
+        "0",
+    ]);
+    assert_eq!(output.status.code(), Some(2));
+}
@@ -0,0 +1,34 @@
+//! Checks that FF1 and FF3-1 never invoke the global allocator when used with
+//! `HeaplessNumeralString`.
+#![cfg(feature = "heapless")]
+
+mod common;
+
+use aes::Aes256;
+use fpe::{ff1::HeaplessNumeralString, ff1::FF1, ff3::FF3_1};
+
+use common::count_allocations;
+
+#[test]
+fn heapless_does_not_allocate() {
+    let key = [0; 32];
+    let pt = HeaplessNumeralString::<32>::from_slice(&[
+        0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18,
+    ])
+    .unwrap();
+
+    let ff1 = FF1::<Aes256>::new(&key, 36).unwrap();
+    let ff3 = FF3_1::<Aes256>::new(&key, 36).unwrap();
+
+    let ((pt1, pt3), allocations) = count_allocations(|| {
+        let ct1 = ff1.encrypt(&[0x37; 11], &pt).unwrap();
+        let pt1 = ff1.decrypt(&[0x37; 11], &ct1).unwrap();
+        let ct3 = ff3.encrypt(&[0x37; 7], &pt).unwrap();
+        let pt3 = ff3.decrypt(&[0x37; 7], &ct3).unwrap();
+        (pt1, pt3)
+    });
+
+    assert_eq!(allocations, 0);
+    assert_eq!(pt1.as_slice(), pt.as_slice());
+    assert_eq!(pt3.as_slice(), pt.as_slice());
+}
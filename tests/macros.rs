@@ -0,0 +1,86 @@
+//! Checks the `fpe_token!` macro.
+#![cfg(all(feature = "macros", feature = "aes"))]
+
+use aes::Aes256;
+use fpe::ff1::{FlexibleNumeralString, HexNumeralString, NumeralStringError, FF1};
+use fpe::fpe_token;
+
+#[test]
+fn encrypts_fields() {
+    let ff = FF1::<Aes256>::new(&[0; 32], 10).unwrap();
+    let ssn = "123456789";
+    let suffix = String::from("004200");
+
+    let token = fpe_token!(ff, b"tweak", "{ssn:9:decimal}_{suffix:6:decimal}").unwrap();
+    let encrypt = |s: &str| {
+        ff.encrypt(
+            b"tweak",
+            &FlexibleNumeralString::from_decimal_str(s).unwrap(),
+        )
+        .unwrap()
+        .to_decimal_str()
+    };
+    assert_eq!(token, format!("{}_{}", encrypt(ssn), encrypt(&suffix)));
+
+    // The FF1 instance and tweak may be any expressions.
+    let tweak = vec![1, 2, 3];
+    assert_eq!(
+        fpe_token!(&ff, &tweak[..], "id-{ssn:9:decimal}",).unwrap(),
+        format!(
+            "id-{}",
+            ff.encrypt(
+                &tweak,
+                &FlexibleNumeralString::from_decimal_str(ssn).unwrap()
+            )
+            .unwrap()
+            .to_decimal_str()
+        ),
+    );
+
+    // Braces can be escaped, and format strings may be raw.
+    assert_eq!(fpe_token!(ff, b"", r"{{}}\n").unwrap(), "{}\\n");
+    assert_eq!(fpe_token!(ff, b"", "\x41{{\u{42}\t").unwrap(), "A{B\t");
+}
+
+#[test]
+fn hex_fields() {
+    let ff = FF1::<Aes256>::new(&[0; 32], 16).unwrap();
+    let id = "DEADBEEF";
+    let token = fpe_token!(ff, b"", "#{id:8:hex}").unwrap();
+    assert_eq!(
+        token,
+        format!(
+            "#{}",
+            ff.encrypt(&[], &HexNumeralString::from_hex_str(id).unwrap())
+                .unwrap()
+                .to_hex_str_lower()
+        ),
+    );
+}
+
+#[test]
+fn errors() {
+    let ff = FF1::<Aes256>::new(&[0; 32], 10).unwrap();
+    let short = "12345678";
+    let long = "1234567890";
+    let hex = "abcdefab";
+    assert_eq!(
+        fpe_token!(ff, b"", "{short:9:decimal}").unwrap_err(),
+        NumeralStringError::TooShort {
+            ns_len: 8,
+            min_len: 9,
+        },
+    );
+    assert_eq!(
+        fpe_token!(ff, b"", "{long:9:decimal}").unwrap_err(),
+        NumeralStringError::TooLong {
+            ns_len: 10,
+            max_len: 9,
+        },
+    );
+    assert!(fpe_token!(ff, b"", "{hex:8:decimal}").is_err());
+    assert_eq!(
+        fpe_token!(ff, b"", "{hex:8:hex}").unwrap_err(),
+        NumeralStringError::InvalidForRadix(10),
+    );
+}
@@ -0,0 +1,87 @@
+//! Checks the async FF1 interface under the tokio test runtime.
+#![cfg(feature = "tokio")]
+
+use aes::Aes256;
+use fpe::ff1::{FlexibleNumeralString, NumeralStringError, SharedFF1, FF1};
+
+fn shared() -> SharedFF1<Aes256> {
+    SharedFF1::new(FF1::<Aes256>::new(&[0; 32], 10).unwrap())
+}
+
+#[tokio::test]
+async fn matches_blocking() {
+    let ff = shared();
+    let pt = "0123456789";
+
+    let ct = ff
+        .encrypt_async(
+            b"tweak",
+            FlexibleNumeralString::from_decimal_str(pt).unwrap(),
+        )
+        .await
+        .unwrap();
+    let expected = ff
+        .encrypt(
+            b"tweak",
+            &FlexibleNumeralString::from_decimal_str(pt).unwrap(),
+        )
+        .unwrap();
+    assert_eq!(ct.to_decimal_str(), expected.to_decimal_str());
+
+    let decrypted = ff.decrypt_async(b"tweak", ct).await.unwrap();
+    assert_eq!(decrypted.to_decimal_str(), pt);
+}
+
+#[tokio::test]
+async fn shared_between_tasks() {
+    let ff = shared();
+    let tasks: Vec<_> = (0..8u32)
+        .map(|i| {
+            let ff = ff.clone();
+            tokio::spawn(async move {
+                let pt = format!("{:010}", i);
+                let ct = ff
+                    .encrypt_async(&[], FlexibleNumeralString::from_decimal_str(&pt).unwrap())
+                    .await
+                    .unwrap();
+                let decrypted = ff.decrypt_async(&[], ct).await.unwrap();
+                assert_eq!(decrypted.to_decimal_str(), pt);
+            })
+        })
+        .collect();
+    for task in tasks {
+        task.await.unwrap();
+    }
+}
+
+#[tokio::test]
+async fn batch() {
+    let ff = shared();
+    let inputs = ["0123456789", "9876543210", "000000"];
+    let to_ns = || {
+        inputs
+            .iter()
+            .map(|s| FlexibleNumeralString::from_decimal_str(s).unwrap())
+            .collect::<Vec<_>>()
+    };
+
+    let cts = ff.encrypt_batch_async(b"tweak", to_ns()).await.unwrap();
+    for (ct, x) in cts.iter().zip(to_ns()) {
+        assert_eq!(
+            ct.to_decimal_str(),
+            ff.encrypt(b"tweak", &x).unwrap().to_decimal_str()
+        );
+    }
+
+    let pts = ff.decrypt_batch_async(b"tweak", cts).await.unwrap();
+    assert_eq!(
+        pts.iter().map(|x| x.to_decimal_str()).collect::<Vec<_>>(),
+        inputs,
+    );
+
+    let err = ff
+        .encrypt_batch_async(b"tweak", vec![FlexibleNumeralString::from(vec![10; 6])])
+        .await
+        .err();
+    assert_eq!(err, Some(NumeralStringError::InvalidForRadix(10)));
+}